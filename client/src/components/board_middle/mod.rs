@@ -1,9 +1,10 @@
 use std::collections::HashMap;
 
+use ludo_core::models::color::Color;
 use stylist::{css, yew::styled_component};
 use yew::prelude::*;
 
-use crate::{context::game_context::context::GameContext, models::color::Color};
+use crate::context::game_context::context::GameContext;
 
 #[styled_component(BoardMiddle)]
 pub fn board_middle() -> Html {
@@ -1,12 +1,11 @@
 use futures::SinkExt;
+use ludo_core::models::{color::Color, messages::ClientMessage};
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 
 use crate::components::icon::Icon;
 use crate::components::pawn::Pawn;
 use crate::context::game_context::context::GameContext;
-use crate::models::color::Color;
-use crate::models::messages::ClientMessage;
 use crate::utils::{resolve_bg_color_class, resolve_text_color_class};
 
 #[derive(PartialEq, Clone)]
@@ -40,6 +39,9 @@ pub fn field(props: &FieldProps) -> Html {
     game,
     sender,
     player_color,
+    spectating,
+    available_moves,
+    selected_die,
     ..
   } = use_context::<GameContext>().expect("context not found");
 
@@ -51,22 +53,38 @@ pub fn field(props: &FieldProps) -> Html {
 
   let text_class = resolve_text_color_class(&color);
 
-  let pawn_color = if variant == FieldVariant::Home {
+  // (color, count) of the pieces standing on the field, squares of the main board can hold a stack
+  let pawns: Vec<(Color, usize)> = if variant == FieldVariant::Home {
     // TODO: add home pawns
     game
       .players
       .iter()
       .find(|player| player.color == color)
       .and_then(|player| player.home.get(position).unwrap_or(&None).clone())
+      .map(|color| vec![(color, 1)])
+      .unwrap_or_default()
   } else {
-    game.fields.get(position)
+    game.fields.get(position).pawns().collect()
   };
 
+  let is_safe_square = variant == FieldVariant::Main && game.is_safe_square(position);
+
   let click_color = match variant {
     FieldVariant::Home => Some(color),
     FieldVariant::Main => None,
   };
 
+  // the piece of the field can be moved with the selected die, the options go to the whole room
+  let movable = !spectating
+    && game.current_player == player_color
+    && available_moves
+      .iter()
+      .find(|die| Some(die.dice_value) == selected_die)
+      .is_some_and(|die| match variant {
+        FieldVariant::Home => color == player_color && die.positions_in_home.contains(&position),
+        FieldVariant::Main => die.positions_on_board.contains(&position),
+      });
+
   let onclick = {
     Callback::from(move |_| {
       let click_color = click_color.clone();
@@ -75,7 +93,11 @@ pub fn field(props: &FieldProps) -> Html {
         if let Some(mut sender) = sender.clone() {
           sender
             .0
-            .send(ClientMessage::MoveFigure(position, click_color))
+            .send(ClientMessage::MoveFigure(
+              position,
+              click_color,
+              selected_die,
+            ))
             .await
             .ok();
         }
@@ -84,17 +106,33 @@ pub fn field(props: &FieldProps) -> Html {
   };
 
   let content = {
-    if let Some(color) = pawn_color {
-      html! { <Pawn color={color.clone()} onclick={(color == player_color).then(|| onclick)} /> }
+    if !pawns.is_empty() {
+      let pawns = pawns.into_iter().map(|(pawn_color, count)| {
+        html! {
+          <div class="relative">
+            <Pawn color={pawn_color.clone()} onclick={(!spectating && pawn_color == player_color).then(|| onclick.clone())} />
+            {
+              if count > 1 {
+                html! { <span class="absolute -top-1 -right-1 text-xs font-bold text-neutral-700">{count}</span> }
+              } else {
+                html! {}
+              }
+            }
+          </div>
+        }
+      });
+      html! { <div class="flex">{ for pawns }</div> }
     } else if raw_position == 6 {
       html! { <Icon class={classes!(arrow_class)} /> }
+    } else if is_safe_square {
+      html! { <Icon class={classes!("fas", "fa-star", "text-neutral-400")} /> }
     } else {
       html! {}
     }
   };
 
   html! {
-    <div class={classes!(String::from("relative border border-neutral-300 shadow-inner grid place-items-center"), bg_class, text_class)}>
+    <div class={classes!(String::from("relative border border-neutral-300 shadow-inner grid place-items-center"), bg_class, text_class, movable.then(|| "ring-2 ring-inset ring-primary-400"))}>
       {content}
     </div>
   }
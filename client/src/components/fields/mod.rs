@@ -2,11 +2,10 @@ use std::array::IntoIter;
 use std::collections::HashMap;
 use std::iter::FromIterator;
 
+use ludo_core::models::color::Color;
 use yew::prelude::*;
 
 use crate::components::field::{Field, FieldVariant};
-use crate::models::color::Color;
-use crate::types::FieldType;
 
 #[derive(PartialEq, Clone)]
 pub enum FieldsPosition {
@@ -20,7 +19,6 @@ pub enum FieldsPosition {
 pub struct FieldsProps {
   pub position: FieldsPosition,
   pub color: Color,
-  pub fields: Vec<FieldType>,
   #[prop_or(0)]
   pub offset: usize,
 }
@@ -30,7 +28,6 @@ pub fn fields(props: &FieldsProps) -> Html {
   let FieldsProps {
     color,
     position,
-    fields,
     offset,
   } = props.clone();
 
@@ -134,7 +131,8 @@ pub fn fields(props: &FieldsProps) -> Html {
     FieldsPosition::Right | FieldsPosition::Left => "grid-cols-6 grid-rows-3".into(),
   };
 
-  let fields = fields.iter().enumerate().map(|(index, _)| {
+  // each arm of the board is a 3 x 6 grid of fields
+  let fields = (0..18).map(|index| {
     if let Some((raw_position, variant)) = map.get(&index) {
       let position = match variant {
         FieldVariant::Home => *raw_position,
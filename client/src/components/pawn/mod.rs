@@ -1,8 +1,9 @@
+use ludo_core::models::color::Color;
 use stylist::css;
 use yew::prelude::*;
 
 use crate::components::icon::Icon;
-use crate::{models::color::Color, utils::resolve_text_color_class};
+use crate::utils::resolve_text_color_class;
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct PawnProps {
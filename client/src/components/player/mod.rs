@@ -3,11 +3,10 @@ use crate::components::card::Card;
 use crate::components::die::Die;
 use crate::components::icon::Icon;
 use crate::context::game_context::context::GameContext;
-use crate::models::color::Color;
-use crate::models::messages::ClientMessage;
 use crate::utils::color_to_name::color_to_name;
 use futures::SinkExt;
-use gloo::timers::callback::Timeout;
+use gloo::timers::callback::{Interval, Timeout};
+use ludo_core::models::{color::Color, messages::ClientMessage};
 use stylist::css;
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
@@ -34,10 +33,44 @@ pub fn player(props: &PlayerProps) -> Html {
     game,
     dice_info,
     sender,
+    turn_time_left,
+    spectating,
+    available_moves,
+    selected_die,
+    select_die,
     ..
   } = use_context::<GameContext>().expect("context not found");
   let die_info = dice_info.get(&color).expect("die info not set for player");
   let is_rolling = use_state(|| false);
+  let seconds_passed = use_state(|| 0_u64);
+
+  // counts the seconds since the server last sent how much time the turn has left
+  {
+    let seconds_passed = seconds_passed.clone();
+    use_effect_with_deps(
+      move |_| {
+        seconds_passed.set(0);
+        let mut count = 0;
+        let interval = Interval::new(1000, move || {
+          count += 1;
+          seconds_passed.set(count);
+        });
+
+        move || drop(interval)
+      },
+      turn_time_left,
+    );
+  }
+
+  let countdown = match turn_time_left {
+    Some(time_left) if game.current_player == color => {
+      let seconds = (time_left / 1000).saturating_sub(*seconds_passed);
+      html! {
+        <span class="text-neutral-600 font-semibold">{format!("{}:{:02}", seconds / 60, seconds % 60)}</span>
+      }
+    }
+    _ => html! {},
+  };
 
   let icon = html! { <Icon class="fas fa-sync-alt" /> };
 
@@ -75,12 +108,42 @@ pub fn player(props: &PlayerProps) -> Html {
 
   let disabled = !die_info.can_roll || *is_rolling || player_color != color;
 
-  let button = if player_color == color {
+  // spectators only watch the dice
+  let button = if player_color == color && !spectating {
     html! { <Button {icon} onclick={roll} { disabled }>{"Roll the die"}</Button> }
   } else {
     html! {}
   };
 
+  // with more than one die left the player picks the one the next click moves with
+  let dice_picker = if player_color == color
+    && game.current_player == color
+    && !spectating
+    && available_moves.len() > 1
+  {
+    let dice = available_moves.iter().map(|die| {
+      let dice_value = die.dice_value;
+      let onclick = {
+        let select_die = select_die.clone();
+        Callback::from(move |_| select_die.emit(dice_value))
+      };
+      let selected = selected_die == Some(dice_value);
+      html! {
+        <button {onclick} disabled={die.is_empty()} class={classes!(String::from("rounded px-3 py-1 font-semibold border"), selected.then(|| "bg-primary-600 text-white"), die.is_empty().then(|| "opacity-50 cursor-default"))}>
+          { dice_value }
+        </button>
+      }
+    });
+    html! {
+      <div class="flex items-center gap-2 px-4 pb-4">
+        <span class="text-neutral-600">{"Move with"}</span>
+        { for dice }
+      </div>
+    }
+  } else {
+    html! {}
+  };
+
   // {
   //   let die_number = die_number.clone();
   //   let throws = throws.clone();
@@ -137,8 +200,10 @@ pub fn player(props: &PlayerProps) -> Html {
       <Card class={classes!("border-2", border_anim, (game.current_player == color).then(|| "border-primary-400"))}>
         <div class={classes!(String::from("flex justify-between items-center p-4"))}>
           <span class="text-lg font-semibold text-neutral-700">{ color_to_name(&game,color) }</span>
+          { countdown }
           <Die is_rolling={*is_rolling} number={die_info.number} />
         </div>
+        { dice_picker }
       // TODO: add timeline
       </Card>
       {
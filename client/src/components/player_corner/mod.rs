@@ -1,11 +1,10 @@
 use futures::SinkExt;
+use ludo_core::models::{color::Color, messages::ClientMessage};
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 
 use crate::components::pawn::Pawn;
 use crate::context::game_context::context::GameContext;
-use crate::models::color::Color;
-use crate::models::messages::ClientMessage;
 use crate::utils::{clamp, resolve_bg_color_class};
 
 #[derive(Properties, PartialEq, Clone)]
@@ -20,6 +19,9 @@ pub fn player_corner(props: &PlayerCornerProps) -> Html {
     game,
     player_color,
     sender,
+    spectating,
+    available_moves,
+    selected_die,
     ..
   } = use_context::<GameContext>().expect("context not found");
 
@@ -37,22 +39,34 @@ pub fn player_corner(props: &PlayerCornerProps) -> Html {
       let sender = sender.clone();
       spawn_local(async move {
         if let Some(mut sender) = sender.clone() {
-          sender.0.send(ClientMessage::PromotePiece).await.ok();
+          sender
+            .0
+            .send(ClientMessage::PromotePiece(selected_die))
+            .await
+            .ok();
         };
       });
     })
   };
 
-  let onclick = if color == player_color {
+  let onclick = if color == player_color && !spectating {
     Some(onclick)
   } else {
     None
   };
 
+  // a new piece can enter with the selected die
+  let can_promote = color == player_color
+    && game.current_player == color
+    && !spectating
+    && available_moves
+      .iter()
+      .any(|die| Some(die.dice_value) == selected_die && die.can_promote);
+
   let color_class = resolve_bg_color_class(&color);
   html! {
     <div class={classes!(String::from("h-full w-full grid place-items-center drop-shadow-lg"), color_class)}>
-      <div class="w-1/2 h-1/2 rounded bg-neutral-100 grid grid-cols-2 grid-rows-2 p-4 drop-shadow-lg border border-neutral-300" >
+      <div class={classes!(String::from("w-1/2 h-1/2 rounded bg-neutral-100 grid grid-cols-2 grid-rows-2 p-4 drop-shadow-lg border border-neutral-300"), can_promote.then(|| "ring-2 ring-primary-400"))}>
         {
           (0..pawn_count).map(|index| html! {
             <div class="grid place-items-center h-full w-full">
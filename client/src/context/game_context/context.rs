@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 
-use crate::models::{
+use crate::models::die_info::DieInfo;
+use futures::channel::mpsc::Sender;
+use ludo_core::models::{
   color::Color,
-  die_info::DieInfo,
+  die_moves::DieMoves,
   game::Game,
   messages::{ClientMessage, ServerMessage},
 };
-use futures::channel::mpsc::Sender;
 use yew::Callback;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -14,11 +15,20 @@ pub struct GameContext {
   pub game: Game,
   pub player_color: Color,
   pub player_count: u32,
+  // watching without a seat, player_color means nothing then
+  pub spectating: bool,
+  pub spectator_count: usize,
   pub subscribe: Callback<Callback<ServerMessage>>,
   pub sender: Option<MsgSender>,
   // pub players: HashMap<Color, Player>,
   pub current_player: Color,
   pub dice_info: HashMap<Color, DieInfo>,
+  pub undo_request: Option<Color>,
+  pub available_moves: Vec<DieMoves>,
+  // the die the next move is made with, the first one with a move unless the player picked another
+  pub selected_die: Option<usize>,
+  pub select_die: Callback<usize>,
+  pub turn_time_left: Option<u64>,
 }
 
 #[derive(Clone, Debug)]
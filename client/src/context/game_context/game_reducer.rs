@@ -1,14 +1,32 @@
+use ludo_core::models::{
+  color::Color,
+  die_moves::DieMoves,
+  game::Game,
+  messages::{SequencedMessage, ServerMessage},
+};
 use std::{collections::HashMap, rc::Rc};
 use yew::Reducible;
 
-use crate::models::{color::Color, die_info::DieInfo, game::Game, messages::ServerMessage};
+use crate::models::die_info::DieInfo;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct GameState {
   pub game: Game,
   pub player_color: Color,
   pub player_count: u32,
+  pub spectating: bool,
+  pub spectator_count: usize,
   pub dice_info: HashMap<Color, DieInfo>,
+  // the player who asked to take back their last move, until it is answered
+  pub undo_request: Option<Color>,
+  // what every die that is still to be played can move, empty unless the player is moving
+  pub available_moves: Vec<DieMoves>,
+  // milliseconds the current player had left when the last update came
+  pub turn_time_left: Option<u64>,
+  // number of the last message of the room, None until the first whole game came
+  pub sequence: Option<u64>,
+  // a message of the room was missed, diffs wait for a snapshot of the game
+  pub out_of_sync: bool,
 }
 
 impl Default for GameState {
@@ -17,6 +35,8 @@ impl Default for GameState {
       game: Game::new(),
       player_color: Color::Green,
       player_count: 0,
+      spectating: false,
+      spectator_count: 0,
       dice_info: [
         (Color::Green, DieInfo::new()),
         (Color::Yellow, DieInfo::new()),
@@ -26,16 +46,52 @@ impl Default for GameState {
       .iter()
       .cloned()
       .collect::<HashMap<_, _>>(),
+      undo_request: None,
+      available_moves: vec![],
+      turn_time_left: None,
+      sequence: None,
+      out_of_sync: false,
     }
   }
 }
 
 impl Reducible for GameState {
-  type Action = ServerMessage;
+  type Action = SequencedMessage;
 
   fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
-    match action {
-      ServerMessage::DiceValue(number, can_roll) => {
+    let SequencedMessage { sequence, message } = action;
+    let missed = matches!(self.sequence, Some(last) if sequence > last + 1);
+    let whole_game = matches!(
+      message,
+      ServerMessage::GameUpdate(..)
+        | ServerMessage::GameStarted(_)
+        | ServerMessage::ConnectResponse(..)
+        | ServerMessage::Spectating(_)
+    );
+    let out_of_sync = !whole_game && (self.out_of_sync || missed);
+
+    // a diff only fits the game it was made from
+    let state = match message {
+      ServerMessage::GameDiff(..) if out_of_sync => self,
+      message => self.apply(message),
+    };
+    let sequence = match whole_game {
+      true => Some(sequence),
+      false => state.sequence.map(|last| last.max(sequence)),
+    };
+    Self {
+      sequence,
+      out_of_sync,
+      ..(*state).clone()
+    }
+    .into()
+  }
+}
+
+impl GameState {
+  fn apply(self: Rc<Self>, message: ServerMessage) -> Rc<Self> {
+    match message {
+      ServerMessage::DiceValue(number, can_roll, turn_time_left) => {
         let mut new_dice_info = self.dice_info.clone();
         new_dice_info.insert(
           self.game.current_player.clone(),
@@ -44,11 +100,12 @@ impl Reducible for GameState {
 
         Self {
           dice_info: new_dice_info,
+          turn_time_left,
           ..(*self).clone()
         }
         .into()
       }
-      ServerMessage::GameUpdate(game) | ServerMessage::GameStarted(game) => {
+      ServerMessage::GameUpdate(game, turn_time_left) => {
         let current_player = game.current_player.clone();
         let dice_info = self.dice_info.iter().map(|(color, die_info)| {
           let can_roll = current_player == *color;
@@ -59,13 +116,39 @@ impl Reducible for GameState {
           (color.clone(), die_info)
         });
 
+        // the options only stay while the same dice are left to play
+        let available_moves = match game.current_player == self.game.current_player
+          && game.pending_moves == self.game.pending_moves
+        {
+          true => self.available_moves.clone(),
+          false => vec![],
+        };
+
         Self {
           game,
           dice_info: dice_info.collect(),
+          available_moves,
+          turn_time_left,
           ..(*self).clone()
         }
         .into()
       }
+      ServerMessage::AvailablePositions(available_moves) => Self {
+        available_moves,
+        ..(*self).clone()
+      }
+      .into(),
+      ServerMessage::GameStarted(game) => {
+        // the first turn has the whole time of the rules
+        let turn_time_left = (game.rules.turn_time > 0 && !game.is_current_player_ai())
+          .then(|| game.rules.turn_time * 1000);
+        self.apply(ServerMessage::GameUpdate(game, turn_time_left))
+      }
+      ServerMessage::GameDiff(diff, turn_time_left) => {
+        let mut game = self.game.clone();
+        game.apply_diff(&diff);
+        self.apply(ServerMessage::GameUpdate(game, turn_time_left))
+      }
       ServerMessage::ConnectResponse(game, player_color) => {
         let current_player = game.current_player.clone();
         let dice_info = self.dice_info.iter().map(|(color, die_info)| {
@@ -85,6 +168,30 @@ impl Reducible for GameState {
         }
         .into()
       }
+      ServerMessage::Spectating(game) => {
+        let player_color = self.player_color;
+        let state = self.apply(ServerMessage::ConnectResponse(game, player_color));
+        Self {
+          spectating: true,
+          ..(*state).clone()
+        }
+        .into()
+      }
+      ServerMessage::SpectatorCountChange(spectator_count) => Self {
+        spectator_count,
+        ..(*self).clone()
+      }
+      .into(),
+      ServerMessage::UndoRequested(color) => Self {
+        undo_request: Some(color),
+        ..(*self).clone()
+      }
+      .into(),
+      ServerMessage::UndoResult(_) => Self {
+        undo_request: None,
+        ..(*self).clone()
+      }
+      .into(),
       _ => self,
     }
   }
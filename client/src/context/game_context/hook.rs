@@ -1,42 +1,112 @@
+use super::context::{GameContext, MsgSender};
+use super::game_reducer::GameState;
+use crate::context::snackbar::context::{SnackbarContext, SnackbarOptions, SnackbarVariant};
+use crate::utils::color_to_name::color_to_name;
+use crate::utils::get_host::WS_STRING;
 use futures::channel::mpsc;
-use futures::{SinkExt, StreamExt};
+use futures::{future, SinkExt, StreamExt};
 use gloo::console::log;
 use gloo::storage::{SessionStorage, Storage};
+use gloo::timers::future::TimeoutFuture;
+use ludo_core::models::messages::{ClientMessage, SequencedMessage, ServerMessage};
 use reqwasm::websocket::futures::WebSocket;
-use reqwasm::websocket::Message;
+use reqwasm::websocket::{Message, WebSocketError};
+use std::{cell::Cell, rc::Rc};
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
-use super::context::{GameContext, MsgSender};
-use super::game_reducer::GameState;
-use crate::context::snackbar::context::{SnackbarContext, SnackbarOptions, SnackbarVariant};
-use crate::models::messages::{ClientMessage, ServerMessage};
-use crate::utils::get_host::WS_STRING;
+
+// milliseconds before the first attempt to reconnect, every failed attempt doubles it
+const RECONNECT_DELAY: u32 = 500;
+const MAX_RECONNECT_DELAY: u32 = 10_000;
+// the server closes with it when the player was kicked or their token was refused,
+// connecting again would be refused the same way
+const POLICY_CLOSE_CODE: u16 = 1008;
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct UseGameProps {
   pub game_id: String,
+  // watch the game without a seat, no token is needed then
+  pub spectate: bool,
 }
 
 pub fn use_game(props: &UseGameProps) -> GameContext {
   let SnackbarContext { open } = use_context::<SnackbarContext>().expect("context not found");
+  let snackbar = open.clone();
   let game_state = use_reducer(GameState::default);
   let sender = use_state(|| None);
   let game_id = props.game_id.clone();
+  let spectate = props.spectate;
   let event_handler = use_state::<Option<Callback<ServerMessage>>, _>(|| None);
 
   let handle_message = {
     let game_state = game_state.clone();
-    Callback::from(move |message: ServerMessage| {
-      if let ServerMessage::Error(message) = message.clone() {
-        open.emit(SnackbarOptions {
+    Callback::from(move |sequenced: SequencedMessage| {
+      match sequenced.message.clone() {
+        ServerMessage::Error(message) => open.emit(SnackbarOptions {
           message,
           variant: SnackbarVariant::Error,
-        });
+        }),
+        ServerMessage::UndoResult(accepted) => open.emit(match accepted {
+          true => SnackbarOptions {
+            message: "The last move was taken back".into(),
+            variant: SnackbarVariant::Success,
+          },
+          false => SnackbarOptions {
+            message: "The takeback was declined".into(),
+            variant: SnackbarVariant::Warning,
+          },
+        }),
+        ServerMessage::PlayerLeft(color) => open.emit(SnackbarOptions {
+          message: format!(
+            "{} left, a bot plays until they are back",
+            color_to_name(&game_state.game, color)
+          ),
+          variant: SnackbarVariant::Warning,
+        }),
+        ServerMessage::PlayerReturned(color) => open.emit(SnackbarOptions {
+          message: format!("{} is back", color_to_name(&game_state.game, color)),
+          variant: SnackbarVariant::Success,
+        }),
+        _ => {}
       }
-      game_state.dispatch(message);
+      game_state.dispatch(sequenced);
     })
   };
 
+  // new options pick the first die that can move something
+  let selected_die = use_state(|| None);
+  {
+    let selected_die = selected_die.clone();
+    use_effect_with_deps(
+      move |available_moves| {
+        let first = available_moves.iter().find(|die| !die.is_empty());
+        selected_die.set(first.map(|die| die.dice_value));
+        || ()
+      },
+      game_state.available_moves.clone(),
+    );
+  }
+  let select_die = {
+    let selected_die = selected_die.clone();
+    Callback::from(move |dice_value| selected_die.set(Some(dice_value)))
+  };
+
+  // a message of the room was missed, the whole game is asked for again
+  {
+    let sender = sender.clone();
+    use_effect_with_deps(
+      move |out_of_sync| {
+        if let (true, Some(mut sender)) = (*out_of_sync, (*sender).clone()) {
+          spawn_local(async move {
+            sender.0.send(ClientMessage::RequestSnapshot).await.ok();
+          });
+        }
+        || ()
+      },
+      game_state.out_of_sync,
+    );
+  }
+
   {
     let sender = sender.clone();
     let event_handler = event_handler.clone();
@@ -44,49 +114,114 @@ pub fn use_game(props: &UseGameProps) -> GameContext {
       move |callback| {
         let callback = (**callback).clone();
         let handle_message = handle_message.clone();
-        let player_id: String = SessionStorage::get("player_id").unwrap();
-        log!(format!(
-          "{}/games/websocket/{}/{}",
-          WS_STRING,game_id, player_id
-        ));
+        let (url, token) = match spectate {
+          true => (
+            format!("{}/games/websocket/{}/spectate", WS_STRING, game_id),
+            None,
+          ),
+          false => (
+            format!("{}/games/websocket/{}", WS_STRING, game_id),
+            SessionStorage::get::<String>("token").ok(),
+          ),
+        };
+        log!(url.clone());
 
-        let ws = WebSocket::open(
-          format!(
-            "{}/games/websocket/{}/{}",
-            WS_STRING,game_id, player_id
-          )
-          .as_str(),
-        )
-        .unwrap();
-
-        let (mut write, mut read) = ws.split();
         let (tx, mut rx) = mpsc::channel::<ClientMessage>(1000);
         sender.set(Some(MsgSender(tx)));
 
-        spawn_local(async move {
-          // TODO: handle errors as well
-          while let Some(Ok(Message::Text(text))) = read.next().await {
-            log!(text.clone());
-            if let Ok(message) = serde_json::from_str::<ServerMessage>(text.as_str()) {
-              handle_message.emit(message.clone());
-              if let Some(callback) = callback.clone() {
-                callback.emit(message.clone());
-              };
-            } else {
-              log!("Parsing of message failed:\n", text);
+        // the page was left, the connection isn't opened again
+        let closed = Rc::new(Cell::new(false));
+        {
+          let closed = closed.clone();
+          spawn_local(async move {
+            // attempts to connect since the last message from the server, each one waits twice as long
+            let mut attempts: u32 = 0;
+            // the lobby tells a kicked player themselves, the connection just isn't opened again
+            let kicked = Cell::new(false);
+            while !closed.get() {
+              // the reason the server gave for closing the connection for good
+              let mut refused = None;
+              if let Ok(ws) = WebSocket::open(url.as_str()) {
+                let (mut write, mut read) = ws.split();
+                // the browser can't send the token in a header, it goes first on every connection
+                if let Some(token) = token.clone() {
+                  let authenticate =
+                    serde_json::to_string(&ClientMessage::Authenticate(token)).unwrap();
+                  write.send(Message::Text(authenticate)).await.ok();
+                }
+                let reading = async {
+                  loop {
+                    let text = match read.next().await {
+                      Some(Ok(Message::Text(text))) => text,
+                      Some(Ok(Message::Bytes(_))) => continue,
+                      Some(Err(WebSocketError::ConnectionClose(event)))
+                        if event.code == POLICY_CLOSE_CODE =>
+                      {
+                        return Some(event.reason)
+                      }
+                      _ => return None,
+                    };
+                    log!(text.clone());
+                    if attempts > 0 {
+                      attempts = 0;
+                      snackbar.emit(SnackbarOptions {
+                        message: "Reconnected".into(),
+                        variant: SnackbarVariant::Success,
+                      });
+                    }
+                    if let Ok(sequenced) = serde_json::from_str::<SequencedMessage>(text.as_str()) {
+                      if sequenced.message == ServerMessage::Kicked {
+                        kicked.set(true);
+                      }
+                      handle_message.emit(sequenced.clone());
+                      if let Some(callback) = callback.clone() {
+                        callback.emit(sequenced.message);
+                      };
+                    } else {
+                      log!("Parsing of message failed:\n", text);
+                    }
+                  }
+                };
+                // messages sent while disconnected wait in the channel for the next connection
+                let writing = async {
+                  while let Some(msg) = rx.next().await {
+                    let json = serde_json::to_string(&msg).unwrap();
+                    if write.send(Message::Text(json)).await.is_err() {
+                      break;
+                    }
+                  }
+                };
+                if let future::Either::Left((reason, _)) =
+                  future::select(Box::pin(reading), Box::pin(writing)).await
+                {
+                  refused = reason;
+                }
+              }
+              log!("WEBSOCKET CLOSED");
+              if closed.get() || kicked.get() {
+                break;
+              }
+              if let Some(reason) = refused {
+                snackbar.emit(SnackbarOptions {
+                  message: format!("Disconnected: {}", reason),
+                  variant: SnackbarVariant::Error,
+                });
+                break;
+              }
+              if attempts == 0 {
+                snackbar.emit(SnackbarOptions {
+                  message: "Connection lost, reconnecting...".into(),
+                  variant: SnackbarVariant::Warning,
+                });
+              }
+              let delay = (RECONNECT_DELAY << attempts.min(5)).min(MAX_RECONNECT_DELAY);
+              attempts += 1;
+              TimeoutFuture::new(delay).await;
             }
-          }
-          log!("WEBSOCKET CLOSED")
-        });
-
-        spawn_local(async move {
-          while let Some(msg) = rx.next().await {
-            let json = serde_json::to_string(&msg).unwrap();
-            write.send(Message::Text(json)).await.unwrap();
-          }
-        });
+          });
+        }
 
-        Box::new(|| {})
+        Box::new(move || closed.set(true))
       },
       event_handler,
     );
@@ -102,9 +237,16 @@ pub fn use_game(props: &UseGameProps) -> GameContext {
     game: game_state.game.clone(),
     player_color: game_state.player_color.clone(),
     player_count: 0,
+    spectating: game_state.spectating,
+    spectator_count: game_state.spectator_count,
     subscribe,
     sender: (*sender).clone(),
     current_player: game_state.game.current_player.clone(),
     dice_info: game_state.dice_info.clone(),
+    undo_request: game_state.undo_request,
+    available_moves: game_state.available_moves.clone(),
+    selected_die: *selected_die,
+    select_die,
+    turn_time_left: game_state.turn_time_left,
   }
 }
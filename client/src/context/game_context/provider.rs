@@ -10,12 +10,15 @@ pub struct GameProviderProps {
   #[prop_or_default]
   pub children: Children,
   pub game_id: String,
+  #[prop_or_default]
+  pub spectate: bool,
 }
 
 #[function_component(GameProvider)]
 pub fn game_provider(props: &GameProviderProps) -> Html {
   let context = use_game(&UseGameProps {
     game_id: props.game_id.clone(),
+    spectate: props.spectate,
   });
 
   html! {
@@ -6,7 +6,6 @@ mod context;
 mod models;
 mod pages;
 mod routes;
-mod types;
 mod utils;
 
 use routes::Routes;
@@ -1,5 +1 @@
-pub mod color;
 pub mod die_info;
-pub mod game;
-pub mod messages;
-pub mod player;
@@ -1,23 +1,110 @@
+use futures::SinkExt;
+use ludo_core::models::{color::Color, messages::ClientMessage};
+use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
+use yew_router::prelude::*;
 
 use crate::components::board::Board;
+use crate::components::button::Button;
+use crate::components::card::Card;
+use crate::components::icon::Icon;
 use crate::components::player::{Player, PlayerButtonPosition};
-use crate::models::color::Color;
+use crate::context::game_context::context::GameContext;
+use crate::routes::MainRoute;
+use crate::utils::color_to_name::color_to_name;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct GameProps {
+  pub id: String,
+}
 
 #[function_component(Game)]
-pub fn game() -> Html {
-  html! {
-    <div class="py-4 flex">
-      <div class="flex flex-col justify-between item-center p-4 max-w-md flex-grow">
-        <Player position={PlayerButtonPosition::Bottom} color={Color::Yellow} />
-        <Player position={PlayerButtonPosition::Top} color={Color::Green} />
+pub fn game(props: &GameProps) -> Html {
+  let GameProps { id } = props.clone();
+  let GameContext {
+    game,
+    sender,
+    undo_request,
+    spectating,
+    spectator_count,
+    ..
+  } = use_context::<GameContext>().expect("context not found");
+  let history = use_history().unwrap();
+
+  let send = move |message: ClientMessage| {
+    let sender = sender.clone();
+    Callback::from(move |_| {
+      let sender = sender.clone();
+      let message = message.clone();
+      spawn_local(async move {
+        if let Some(mut sender) = sender {
+          sender.0.send(message).await.ok();
+        };
+      });
+    })
+  };
+
+  // the other players have to agree before a move is taken back
+  let undo = match (game.winner, undo_request) {
+    _ if spectating => html! {},
+    (Some(_), _) => html! {},
+    (None, Some(color)) => html! {
+      <div class="flex justify-center pb-4">
+        <Card class="p-4 flex items-center gap-4">
+          <p class="font-semibold text-neutral-600">
+            {format!("{} wants to take back their last move", color_to_name(&game, color))}
+          </p>
+          <Button onclick={send(ClientMessage::AnswerUndo(true))}>{"Allow"}</Button>
+          <Button class="bg-neutral-400" onclick={send(ClientMessage::AnswerUndo(false))}>{"Decline"}</Button>
+        </Card>
       </div>
-      <div class="flex-grow">
-        <Board />
+    },
+    (None, None) => html! {
+      <div class="flex justify-center pb-4">
+        <Button onclick={send(ClientMessage::RequestUndo)} icon={html! { <Icon class="fas fa-undo"/> }}>{"Take back my move"}</Button>
+      </div>
+    },
+  };
+
+  // once the game is over it can be watched again from the start
+  let replay = match game.winner {
+    Some(_) => {
+      let onclick = Callback::from(move |_| history.push(MainRoute::GameReplay { id: id.clone() }));
+      html! {
+        <div class="flex justify-center pb-4">
+          <Button {onclick} icon={html! { <Icon class="fas fa-history"/> }}>{"Watch the replay"}</Button>
+        </div>
+      }
+    }
+    None => html! {},
+  };
+
+  let watching = match (spectating, game.started) {
+    (true, false) => String::from("You are watching, the game hasn't started yet"),
+    (true, true) => String::from("You are watching"),
+    (false, _) => format!("{} watching", spectator_count),
+  };
+
+  html! {
+    <div class="py-4">
+      <div class="flex justify-center items-center gap-2 pb-4 font-semibold text-neutral-600">
+        <Icon class="fas fa-eye"/>
+        <span>{ watching }</span>
       </div>
-      <div class="flex flex-col justify-between item-center p-4 max-w-md flex-grow">
-        <Player position={PlayerButtonPosition::Bottom} color={Color::Blue} />
-        <Player position={PlayerButtonPosition::Top} color={Color::Red} />
+      { replay }
+      { undo }
+      <div class="flex">
+        <div class="flex flex-col justify-between item-center p-4 max-w-md flex-grow">
+          <Player position={PlayerButtonPosition::Bottom} color={Color::Yellow} />
+          <Player position={PlayerButtonPosition::Top} color={Color::Green} />
+        </div>
+        <div class="flex-grow">
+          <Board />
+        </div>
+        <div class="flex flex-col justify-between item-center p-4 max-w-md flex-grow">
+          <Player position={PlayerButtonPosition::Bottom} color={Color::Blue} />
+          <Player position={PlayerButtonPosition::Top} color={Color::Red} />
+        </div>
       </div>
     </div>
   }
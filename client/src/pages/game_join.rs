@@ -1,4 +1,5 @@
 use gloo::storage::{SessionStorage, Storage};
+use ludo_core::models::lobby::JoinResponse;
 use reqwasm::http::Request;
 use serde::Deserialize;
 use wasm_bindgen::JsCast;
@@ -23,6 +24,7 @@ pub struct GameJoinProps {
 #[derive(serde::Serialize, Deserialize)]
 pub struct JoinGameBody {
   pub name: String,
+  pub password: Option<String>,
 }
 
 #[function_component(GameJoin)]
@@ -31,6 +33,9 @@ pub fn game_join(props: &GameJoinProps) -> Html {
   let SnackbarContext { open } = use_context().expect("context not found");
   let history = use_history().unwrap();
   let nickname = use_state::<String, _>(|| "".into());
+  let password = use_state::<String, _>(|| "".into());
+  // the password field is only shown once the server asked for one
+  let needs_password = use_state(|| false);
 
   // use_effect_with_deps(|_| {
 
@@ -48,16 +53,36 @@ pub fn game_join(props: &GameJoinProps) -> Html {
     })
   };
 
+  let on_password = {
+    let password = password.clone();
+    Callback::from(move |event: InputEvent| {
+      let target = event.target();
+      let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+
+      if let Some(element) = input {
+        password.set(element.value());
+      }
+    })
+  };
+
   let onclick = {
     let nickname = nickname.clone();
+    let password = password.clone();
+    let needs_password = needs_password.clone();
     Callback::from(move |_| {
       let open = open.clone();
       let nickname = nickname.clone();
+      let password = password.clone();
+      let needs_password = needs_password.clone();
       let id = id.clone();
       let history = history.clone();
       spawn_local(async move {
         let body = JoinGameBody {
           name: (*nickname).clone(),
+          password: match password.is_empty() {
+            true => None,
+            false => Some((*password).clone()),
+          },
         };
         let body_json = serde_json::to_string(&body).unwrap();
         let res = Request::put(format!("{}/games/{}",HTTP_STRING, id).as_str())
@@ -77,6 +102,15 @@ pub fn game_join(props: &GameJoinProps) -> Html {
           }
         };
 
+        if resp.status() == 403 {
+          needs_password.set(true);
+          open.emit(SnackbarOptions {
+            message: "The game needs the right password".into(),
+            variant: SnackbarVariant::Error,
+          });
+          return;
+        };
+
         if !resp.ok() {
           open.emit(SnackbarOptions {
             message: "Couldn't join game".into(),
@@ -85,8 +119,8 @@ pub fn game_join(props: &GameJoinProps) -> Html {
           return;
         };
 
-        let player_id = match resp.text().await {
-          Ok(player_id) => player_id,
+        let joined = match resp.json::<JoinResponse>().await {
+          Ok(joined) => joined,
           Err(e) => {
             open.emit(SnackbarOptions {
               message: e.to_string(),
@@ -96,9 +130,10 @@ pub fn game_join(props: &GameJoinProps) -> Html {
           }
         };
 
-        if SessionStorage::set("player_id", player_id).is_err() {
+        // only the token opens the websocket, the player id is no secret
+        if SessionStorage::set("token", joined.token).is_err() {
           open.emit(SnackbarOptions {
-            message: "Failed to set your player id".into(),
+            message: "Failed to store your session".into(),
             variant: SnackbarVariant::Error,
           });
           return;
@@ -121,6 +156,13 @@ pub fn game_join(props: &GameJoinProps) -> Html {
       <Card class="w-full px-8 py-14 lg:px-40">
         <p class="text-3xl mb-8">{"Enter your nickname bellow"}</p>
         <TextInput value={(*nickname).clone()} label={"Nickname:".to_string()} {onchange} />
+        {
+          if *needs_password {
+            html! { <TextInput value={(*password).clone()} label={"Password:".to_string()} onchange={on_password} /> }
+          } else {
+            html! {}
+          }
+        }
         <Button class="w-full mt-8" {onclick} disabled={(*nickname).is_empty()}>{"Join the game!"}</Button>
       </Card>
     </Content>
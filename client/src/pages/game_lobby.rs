@@ -1,6 +1,11 @@
 // use futures::channel::oneshot::channel;
 use futures::SinkExt;
 use gloo::timers::callback::Interval;
+use ludo_core::models::{
+  color::Color,
+  lobby::LobbyPlayer,
+  messages::{ClientMessage, ServerMessage},
+};
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 use yew_router::prelude::*;
@@ -12,9 +17,10 @@ use crate::components::copy_bar::CopyBar;
 use crate::components::icon::Icon;
 use crate::components::outlined_item::OutlinedItem;
 use crate::context::game_context::context::GameContext;
-use crate::models::messages::{ClientMessage, ServerMessage};
+use crate::context::snackbar::context::{SnackbarContext, SnackbarOptions, SnackbarVariant};
 use crate::routes::{GameRoute, MainRoute};
 use crate::utils::get_host::JOIN_STRING;
+use crate::utils::resolve_bg_color_class;
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct GameLobbyProps {
@@ -25,24 +31,37 @@ pub struct GameLobbyProps {
 pub fn game_lobby(props: &GameLobbyProps) -> Html {
   let GameLobbyProps { id } = props.clone();
   let GameContext {
-    subscribe, sender, ..
+    game,
+    spectator_count,
+    subscribe,
+    sender,
+    player_color,
+    ..
   } = use_context::<GameContext>().expect("provider is not a parent");
+  let SnackbarContext { open } = use_context::<SnackbarContext>().expect("context not found");
   let history = use_history().unwrap();
-  let player_count = use_state(|| 0);
+  let roster = use_state(Vec::<LobbyPlayer>::new);
   let seconds = use_state(|| 0);
 
   {
     let id = id.clone();
     let history = history.clone();
-    let player_count = player_count.clone();
+    let roster = roster.clone();
     use_effect_with_deps(
       move |_: &[u32; 0]| {
         subscribe.emit(Callback::from(
           move |message: ServerMessage| match message {
-            ServerMessage::PlayerCountChange(count) => player_count.set(count),
+            ServerMessage::LobbyState(players) => roster.set(players),
             ServerMessage::GameStarted(_) => {
               history.push(GameRoute::Game { id: id.clone() });
             }
+            ServerMessage::Kicked => {
+              open.emit(SnackbarOptions {
+                message: "The host removed you from the lobby".into(),
+                variant: SnackbarVariant::Error,
+              });
+              history.push(MainRoute::Home);
+            }
             _ => {}
           },
         ));
@@ -53,17 +72,48 @@ pub fn game_lobby(props: &GameLobbyProps) -> Html {
     );
   }
 
+  let send = Callback::from(move |message: ClientMessage| {
+    let sender = sender.clone();
+    spawn_local(async move {
+      if let Some(mut sender) = sender.clone() {
+        sender.0.send(message).await.ok();
+      };
+    });
+  });
+
+  let me = roster.iter().find(|player| player.color == player_color);
+  let is_host = me.is_some_and(|player| player.is_host);
+  let is_ready = me.is_some_and(|player| player.ready);
+  let everyone_ready = roster.iter().all(|player| player.is_bot || player.ready);
+
   let on_start = {
-    Callback::from(move |_| {
-      let sender = sender.clone();
-      spawn_local(async move {
-        if let Some(mut sender) = sender.clone() {
-          sender.0.send(ClientMessage::StartGame).await.ok();
-        };
-      });
-    })
+    let send = send.clone();
+    Callback::from(move |_| send.emit(ClientMessage::StartGame))
+  };
+
+  let on_ready = {
+    let send = send.clone();
+    Callback::from(move |_| send.emit(ClientMessage::Ready(!is_ready)))
+  };
+
+  // a dot of the color, clicking it sends the message
+  let color_button = |color: Color, disabled: bool, message: ClientMessage| {
+    let send = send.clone();
+    let onclick = Callback::from(move |_| send.emit(message.clone()));
+    html! {
+      <button {onclick} {disabled} title={color.to_string()}
+        class={classes!("w-6", "h-6", "rounded-full", resolve_bg_color_class(&color), disabled.then(|| "opacity-25"))} />
+    }
   };
 
+  let color_picker = Color::ordered()
+    .into_iter()
+    .map(|color| {
+      let taken = roster.iter().any(|player| player.color == color);
+      color_button(color, taken, ClientMessage::ChooseColor(color))
+    })
+    .collect::<Html>();
+
   let redirect_to_home = Callback::from(move |_| {
     history.push(MainRoute::Home);
   });
@@ -83,18 +133,103 @@ pub fn game_lobby(props: &GameLobbyProps) -> Html {
     <Icon class="fas fa-play"/>
   };
 
+  let ready_icon = html! {
+    <Icon class={if is_ready { "fas fa-times" } else { "fas fa-check" }}/>
+  };
+  let ready_text = if is_ready { "Not ready" } else { "Ready" };
+  let waiting_text = match (is_host, everyone_ready) {
+    (true, true) => "Everyone is ready",
+    (true, false) => "Waiting for every player to get ready",
+    (false, _) => "Waiting for the host to start the game",
+  };
+
   let leave_icon = html! {
     <Icon class="fas fa-sign-out-alt"/>
   };
 
+  let connected = roster.iter().filter(|player| player.connected).count();
   let players_item = html! {
-    {format!("{} / 4", *player_count)}
+    {format!("{} / 4", connected)}
+  };
+
+  let spectators_item = html! {
+    {spectator_count}
   };
 
+  let roster_items = roster
+    .iter()
+    .map(|player| {
+      let status = vec![
+        player.is_host.then(|| "host"),
+        Some(if player.ready { "ready" } else { "not ready" }),
+        (!player.connected).then(|| "offline"),
+      ]
+      .into_iter()
+      .flatten()
+      .collect::<Vec<_>>()
+      .join(", ");
+      let name = match player.color == player_color {
+        true => format!("{} (you)", player.name),
+        false => player.name.clone(),
+      };
+      // the host moves the others to another color or sends them out of the lobby
+      let host_controls = match is_host && player.color != player_color {
+        true => {
+          let from = player.color;
+          let seats = Color::ordered()
+            .into_iter()
+            .filter(|color| *color != from)
+            .map(|color| color_button(color, false, ClientMessage::SeatPlayer(from, color)))
+            .collect::<Html>();
+          let on_kick = {
+            let send = send.clone();
+            Callback::from(move |_| send.emit(ClientMessage::KickPlayer(from)))
+          };
+          html! {
+            <div class="flex items-center gap-2">
+              { seats }
+              <button onclick={on_kick} title="Kick"><Icon class="fas fa-user-times text-red-700" /></button>
+            </div>
+          }
+        }
+        false => html! {},
+      };
+      let item = html! {
+        <div class="flex items-center gap-4">
+          { host_controls }
+          <span class={classes!("text-neutral-600", (!player.connected).then(|| "opacity-50"))}>{ status }</span>
+        </div>
+      };
+      let label = html! {
+        <div class="flex items-center gap-3">
+          <div class={classes!("w-4", "h-4", "rounded-full", resolve_bg_color_class(&player.color))} />
+          <span>{ name }</span>
+        </div>
+      };
+      html! {
+        <div class="w-full font-semibold rounded border border-neutral-300 p-3 flex justify-between items-center">
+          { label }
+          { item }
+        </div>
+      }
+    })
+    .collect::<Html>();
+
   let time_item = html! {
     {format!("{} seconds", *seconds)}
   };
 
+  // private games aren't listed, friends join them with the code
+  let join_code = match &game.join_code {
+    Some(code) => html! {
+      <div class="flex items-center gap-3 mt-3">
+        <span class="font-semibold text-neutral-600">{"Join code"}</span>
+        <span class="text-2xl font-bold tracking-widest">{ code }</span>
+      </div>
+    },
+    None => html! {},
+  };
+
   html! {
     <Content class="py-12 h-full">
       <div class="flex items-center mb-6 w-full">
@@ -107,6 +242,9 @@ pub fn game_lobby(props: &GameLobbyProps) -> Html {
       <Card class="w-full px-8 py-14 lg:px-40">
         <p class="text-xl text-neutral-600 font-bold">{"Share the link with your friends and start the game"}</p>
         <CopyBar content={ format!("{}/games/{}/join",JOIN_STRING, id) } />
+        { join_code }
+        <p class="font-semibold text-neutral-600 mt-3">{"Friends who only want to watch can use"}</p>
+        <CopyBar content={ format!("{}/games/{}/watch",JOIN_STRING, id) } />
         <div class="flex items-center gap-3 text-neutral-600 mt-16">
           <Icon class="fas fa-info-circle" />
           <p class="text-xl font-bold">{"Starting the game without all 4 players will fill the remaining spots with
@@ -114,13 +252,26 @@ pub fn game_lobby(props: &GameLobbyProps) -> Html {
         </div>
         <div class="flex flex-col gap-3">
           <OutlinedItem label="Players connected" item={players_item} />
+          { roster_items }
+          <div class="w-full font-semibold rounded border border-neutral-300 p-3 flex justify-between items-center">
+            <span>{"Your color"}</span>
+            <div class="flex items-center gap-2">{ color_picker }</div>
+          </div>
+          <OutlinedItem label="Spectators" item={spectators_item} />
           <OutlinedItem label="Time in lobby" item={time_item} />
         </div>
         <div class="w-full flex justify-end">
-          <span>{"Waiting for other players to join"}</span>
+          <span>{ waiting_text }</span>
         </div>
         <div class="flex items-center gap-3 mt-16">
-          <Button class="w-full" onclick={on_start} icon={start_icon}>{"Start the game!"}</Button>
+          <Button class="w-full" onclick={on_ready} icon={ready_icon}>{ ready_text }</Button>
+          {
+            if is_host {
+              html! { <Button class="w-full" disabled={!everyone_ready} onclick={on_start} icon={start_icon}>{"Start the game!"}</Button> }
+            } else {
+              html! {}
+            }
+          }
           <Button class="w-full bg-red-700" onclick={redirect_to_home} icon={leave_icon}>{"Leave the lobby"}</Button>
         </div>
       </Card>
@@ -0,0 +1,308 @@
+use gloo::timers::callback::Timeout;
+use ludo_core::models::{game::Game, game_event::GameEvent, replay::Replay};
+use ludo_core::utils::enums::Move;
+use reqwasm::http::Request;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::board::Board;
+use crate::components::button::Button;
+use crate::components::card::Card;
+use crate::components::icon::Icon;
+use crate::components::spinner::Spinner;
+use crate::components::text_input::TextInput;
+use crate::context::game_context::context::GameContext;
+use crate::context::game_context::game_reducer::GameState;
+use crate::context::snackbar::context::{SnackbarContext, SnackbarOptions, SnackbarVariant};
+use crate::utils::color_to_name::color_to_name;
+use crate::utils::get_host::HTTP_STRING;
+
+// how long a step stays on the board during autoplay at 1x
+const STEP_DURATION: u32 = 1000;
+const SPEEDS: [u32; 4] = [1, 2, 4, 8];
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct GameReplayProps {
+  pub id: String,
+}
+
+// the replay together with the game after every step, computed once when it is loaded
+#[derive(PartialEq)]
+struct LoadedReplay {
+  replay: Replay,
+  states: Vec<Game>,
+  turns: Vec<usize>,
+}
+
+// what happened in the step, 'game' is the game before the step was played
+fn describe_step(game: &Game, event: &GameEvent) -> String {
+  let name = color_to_name(game, game.current_player);
+  match event {
+    GameEvent::Rolled(roll) => format!("{} rolled {}", name, roll),
+    GameEvent::Moved(player_move) => {
+      let action = match player_move {
+        Move::Enter { .. } => String::from("brought a new piece into the game"),
+        Move::Advance { from, to, .. } => format!("moved a piece from {} to {}", from, to),
+        Move::EnterHome { .. } => String::from("moved a piece into the home column"),
+        Move::AdvanceInHome { from, to } => {
+          format!("moved a piece in the home column from {} to {}", from, to)
+        }
+        Move::Finish { .. } => String::from("brought a piece to the finish"),
+      };
+      match player_move.is_capture() {
+        true => format!("{} {} and captured a piece", name, action),
+        false => format!("{} {}", name, action),
+      }
+    }
+    GameEvent::Skipped => format!("{} had no move to make", name),
+    GameEvent::Undone { .. } => String::from("The last move was taken back"),
+    GameEvent::Left(color) => format!("{} left, a bot plays for them", color_to_name(game, *color)),
+    GameEvent::Returned(color) => format!("{} is back", color_to_name(game, *color)),
+    _ => String::new(),
+  }
+}
+
+#[function_component(GameReplay)]
+pub fn game_replay(props: &GameReplayProps) -> Html {
+  let GameReplayProps { id } = props.clone();
+  let SnackbarContext { open } = use_context().expect("context not found");
+  let loaded = use_state::<Option<Rc<LoadedReplay>>, _>(|| None);
+  let step = use_state(|| 0_usize);
+  let playing = use_state(|| false);
+  let speed = use_state(|| 1_u32);
+  let turn_input = use_state(String::new);
+
+  {
+    let loaded = loaded.clone();
+    let open = open.clone();
+    use_effect_with_deps(
+      move |id: &String| {
+        let id = id.clone();
+        spawn_local(async move {
+          let res = Request::get(format!("{}/games/{}/replay", HTTP_STRING, id).as_str())
+            .send()
+            .await;
+          let replay = match res {
+            Ok(resp) if resp.ok() => resp.json::<Replay>().await.ok(),
+            _ => None,
+          };
+          match replay {
+            Some(replay) => {
+              let states = replay.states();
+              let turns = Replay::turns(&states);
+              loaded.set(Some(Rc::new(LoadedReplay {
+                replay,
+                states,
+                turns,
+              })));
+            }
+            None => open.emit(SnackbarOptions {
+              message: "Couldn't load the replay".into(),
+              variant: SnackbarVariant::Error,
+            }),
+          }
+        });
+
+        || {}
+      },
+      id,
+    );
+  }
+
+  let last_step = (*loaded)
+    .as_ref()
+    .map(|loaded| loaded.states.len() - 1)
+    .unwrap_or(0);
+  let step_index = (*step).min(last_step);
+
+  // every render schedules the next step while playing, so the timeout never sees a stale step
+  {
+    let step = step.clone();
+    let playing = playing.clone();
+    let delay = STEP_DURATION / *speed;
+    use_effect(move || {
+      let timeout = (*playing).then(|| {
+        Timeout::new(delay, move || match step_index < last_step {
+          true => step.set(step_index + 1),
+          false => playing.set(false),
+        })
+      });
+
+      move || drop(timeout)
+    });
+  }
+
+  let loaded = match &*loaded {
+    Some(loaded) => loaded.clone(),
+    None => {
+      return html! {
+        <div class="h-full grid place-items-center">
+          <Spinner />
+        </div>
+      }
+    }
+  };
+
+  let game = loaded.states[step_index].clone();
+  let turn = loaded
+    .turns
+    .iter()
+    .filter(|start| **start <= step_index)
+    .count();
+  let description = match step_index {
+    0 => String::from("The game has started"),
+    index => describe_step(
+      &loaded.states[index - 1],
+      &loaded.replay.steps[index - 1].event,
+    ),
+  };
+
+  // the board is only looked at, without a sender clicking a piece doesn't send anything
+  let context = GameContext {
+    game: game.clone(),
+    player_color: game.current_player,
+    player_count: game.players.len() as u32,
+    // nobody rolls the dice of a replay
+    spectating: true,
+    spectator_count: 0,
+    subscribe: Callback::noop(),
+    sender: None,
+    current_player: game.current_player,
+    dice_info: GameState::default().dice_info,
+    undo_request: None,
+    available_moves: vec![],
+    selected_die: None,
+    select_die: Callback::noop(),
+    turn_time_left: None,
+  };
+
+  let on_back = {
+    let step = step.clone();
+    let playing = playing.clone();
+    Callback::from(move |_| {
+      playing.set(false);
+      step.set(step_index.saturating_sub(1));
+    })
+  };
+
+  let on_forward = {
+    let step = step.clone();
+    let playing = playing.clone();
+    Callback::from(move |_| {
+      playing.set(false);
+      step.set((step_index + 1).min(last_step));
+    })
+  };
+
+  let on_play = {
+    let step = step.clone();
+    let playing = playing.clone();
+    Callback::from(move |_| {
+      if !*playing && step_index == last_step {
+        step.set(0);
+      }
+      playing.set(!*playing);
+    })
+  };
+
+  let on_turn_input = {
+    let turn_input = turn_input.clone();
+    Callback::from(move |event: InputEvent| {
+      let target = event.target();
+      let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+
+      if let Some(element) = input {
+        turn_input.set(element.value());
+      }
+    })
+  };
+
+  let on_jump = {
+    let step = step.clone();
+    let playing = playing.clone();
+    let turn_input = turn_input.clone();
+    let turns = loaded.turns.clone();
+    Callback::from(move |_| {
+      let start = turn_input
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|turn| turn.checked_sub(1))
+        .and_then(|index| turns.get(index));
+      match start {
+        Some(start) => {
+          playing.set(false);
+          step.set(*start);
+        }
+        None => open.emit(SnackbarOptions {
+          message: format!("There is no turn {}", *turn_input),
+          variant: SnackbarVariant::Error,
+        }),
+      }
+    })
+  };
+
+  let speed_buttons = SPEEDS.iter().map(|value| {
+    let speed = speed.clone();
+    let value = *value;
+    let class = match *speed == value {
+      true => String::from("w-full"),
+      false => String::from("w-full bg-neutral-400"),
+    };
+    html! {
+      <Button {class} onclick={Callback::from(move |_| speed.set(value))}>{format!("{}x", value)}</Button>
+    }
+  });
+
+  let winner = game
+    .winner
+    .map(|winner| format!("{} won the game", color_to_name(&game, winner)));
+
+  let play_icon = match *playing {
+    true => html! { <Icon class="fas fa-pause"/> },
+    false => html! { <Icon class="fas fa-play"/> },
+  };
+
+  html! {
+    <ContextProvider<GameContext> {context}>
+      <div class="py-4 flex">
+        <div class="flex-grow">
+          <Board />
+        </div>
+        <div class="p-4 max-w-md flex-grow">
+          <Card class="p-6 flex flex-col gap-6">
+            <div class="flex flex-col gap-2">
+              <p class="text-3xl font-bold">{format!("Turn {} of {}", turn, loaded.turns.len())}</p>
+              <p class="text-xl text-neutral-600 font-bold">{description}</p>
+              {
+                match winner {
+                  Some(winner) => html! { <p class="text-xl text-primary-600 font-bold">{winner}</p> },
+                  None => html! {},
+                }
+              }
+              <p class="text-neutral-600">{format!("Step {} / {}", step_index, last_step)}</p>
+            </div>
+            <div class="flex items-center gap-3">
+              <Button class="w-full" onclick={on_back} disabled={step_index == 0} icon={html! { <Icon class="fas fa-step-backward"/> }} />
+              <Button class="w-full" onclick={on_play} icon={play_icon} />
+              <Button class="w-full" onclick={on_forward} disabled={step_index == last_step} icon={html! { <Icon class="fas fa-step-forward"/> }} />
+            </div>
+            <div class="flex flex-col gap-2">
+              <p class="font-semibold text-neutral-600">{"Autoplay speed:"}</p>
+              <div class="flex items-center gap-3">
+                { for speed_buttons }
+              </div>
+            </div>
+            <div class="flex items-end gap-3">
+              <TextInput value={(*turn_input).clone()} label={"Jump to turn:".to_string()} onchange={on_turn_input} />
+              <Button onclick={on_jump} disabled={turn_input.is_empty()}>{"Jump"}</Button>
+            </div>
+          </Card>
+        </div>
+      </div>
+    </ContextProvider<GameContext>>
+  }
+}
@@ -1,5 +1,8 @@
 use reqwasm::http::Request;
+use serde::Serialize;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
@@ -7,20 +10,88 @@ use crate::components::button::Button;
 use crate::components::card::Card;
 use crate::components::content::Content;
 use crate::components::icon::Icon;
+use crate::components::text_input::TextInput;
 use crate::utils::get_host::HTTP_STRING;
 use crate::context::snackbar::context::{SnackbarContext, SnackbarOptions, SnackbarVariant};
 use crate::routes::MainRoute;
 
+#[derive(Serialize)]
+pub struct CreateGameBody {
+  pub private: bool,
+  pub password: Option<String>,
+}
+
+fn input_setter(state: UseStateHandle<String>) -> Callback<InputEvent> {
+  Callback::from(move |event: InputEvent| {
+    let input = event
+      .target()
+      .and_then(|target| target.dyn_into::<HtmlInputElement>().ok());
+    if let Some(element) = input {
+      state.set(element.value());
+    }
+  })
+}
+
 #[function_component(Home)]
 pub fn home() -> Html {
   let history = use_history().unwrap();
   let SnackbarContext { open } = use_context::<SnackbarContext>().expect("context not found");
+  let private = use_state(|| false);
+  let password = use_state(String::new);
+  let join_code = use_state(String::new);
+
+  let browse = {
+    let history = history.clone();
+    Callback::from(move |_| history.push(MainRoute::Lobbies))
+  };
+
+  let on_private = {
+    let private = private.clone();
+    Callback::from(move |_| private.set(!*private))
+  };
+
+  // resolves the code of a private game to its id and goes on to joining it
+  let on_join_code = {
+    let history = history.clone();
+    let open = open.clone();
+    let join_code = join_code.clone();
+    Callback::from(move |_| {
+      let history = history.clone();
+      let open = open.clone();
+      let code = join_code.trim().to_string();
+      spawn_local(async move {
+        let url = format!("{}/games/codes/{}", HTTP_STRING, code);
+        let id = match Request::get(url.as_str()).send().await {
+          Ok(resp) if resp.ok() => resp.text().await.ok(),
+          _ => None,
+        };
+        match id {
+          Some(id) => history.push(MainRoute::GameJoin { id }),
+          None => open.emit(SnackbarOptions {
+            message: "There is no game with this code".into(),
+            variant: SnackbarVariant::Error,
+          }),
+        }
+      });
+    })
+  };
 
   let onclick = Callback::from(move |_| {
     let history = history.clone();
     let open = open.clone();
+    let body = CreateGameBody {
+      private: *private,
+      password: match *private && !password.is_empty() {
+        true => Some((*password).clone()),
+        false => None,
+      },
+    };
     spawn_local(async move {
-      let res = Request::post(format!("{}/games",HTTP_STRING).as_str()).send().await;
+      let res = Request::post(format!("{}/games",HTTP_STRING).as_str())
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_string(&body).unwrap())
+        .send()
+        .await;
 
       let resp = match res {
         Ok(resp) => resp,
@@ -33,6 +104,14 @@ pub fn home() -> Html {
         }
       };
 
+      if !resp.ok() {
+        open.emit(SnackbarOptions {
+          message: "Server failed creating new game".into(),
+          variant: SnackbarVariant::Error,
+        });
+        return;
+      }
+
       let id = match resp.text().await {
         Ok(id) => id,
         Err(_) => {
@@ -52,6 +131,10 @@ pub fn home() -> Html {
     <Icon class={classes!(String::from("fas fa-gamepad"))}/>
   };
 
+  let browse_icon = html! {
+    <Icon class="fas fa-list"/>
+  };
+
   html! {
     <Content class="py-12 h-full">
       <div class="flex items-center mb-6 w-full">
@@ -69,7 +152,25 @@ pub fn home() -> Html {
           <li class="text-lg font-semibold text-neutral-600">{"Invite up to 4 friends to play"}</li>
           <li class="text-lg font-semibold text-neutral-600">{"Start the game when ready!"}</li>
         </ol>
+        <div class="flex items-end gap-6 mb-6">
+          <label class="flex items-center gap-2 font-semibold text-neutral-600 p-3">
+            <input type="checkbox" checked={*private} onclick={on_private} />
+            {"Private game"}
+          </label>
+          {
+            if *private {
+              html! { <TextInput label="Password (optional)" value={(*password).clone()} onchange={input_setter(password.clone())} /> }
+            } else {
+              html! {}
+            }
+          }
+        </div>
         <Button class="w-full" {onclick} icon={create_icon}>{"Create new game lobby"}</Button>
+        <Button class="w-full mt-3 bg-neutral-600" onclick={browse} icon={browse_icon}>{"Join a game of strangers"}</Button>
+        <div class="flex items-end gap-6 mt-8">
+          <TextInput label="Join code" value={(*join_code).clone()} onchange={input_setter(join_code.clone())} />
+          <Button onclick={on_join_code} disabled={join_code.trim().is_empty()}>{"Join with the code"}</Button>
+        </div>
       </Card>
     </Content>
   }
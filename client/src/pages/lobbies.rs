@@ -0,0 +1,215 @@
+use ludo_core::models::{
+  lobby::{LobbyPage, LobbySummary},
+  rule_set::RuleSet,
+};
+use reqwasm::http::Request;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::button::Button;
+use crate::components::card::Card;
+use crate::components::content::Content;
+use crate::components::icon::Icon;
+use crate::components::text_input::TextInput;
+use crate::context::snackbar::context::{SnackbarContext, SnackbarOptions, SnackbarVariant};
+use crate::routes::MainRoute;
+use crate::utils::get_host::HTTP_STRING;
+
+const PAGE_SIZE: usize = 10;
+
+// everything but letters and digits is percent encoded, so the host filter can hold any text
+fn encode_query(value: &str) -> String {
+  value
+    .bytes()
+    .map(|byte| match byte.is_ascii_alphanumeric() {
+      true => (byte as char).to_string(),
+      false => format!("%{:02X}", byte),
+    })
+    .collect()
+}
+
+fn format_age(age: u64) -> String {
+  match age {
+    0..=59 => String::from("just now"),
+    60..=3599 => format!("{} min ago", age / 60),
+    _ => format!("{} h ago", age / 3600),
+  }
+}
+
+// the page after the cursor, None when the request failed
+async fn fetch_page(host: String, free_seats: usize, cursor: Option<String>) -> Option<LobbyPage> {
+  let mut url = format!(
+    "{}/games?limit={}&free_seats={}",
+    HTTP_STRING, PAGE_SIZE, free_seats
+  );
+  if !host.is_empty() {
+    url.push_str(&format!("&host={}", encode_query(&host)));
+  }
+  if let Some(cursor) = cursor {
+    url.push_str(&format!("&cursor={}", encode_query(&cursor)));
+  }
+  match Request::get(url.as_str()).send().await {
+    Ok(resp) if resp.ok() => resp.json::<LobbyPage>().await.ok(),
+    _ => None,
+  }
+}
+
+#[function_component(Lobbies)]
+pub fn lobbies() -> Html {
+  let SnackbarContext { open } = use_context::<SnackbarContext>().expect("context not found");
+  let history = use_history().unwrap();
+  let lobbies = use_state(Vec::<LobbySummary>::new);
+  let next_cursor = use_state::<Option<String>, _>(|| None);
+  let host = use_state(String::new);
+  let free_seats = use_state(|| 1_usize);
+
+  // loads one more page, a missing cursor starts the list over
+  let load = {
+    let lobbies = lobbies.clone();
+    let next_cursor = next_cursor.clone();
+    let host = host.clone();
+    let free_seats = free_seats.clone();
+    Callback::from(move |cursor: Option<String>| {
+      let lobbies = lobbies.clone();
+      let next_cursor = next_cursor.clone();
+      let open = open.clone();
+      let (host, free_seats) = ((*host).clone(), *free_seats);
+      spawn_local(async move {
+        match fetch_page(host, free_seats, cursor.clone()).await {
+          Some(page) => {
+            let mut loaded = match cursor {
+              Some(_) => (*lobbies).clone(),
+              None => vec![],
+            };
+            loaded.extend(page.lobbies);
+            lobbies.set(loaded);
+            next_cursor.set(page.next_cursor);
+          }
+          None => open.emit(SnackbarOptions {
+            message: "Couldn't load the games".into(),
+            variant: SnackbarVariant::Error,
+          }),
+        }
+      });
+    })
+  };
+
+  // the filters start the list over
+  {
+    let load = load.clone();
+    use_effect_with_deps(
+      move |_| {
+        load.emit(None);
+        || {}
+      },
+      ((*host).clone(), *free_seats),
+    );
+  }
+
+  let on_host_change = {
+    let host = host.clone();
+    Callback::from(move |event: InputEvent| {
+      let input = event
+        .target()
+        .and_then(|target| target.dyn_into::<HtmlInputElement>().ok());
+      if let Some(element) = input {
+        host.set(element.value());
+      }
+    })
+  };
+
+  let seat_buttons = (1..=3)
+    .map(|seats| {
+      let free_seats = free_seats.clone();
+      let class = match *free_seats == seats {
+        true => "w-12",
+        false => "w-12 bg-neutral-400",
+      };
+      let onclick = Callback::from(move |_| free_seats.set(seats));
+      html! {
+        <Button {class} {onclick}>
+          { format!("{}+", seats) }
+        </Button>
+      }
+    })
+    .collect::<Html>();
+
+  let rows = lobbies
+    .iter()
+    .map(|lobby| {
+      let onclick = {
+        let history = history.clone();
+        let id = lobby.id.clone();
+        Callback::from(move |_| history.push(MainRoute::GameJoin { id: id.clone() }))
+      };
+      let on_watch = {
+        let history = history.clone();
+        let id = lobby.id.clone();
+        Callback::from(move |_| history.push(MainRoute::GameWatch { id: id.clone() }))
+      };
+      let rules = match lobby.rules == RuleSet::default() {
+        true => "standard rules",
+        false => "house rules",
+      };
+      let join_icon = html! {
+        <Icon class="fas fa-sign-in-alt"/>
+      };
+      html! {
+        <div class="w-full font-semibold rounded border border-neutral-300 p-3 flex justify-between items-center gap-4">
+          <div class="flex flex-col">
+            <span>{ format!("{}'s game", lobby.host) }</span>
+            <span class="text-sm text-neutral-600">
+              { format!("{} free seats, {}, created {}", lobby.free_seats, rules, format_age(lobby.age)) }
+            </span>
+          </div>
+          <div class="flex gap-2">
+            <Button class="bg-neutral-400" onclick={on_watch}>{"Watch"}</Button>
+            <Button {onclick} icon={join_icon}>{"Join"}</Button>
+          </div>
+        </div>
+      }
+    })
+    .collect::<Html>();
+
+  let empty = match lobbies.is_empty() {
+    true => html! { <p class="text-neutral-600">{"There are no games waiting for players"}</p> },
+    false => html! {},
+  };
+
+  let more = match (*next_cursor).clone() {
+    Some(cursor) => {
+      let onclick = Callback::from(move |_| load.emit(Some(cursor.clone())));
+      html! { <Button class="w-full" {onclick}>{"Load more"}</Button> }
+    }
+    None => html! {},
+  };
+
+  html! {
+    <Content class="py-12 h-full">
+      <div class="flex items-center mb-6 w-full">
+        <div class="flex flex-col gap-2 w-full justify-between">
+          <p class="text-5xl font-bold">{"Ludo"}</p>
+          <p class="text-2xl text-neutral-600 font-bold">{"Games waiting for players"}</p>
+        </div>
+        <img class="h-28" src="/assets/ludo.svg" alt="" />
+      </div>
+      <Card class="w-full px-8 py-14 lg:px-40">
+        <div class="flex items-end gap-6 mb-6">
+          <TextInput label="Host" value={(*host).clone()} onchange={on_host_change} />
+          <div>
+            <p class="font-semibold text-neutral-600">{"Free seats"}</p>
+            <div class="flex gap-2">{ seat_buttons }</div>
+          </div>
+        </div>
+        <div class="flex flex-col gap-3">
+          { rows }
+          { empty }
+          { more }
+        </div>
+      </Card>
+    </Content>
+  }
+}
@@ -1,5 +1,7 @@
 pub mod game;
 pub mod game_join;
 pub mod game_lobby;
+pub mod game_replay;
 pub mod home;
+pub mod lobbies;
 pub mod not_found;
@@ -6,15 +6,25 @@ use crate::context::snackbar::provider::SnackbarProvider;
 use crate::pages::game::Game;
 use crate::pages::game_join::GameJoin;
 use crate::pages::game_lobby::GameLobby;
+use crate::pages::game_replay::GameReplay;
 use crate::pages::home::Home;
+use crate::pages::lobbies::Lobbies;
 use crate::pages::not_found::NotFound;
 
 #[derive(Clone, Routable, PartialEq)]
 pub enum MainRoute {
   #[at("/")]
   Home,
+  #[at("/lobbies")]
+  Lobbies,
   #[at("/games/:id/join")]
   GameJoin { id: String },
+  // the replay is fetched once, it doesn't need the websocket of the game subroutes
+  #[at("/games/:id/replay")]
+  GameReplay { id: String },
+  // the game without a seat, the websocket is read only
+  #[at("/games/:id/watch")]
+  GameWatch { id: String },
   #[at("/games/:id/:rest")]
   GameSubroutes { id: String },
   #[not_found]
@@ -33,14 +43,21 @@ pub enum GameRoute {
 fn switch_game(route: &GameRoute) -> Html {
   match route {
     GameRoute::GameLobby { id } => html! { <GameLobby id={ id.clone() } /> },
-    GameRoute::Game { id: _ } => html! { <Game /> },
+    GameRoute::Game { id } => html! { <Game id={ id.clone() } /> },
   }
 }
 
 fn switch_main(routes: &MainRoute) -> Html {
   match routes {
     MainRoute::Home => html! {<Home />},
+    MainRoute::Lobbies => html! {<Lobbies />},
     MainRoute::GameJoin { id } => html! { <GameJoin id={ id.clone() } /> },
+    MainRoute::GameReplay { id } => html! { <GameReplay id={ id.clone() } /> },
+    MainRoute::GameWatch { id } => html! {
+      <GameProvider game_id={ id.clone() } spectate={true}>
+        <Game id={ id.clone() } />
+      </GameProvider>
+    },
     MainRoute::GameSubroutes { id } => html! {
       <GameProvider game_id={ id.clone() }>
         <Switch<GameRoute> render={Switch::render(switch_game)} />
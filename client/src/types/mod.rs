@@ -1,3 +0,0 @@
-use crate::models::color::Color;
-
-pub type FieldType = Option<Color>;
@@ -1,4 +1,4 @@
-use crate::models::{color::Color, game::Game};
+use ludo_core::models::{color::Color, game::Game};
 
 pub fn color_to_name(game: &Game, color: Color) -> String {
   let player = game
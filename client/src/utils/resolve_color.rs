@@ -1,4 +1,4 @@
-use crate::models::color::Color;
+use ludo_core::models::color::Color;
 
 pub fn resolve_bg_color_class(color: &Color) -> String {
   match color {
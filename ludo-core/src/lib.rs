@@ -0,0 +1,4 @@
+// Models, game rules and the websocket protocol shared by the server and the client
+pub mod models;
+pub mod types;
+pub mod utils;
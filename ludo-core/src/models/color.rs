@@ -2,7 +2,7 @@ use core::fmt;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Hash, Eq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum Color {
   Red,
   Green,
@@ -10,6 +10,12 @@ pub enum Color {
   Yellow,
 }
 
+impl Color {
+  pub fn ordered() -> Vec<Color> {
+    vec![Color::Green, Color::Yellow, Color::Blue, Color::Red]
+  }
+}
+
 impl fmt::Display for Color {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     write!(f, "{:?}", self)
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+// What the current player can do with one of the pending dice
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct DieMoves {
+  pub dice_value: usize,
+  pub positions_on_board: Vec<usize>,
+  pub positions_in_home: Vec<usize>,
+  pub can_promote: bool,
+}
+
+impl DieMoves {
+  pub fn is_empty(&self) -> bool {
+    self.positions_on_board.is_empty() && self.positions_in_home.is_empty() && !self.can_promote
+  }
+}
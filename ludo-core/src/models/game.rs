@@ -0,0 +1,1740 @@
+use crate::models::color::Color;
+use crate::types::Field;
+use crate::utils::enums::{Move, MoveResult, RoundPhase};
+use serde::{Deserialize, Serialize};
+
+use super::player::Player;
+use super::rule_set::RuleSet;
+use super::square::Square;
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Game {
+  pub started: bool,
+  pub winner: Option<Color>,
+  pub fields: Fields,
+  pub players: Vec<Player>,
+  pub current_player: Color,
+  pub dice_throws: Vec<usize>,
+  pub round_phase: RoundPhase,
+  // dice of the current turn that still have to be moved with
+  #[serde(default)]
+  pub pending_moves: Vec<usize>,
+  // the dice of the game are generated from the seed, together with how many rolls were made so far
+  #[serde(default)]
+  pub dice_seed: u64,
+  #[serde(default)]
+  pub dice_rolls: u64,
+  // games stored before house rules existed are played with the standard rules
+  #[serde(default)]
+  pub rules: RuleSet,
+  // only private games have a code, they are joined with it and aren't listed in the lobbies
+  #[serde(default)]
+  pub join_code: Option<String>,
+}
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Fields {
+  values: Vec<Square>,
+}
+impl Fields {
+  pub fn new(size: usize) -> Fields {
+    Fields {
+      values: vec![Square::default(); size],
+    }
+  }
+  pub fn get(&self, i: usize) -> &Square {
+    self.values.get(i % self.len()).unwrap()
+  }
+  pub(crate) fn get_mut(&mut self, i: usize) -> &mut Square {
+    let size = self.len();
+    &mut self.values[i % size]
+  }
+  // replaces whatever is on the square with a single piece
+  #[cfg(test)]
+  fn set(&mut self, k: usize, v: Field) {
+    *self.get_mut(k) = v.into();
+  }
+  pub(crate) fn len(&self) -> usize {
+    self.values.len()
+  }
+  pub fn iter(&self) -> impl Iterator<Item = &Square> {
+    self.values.iter()
+  }
+}
+
+impl Default for Fields {
+  fn default() -> Self {
+    Self::new(RuleSet::default().field_count)
+  }
+}
+
+impl Game {
+  pub fn new() -> Self {
+    Self::with_rules(RuleSet::default())
+  }
+
+  pub fn with_rules(rules: RuleSet) -> Self {
+    Game {
+      started: false,
+      winner: None,
+      fields: Fields::new(rules.field_count),
+      players: vec![],
+      current_player: Color::ordered().first().unwrap().to_owned(),
+      round_phase: RoundPhase::Rolling,
+      dice_throws: vec![],
+      pending_moves: vec![],
+      dice_seed: 0,
+      dice_rolls: 0,
+      rules,
+      join_code: None,
+    }
+  }
+
+  // there should be at most one winner at a time, therefore we take the first
+  //   player that meets the winning condition
+  pub fn check_winner(&self) -> Option<Color> {
+    for player in &self.players {
+      if player.check_winner(&self.rules) {
+        return Some(player.color);
+      }
+    }
+    None
+  }
+
+  pub fn finish_game(&mut self, color: Color) {
+    self.winner = Some(color)
+  }
+
+  pub fn field_size(&self) -> usize {
+    self.fields.len()
+  }
+
+  pub fn get_player_id(&self, color: Color) -> String {
+    self.get_player(color).id.clone()
+  }
+
+  pub fn get_current_player_id(&self) -> String {
+    self.get_player_id(self.current_player)
+  }
+
+  // records a roll of the current player, once the rolling is over the dice have to be moved with
+  pub fn add_roll(&mut self, roll: usize) {
+    self.dice_throws.push(roll);
+    if !self.rules.can_roll_again(&self.dice_throws) && !self.rules.is_forfeit(&self.dice_throws) {
+      self.pending_moves = self.rules.pending_moves(&self.dice_throws);
+      self.round_phase = RoundPhase::Moving;
+    }
+  }
+
+  // the turn goes to the next player without a move
+  pub fn skip_player(&mut self) {
+    self.update_current_player();
+    self.dice_throws.clear();
+  }
+
+  pub fn update_current_player(&mut self) {
+    self.current_player = match self.current_player {
+      Color::Yellow => Color::Blue,
+      Color::Blue => Color::Red,
+      Color::Red => Color::Green,
+      Color::Green => Color::Yellow,
+    };
+    self.round_phase = RoundPhase::Rolling;
+    self.pending_moves.clear();
+  }
+
+  // removes a die from the pending moves once a move was made with it
+  pub fn spend_move(&mut self, dice_value: usize) {
+    if let Some(index) = self
+      .pending_moves
+      .iter()
+      .position(|pending| *pending == dice_value)
+    {
+      self.pending_moves.remove(index);
+    }
+  }
+
+  // how many steps we need to make to reach the first field of player's home
+  // e.g. curr_pos = 0, end_pos = 39 => distance = 40 (need to throw 40 to get to home)
+  // max(end_pos + field_size) = 39, max(curr_pos) = 39
+  pub fn distance_from_home(&self, current_position: usize) -> usize {
+    // position of the field right in front of home
+    let end_position = self.get_end_position();
+    // +1 to get to the first home field
+    (end_position + self.field_size() - current_position) % self.field_size() + 1
+  }
+
+  // how far away is the starting position (where we place pieces after throwing 6)
+  //   from the ending position (= the last field before home)
+  fn start_end_position_difference(&self) -> usize {
+    self.rules.start_end_difference
+  }
+
+  // we can use this 'modulo trick' to deal with different offsets and looping (pos 39 -> 0)
+  // e.g. start_pos = 0 => end_pos = 39
+  pub fn get_end_position(&self) -> usize {
+    (self.get_starting_position() + self.fields.len() - self.start_end_position_difference())
+      % self.fields.len()
+  }
+
+  // returns size of the home column (finish)
+  pub fn get_home_size(&self) -> usize {
+    self.rules.home_size
+  }
+
+  pub fn is_occupied_by(&self, field: &Field, player_color: Color) -> bool {
+    match field {
+      None => false,
+      Some(color) => *color == player_color,
+    }
+  }
+
+  // stacked pieces are listed once
+  pub fn get_players_pieces_positions(&self, color: Color) -> Vec<usize> {
+    self
+      .fields
+      .iter()
+      .enumerate()
+      .filter(|&(_position, square)| square.count(color) > 0)
+      .map(|(position, _square)| position)
+      .collect()
+  }
+
+  pub fn get_players_pieces_positions_in_home(&self, color: Color) -> Vec<usize> {
+    self
+      .get_player(color)
+      .home
+      .clone()
+      .into_iter()
+      .enumerate()
+      .filter(|(_position, field)| self.is_occupied_by(field, self.current_player))
+      .map(|(position, _field)| position)
+      .collect()
+  }
+
+  pub fn get_home_field(&self, home_offset: usize) -> &Field {
+    let player = self.get_current_player();
+    match self.is_in_bounds_home(home_offset) {
+      true => &player.home[home_offset],
+      false => &None,
+    }
+  }
+
+  // bounds are solved by using Fields struct
+  // pub fn is_in_bounds(&self, position: usize) -> bool {
+  //   position < self.fields.len()
+  // }
+
+  // there is a clock-wise ordering: Yellow, Blue, Red, Green
+  // TODO: move to a utility, pass attr 'color' to replace 'self.current_player'
+  pub fn get_offset(&self) -> usize {
+    let offset = self.fields.len() / 4;
+    match self.current_player {
+      Color::Yellow => 0,
+      Color::Blue => offset,
+      Color::Red => offset * 2,
+      Color::Green => offset * 3,
+    }
+  }
+
+  // position of the field where we put pieces after throwing 6
+  pub fn get_starting_position(&self) -> usize {
+    self.get_offset() + self.rules.start_offset
+  }
+
+  pub fn is_safe_square(&self, position: usize) -> bool {
+    let position = position % self.field_size();
+    self.rules.safe_squares.contains(&position)
+  }
+
+  // if we land on opponent at 'position', we remove his pieces (our own pieces stay stacked)
+  pub fn clear_field(&mut self, position: usize) {
+    let opponents = self
+      .fields
+      .get(position)
+      .pawns()
+      .map(|(color, _count)| color)
+      .filter(|color| *color != self.current_player)
+      .collect::<Vec<_>>();
+    for color in opponents {
+      let removed = self.fields.get_mut(position).remove_all(color);
+      for _ in 0..removed {
+        self.remove_players_piece(color);
+      }
+    }
+  }
+
+  pub fn remove_players_piece(&mut self, color: Color) {
+    let player = self.get_player_mut(color);
+    player.increase_pieces_at_start();
+  }
+
+  // add check for player.pawns_at_start > 0 ?
+  /// check if no blockade stands on the starting position or between it and where the promoted piece would land
+  pub fn can_promote_piece(&self, dice_value: usize) -> bool {
+    println!("promote roll: {}", dice_value);
+    self.rules.can_enter(dice_value)
+      && self.is_available_field(self.get_starting_position())
+      && !self.is_path_blocked(
+        self.get_starting_position(),
+        self.rules.entry_steps(dice_value),
+      )
+      && self.get_current_player().pawns_at_start > 0
+  }
+
+  // we can jump to a field, unless an opponent's blockade stands there
+  // our own pieces get stacked, opponent's single piece gets removed (or shares a safe square)
+  pub fn is_available_field(&self, position: usize) -> bool {
+    !self
+      .fields
+      .get(position)
+      .is_blockade_for(self.current_player)
+  }
+
+  // if there is an opponent's blockade on any of the 'steps' fields after 'position'
+  pub fn is_path_blocked(&self, position: usize, steps: usize) -> bool {
+    (1..=steps).any(|step| !self.is_available_field(position + step))
+  }
+
+  // how many of the 'dice_value' steps are made on the main board (the rest is in home column)
+  fn board_steps(&self, position: usize, dice_value: usize) -> usize {
+    dice_value.min(self.distance_from_home(position) - 1)
+  }
+
+  // pub fn opponent_at_field(&self, position: usize) -> bool {
+  //   // self.is_in_bounds(position) &&
+  //     !self.is_current_players_piece(position)
+  //     && self.is_opponents_piece(position)
+  // }
+
+  pub fn get_new_position(&self, position: usize, dice_value: usize) -> usize {
+    (position + dice_value) % self.fields.len()
+  }
+
+  /// if we can make a move/jump within main board/field (not reaching home)
+  pub fn can_jump(&self, position: usize, dice_value: usize) -> bool {
+    dice_value < self.distance_from_home(position) && !self.is_path_blocked(position, dice_value)
+  }
+
+  pub fn will_remove_enemy(&self, position: usize, dice_value: usize) -> bool {
+    self.can_jump(position, dice_value)
+      && self.is_opponents_piece(position + dice_value)
+      && !self.is_safe_square(position + dice_value)
+    // get_new_position(position, dice_value) can be replaced with position+dice_value,
+    //  since
+  }
+
+  pub fn is_in_bounds_home(&self, home_offset: usize) -> bool {
+    home_offset < self.get_home_size()
+  }
+
+  pub fn is_available_home_field(&self, home_offset: usize) -> bool {
+    self.is_in_bounds_home(home_offset) && !self.is_home_field_occupied(home_offset)
+  }
+
+  /// check if piece can reach `safe zone`
+  pub fn can_jump_to_home(&self, position: usize, dice_value: usize) -> bool {
+    // let distance_from_home = self.distance_from_home(position);
+    // let will_reach_home = dice_value >= distance_from_home;
+    // let will_not_overjump_home = dice_value < distance_from_home + self.get_home_size();
+    // match will_reach_home && will_not_overjump_home {
+    //     true => {
+    //         let home_offset = dice_value - distance_from_home;
+    //         self.is_available_home_field(home_offset)
+    //     },
+    //     false => false
+    // }
+
+    match self.can_reach_home(position, dice_value)
+      && !self.would_overjump_home(position, dice_value)
+      && !self.is_path_blocked(position, self.board_steps(position, dice_value))
+    {
+      true => self.is_available_home_field(self.get_home_offset(position, dice_value)),
+      false => false,
+    }
+  }
+
+  pub fn jump(&mut self, old_position: usize, new_position: usize) {
+    let color = self.current_player;
+    self.fields.get_mut(old_position).remove(color);
+    if !self.is_safe_square(new_position) {
+      self.clear_field(new_position);
+    }
+    self.fields.get_mut(new_position).add(color)
+  }
+
+  // we assume we jump from 'main fields' to player's home
+  // this currently doesn't allow moving pieces within home itself - we would just have to
+  //    distinguish between old_position in self.fields and in home, so that we can clear
+  //    the correct field
+  pub fn jump_home(&mut self, old_position: usize, home_offset: usize) {
+    // TODO: add Home struct with safe get() / set() etc.
+    if !self.is_in_bounds_home(home_offset) {
+      return;
+    }
+    let color = self.current_player;
+    self.fields.get_mut(old_position).remove(color);
+    let home = self.get_home_mut();
+    home[home_offset] = Some(color);
+  }
+
+  // if we move 'dice_value' fields, we will reach beyond the main board/field
+  pub fn can_reach_home(&self, position: usize, dice_value: usize) -> bool {
+    dice_value >= self.distance_from_home(position)
+  }
+
+  // if we move 'dice_value' fields, we will reach beyond the main board/field
+  // assumes position indexes game.fields()
+  pub fn can_jump_to_finish(&self, position: usize, dice_value: usize) -> bool {
+    dice_value == self.distance_from_home(position) + self.get_home_size()
+      && !self.is_path_blocked(position, self.board_steps(position, dice_value))
+  }
+
+  // distance_from_home gets you already to the first home field, that's why '>=' and not only '>'
+  pub fn would_overjump_home(&self, position: usize, dice_value: usize) -> bool {
+    dice_value >= self.distance_from_home(position) + self.get_home_size()
+  }
+
+  // returns position/index of field in player's home column where we will jump,
+  // i.e. offset in player's home column
+  // e.g. if piece is right in front of home => distance = 1, and if we throw a 1,
+  //      we would reach the first home field (home_offset = 0)
+  pub fn get_home_offset(&self, position: usize, dice_value: usize) -> usize {
+    dice_value - self.distance_from_home(position)
+  }
+
+  pub fn get_home(&self) -> &Vec<Field> {
+    let player = self.get_current_player();
+    &player.home
+  }
+
+  pub fn get_home_mut(&mut self) -> &mut Vec<Field> {
+    let player = self.get_current_player_mut();
+    &mut player.home
+  }
+
+  pub fn is_home_field_occupied(&self, home_offset: usize) -> bool {
+    self.get_home_field(home_offset).is_some()
+  }
+
+  // jump from home column (1 of 5 home fields) to finish
+  pub fn jump_from_home_to_finish(&mut self, home_offset: usize) {
+    if !self.is_in_bounds_home(home_offset) {
+      return;
+    }
+    let player = self.get_current_player_mut();
+    player.home[home_offset] = None;
+    player.pawns_at_finish += 1;
+  }
+
+  // jump from main field to finish
+  pub fn jump_to_finish(&mut self, position: usize) {
+    let color = self.current_player;
+    self.fields.get_mut(position).remove(color);
+    let player = self.get_current_player_mut();
+    player.pawns_at_finish += 1;
+  }
+
+  // can jump from home (at home_offset) to finish OR move forward in home
+  pub fn can_jump_from_home(&self, home_offset: usize, dice_value: usize) -> bool {
+    let home = self.get_home();
+    self.can_jump_from_home_to_finish(home_offset, dice_value)
+      || (home_offset + dice_value < home.len() && home[home_offset + dice_value].is_none())
+  }
+
+  pub fn jump_from_home(&mut self, old_home_offset: usize, new_home_offset: usize) {
+    if !self.is_in_bounds_home(old_home_offset) || !self.is_in_bounds_home(new_home_offset) {
+      return;
+    }
+    let color = self.current_player;
+    let home = self.get_home_mut();
+    home[old_home_offset] = None;
+    home[new_home_offset] = Some(color)
+  }
+
+  // can jump from home (at home_offset) to finish
+  pub fn can_jump_from_home_to_finish(&self, home_offset: usize, dice_value: usize) -> bool {
+    let home = self.get_home();
+    home_offset + dice_value == home.len()
+  }
+
+  // when we are trying to move piece in home column (1 out of 5 home fields)
+  fn execute_move_from_home(&mut self, home_offset: usize, dice_value: usize) -> MoveResult {
+    let distance_from_home = self.get_home_size() - home_offset;
+    match dice_value == distance_from_home {
+      true => {
+        self.jump_from_home_to_finish(home_offset);
+        MoveResult::Success(String::from("Move successful."))
+      }
+      false => match dice_value > distance_from_home {
+        true => MoveResult::Error(String::from("Would overjump home.")),
+        false => {
+          let new_home_offset = home_offset + dice_value;
+          match self.is_available_home_field(new_home_offset) {
+            true => {
+              self.jump_from_home(home_offset, new_home_offset);
+              MoveResult::Success(String::from("Move successful."))
+            }
+            false => MoveResult::Error(String::from("Home field is occupied.")),
+          }
+        }
+      },
+    }
+  }
+
+  // as of now, we assume we can only move pieces from 'main fields', not home
+  pub fn execute_move(
+    &mut self,
+    position: usize,
+    dice_value: usize,
+    home_column: bool,
+  ) -> MoveResult {
+    // we are trying to moving piece from
+    if home_column {
+      return self.execute_move_from_home(position, dice_value);
+    }
+
+    if self.is_path_blocked(position, self.board_steps(position, dice_value)) {
+      return MoveResult::Error(String::from(
+        "Can't move - the way is blocked by a blockade.",
+      ));
+    }
+
+    if self.can_jump_to_finish(position, dice_value) {
+      self.jump_to_finish(position);
+      return MoveResult::Success(String::from("Jumped to finish!"));
+    }
+
+    match self.can_reach_home(position, dice_value) {
+      true => {
+        // first we check a situation where we overjump home
+        if self.would_overjump_home(position, dice_value) {
+          return MoveResult::Error(String::from("Can't move - would overjump home."));
+        }
+
+        // offset/position in player's home column
+        let home_offset = self.get_home_offset(position, dice_value);
+        match self.is_available_home_field(home_offset) {
+          false => MoveResult::Error(String::from("Can't move - home field is already occupied.")),
+          true => {
+            self.jump_home(position, home_offset);
+            MoveResult::Success(String::from("Successfully moved a piece to home!"))
+          }
+        }
+      }
+      false => {
+        let new_position = self.get_new_position(position, dice_value);
+        match self.is_available_field(new_position) {
+          false => MoveResult::Error(String::from("Can't move - field is blocked.")),
+          true => {
+            self.jump(position, new_position);
+            MoveResult::Success(String::from("Moved to a new position."))
+          }
+        }
+      }
+    }
+  }
+  pub fn promote_piece(&mut self, dice_value: usize) -> MoveResult {
+    match self.can_promote_piece(dice_value) {
+      false => MoveResult::Error(String::from("You can't promote a piece.")),
+      true => {
+        let mut position = self.get_starting_position();
+        // self.clear_field(position);  // would remove enemy at starting position
+        position += self.rules.entry_steps(dice_value);
+        if !self.is_safe_square(position) {
+          self.clear_field(position);
+        }
+        let color = self.current_player;
+        let player = self.get_player_mut(color);
+        player.decrease_pieces_at_start();
+        self.fields.get_mut(position).add(color);
+        println!("promoted");
+        MoveResult::Success(String::from("Your piece has been promoted!"))
+      }
+    }
+  }
+
+  /// every move the current player can make with 'roll'
+  pub fn legal_moves(&self, roll: usize) -> Vec<Move> {
+    let mut moves = Vec::new();
+    let color = self.current_player;
+
+    if self.can_promote_piece(roll) {
+      let to = self.get_new_position(self.get_starting_position(), self.rules.entry_steps(roll));
+      moves.push(Move::Enter {
+        to,
+        capture: self.is_opponents_piece(to) && !self.is_safe_square(to),
+      });
+    }
+
+    for from in self.get_players_pieces_positions(color) {
+      if self.can_jump_to_finish(from, roll) {
+        moves.push(Move::Finish {
+          from,
+          from_home: false,
+        });
+      } else if self.can_jump_to_home(from, roll) {
+        moves.push(Move::EnterHome {
+          from,
+          to: self.get_home_offset(from, roll),
+        });
+      } else if self.can_jump(from, roll) {
+        moves.push(Move::Advance {
+          from,
+          to: self.get_new_position(from, roll),
+          capture: self.will_remove_enemy(from, roll),
+        });
+      }
+    }
+
+    for from in self.get_players_pieces_positions_in_home(color) {
+      if self.can_jump_from_home_to_finish(from, roll) {
+        moves.push(Move::Finish {
+          from,
+          from_home: true,
+        });
+      } else if self.can_jump_from_home(from, roll) {
+        moves.push(Move::AdvanceInHome {
+          from,
+          to: from + roll,
+        });
+      }
+    }
+
+    moves
+  }
+
+  // the roll a move is made with
+  pub fn move_roll(&self, player_move: &Move) -> usize {
+    let field_size = self.field_size();
+    match *player_move {
+      Move::Enter { to, .. } => {
+        let steps = (to + field_size - self.get_starting_position()) % field_size;
+        self.rules.entry_roll + steps
+      }
+      Move::Advance { from, to, .. } => (to + field_size - from) % field_size,
+      Move::EnterHome { from, to } => self.distance_from_home(from) + to,
+      Move::AdvanceInHome { from, to } => to - from,
+      Move::Finish {
+        from,
+        from_home: false,
+      } => self.distance_from_home(from) + self.get_home_size(),
+      Move::Finish {
+        from,
+        from_home: true,
+      } => self.get_home_size() - from,
+    }
+  }
+
+  /// makes a move generated by `legal_moves`
+  pub fn apply(&mut self, player_move: Move) -> MoveResult {
+    let roll = self.move_roll(&player_move);
+    if !self.legal_moves(roll).contains(&player_move) {
+      return MoveResult::Error(String::from("This move is not allowed."));
+    }
+    match player_move.origin() {
+      None => self.promote_piece(roll),
+      Some(origin) => self.execute_move(origin.position, roll, origin.is_home),
+    }
+  }
+
+  // returns whether a field specified by <position> is is occupied by a piece with <color>
+  pub fn is_players_piece(&self, position: usize, player_color: Color) -> bool {
+    self.fields.get(position).count(player_color) > 0
+  }
+
+  pub fn is_opponents_piece(&self, position: usize) -> bool {
+    self
+      .fields
+      .get(position)
+      .pawns()
+      .any(|(color, _count)| color != self.current_player)
+  }
+
+  pub fn is_current_players_piece(&self, position: usize) -> bool {
+    self.is_players_piece(position, self.current_player)
+  }
+
+  // // returns whether a field is empty
+  // pub fn is_field_empty(&self, position: usize) -> bool {
+  //   self.fields.get(position).is_none()
+  // }
+
+  pub fn get_player(&self, player_color: Color) -> &Player {
+    self
+      .players
+      .iter()
+      .find(|&player| player.color == player_color)
+      .unwrap() // TODO: remove unwrap
+  }
+
+  pub fn get_player_mut(&mut self, player_color: Color) -> &mut Player {
+    self
+      .players
+      .iter_mut()
+      .find(|player| player.color == player_color)
+      .unwrap() // TODO: remove unwrap
+  }
+
+  pub fn get_current_player(&self) -> &Player {
+    self.get_player(self.current_player)
+  }
+
+  pub fn get_current_player_mut(&mut self) -> &mut Player {
+    self.get_player_mut(self.current_player)
+  }
+
+  pub fn is_player_ai(&self, player_color: Color) -> bool {
+    self
+      .players
+      .iter()
+      .any(|player| player.is_bot && player.color == player_color)
+  }
+
+  pub fn is_current_player_ai(&self) -> bool {
+    self.is_player_ai(self.current_player)
+  }
+
+  pub fn get_player_by_id(&self, id: &str) -> Option<&Player> {
+    self.players.iter().find(|player| player.id == id)
+  }
+
+  // the player who created the game joins it first, bots only join once it starts
+  pub fn get_host(&self) -> Option<&Player> {
+    self.players.first()
+  }
+
+  pub fn is_host(&self, id: &str) -> bool {
+    self.get_host().is_some_and(|host| host.id == id)
+  }
+
+  pub fn is_color_taken(&self, color: Color) -> bool {
+    self.players.iter().any(|player| player.color == color)
+  }
+
+  // moves the player to the other color, a player who sat there gets the color of the moved one
+  pub fn seat_player(&mut self, from: Color, to: Color) {
+    for player in self.players.iter_mut() {
+      if player.color == from {
+        player.color = to;
+      } else if player.color == to {
+        player.color = from;
+      }
+    }
+  }
+
+  pub fn remove_player(&mut self, color: Color) {
+    self.players.retain(|player| player.color != color);
+  }
+
+  pub fn everyone_ready(&self) -> bool {
+    self
+      .players
+      .iter()
+      .all(|player| player.is_bot || player.ready)
+  }
+}
+
+impl Default for Game {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::models::position::Position;
+  use crate::models::rule_set::EntryRule;
+  use crate::utils::enums::MoveType;
+  use crate::utils::player::{get_available_positions, make_a_move, next_available_positions};
+
+  fn get_empty_game() -> Game {
+    get_empty_game_with_rules(RuleSet::default())
+  }
+
+  fn get_empty_game_with_rules(rules: RuleSet) -> Game {
+    let mut game = Game::with_rules(rules);
+    let mut players = Vec::new();
+    for color in get_colors() {
+      players.push(Player::new("".into(), "".into(), color, false, &game.rules));
+    }
+    game.players = players;
+    game
+  }
+
+  fn get_colors() -> Vec<Color> {
+    vec![Color::Yellow, Color::Blue, Color::Red, Color::Green]
+  }
+
+  fn get_all_players(game: &Game) -> Vec<&Player> {
+    get_colors()
+      .iter()
+      .map(|color| game.get_player(*color))
+      .collect::<Vec<&Player>>()
+  }
+
+  fn is_empty_fields(fields: &[Field]) -> bool {
+    fields.iter().all(|field| field.is_none())
+  }
+
+  fn is_empty_field(fields: &[Field], position: usize) -> bool {
+    matches!(fields.get(position), Some(None))
+  }
+
+  fn is_occupied_field_by(fields: &[Field], position: usize, color: Color) -> bool {
+    match fields.get(position) {
+      Some(Some(_color)) => *_color == color,
+      _ => false,
+    }
+  }
+
+  fn is_empty_board(fields: &Fields) -> bool {
+    fields.iter().all(|square| square.is_empty())
+  }
+
+  fn empty_squares_count(fields: &Fields) -> usize {
+    fields.iter().filter(|square| square.is_empty()).count()
+  }
+
+  fn is_empty_square(fields: &Fields, position: usize) -> bool {
+    fields.get(position).is_empty()
+  }
+
+  fn is_occupied_square_by(fields: &Fields, position: usize, color: Color) -> bool {
+    fields.get(position).count(color) > 0
+  }
+
+  fn set_field(fields: &mut Fields, position: usize, field: Field) {
+    fields.set(position, field)
+  }
+
+  fn set_blockade(fields: &mut Fields, position: usize, color: Color) {
+    fields.set(position, Some(color));
+    fields.get_mut(position).add(color);
+  }
+
+  fn print_game(game: &Game) {
+    println!();
+    for (i, field) in game.fields.iter().enumerate() {
+      println!("{}: {:?}", i, field);
+    }
+    println!();
+  }
+
+  #[test]
+  fn initial_promote() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    for player in get_all_players(&game) {
+      assert_eq!(player.pawns_at_start, 4);
+      assert_eq!(player.pawns_at_finish, 0);
+      assert!(is_empty_fields(&player.home));
+    }
+
+    // the starting player is Yellow
+    assert_eq!(game.current_player, Color::Yellow);
+    assert!(is_empty_board(&game.fields));
+    assert_eq!(game.get_starting_position(), 8); // Yellow player starts at 8
+
+    let dice_value = 9;
+
+    assert!(matches!(
+      game.promote_piece(dice_value),
+      MoveResult::Success(_)
+    ));
+
+    assert!(is_empty_square(&game.fields, game.get_starting_position()));
+    assert!(is_occupied_square_by(
+      &game.fields,
+      game.get_starting_position() + 3,
+      Color::Yellow
+    ));
+    assert!(is_occupied_square_by(&game.fields, 11, Color::Yellow));
+    assert!(!(is_empty_board(&game.fields)));
+    assert_eq!(game.get_current_player().pawns_at_start, 3);
+
+    game.update_current_player();
+
+    assert_eq!(game.current_player, Color::Blue)
+  }
+
+  #[test]
+  fn blocked_promotion() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    assert_eq!(game.get_starting_position(), 8);
+    set_blockade(&mut game.fields, 8 + 2, Color::Green);
+    assert_eq!(empty_squares_count(&game.fields), game.field_size() - 1);
+
+    // the blockade can't be passed
+    assert!(matches!(game.promote_piece(6 + 3), MoveResult::Error(_)));
+    // nor captured
+    assert!(matches!(game.promote_piece(6 + 2), MoveResult::Error(_)));
+
+    assert_eq!(game.get_current_player().pawns_at_start, 4);
+    assert!(is_empty_square(&game.fields, 8));
+    assert_eq!(game.fields.get(8 + 2).count(Color::Green), 2);
+    assert_eq!(empty_squares_count(&game.fields), game.field_size() - 1);
+
+    // pieces enter before the blockade
+    assert!(matches!(game.promote_piece(6 + 1), MoveResult::Success(_)));
+    assert!(is_occupied_square_by(&game.fields, 8 + 1, Color::Yellow));
+  }
+
+  #[test]
+  fn promotion_stacks_own_pieces() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    set_field(&mut game.fields, 8 + 3, Some(Color::Yellow));
+    assert!(matches!(game.promote_piece(6 + 3), MoveResult::Success(_)));
+
+    assert_eq!(game.get_current_player().pawns_at_start, 3);
+    assert_eq!(game.fields.get(8 + 3).count(Color::Yellow), 2);
+    assert_eq!(empty_squares_count(&game.fields), game.field_size() - 1);
+  }
+
+  #[test]
+  fn promotion_remove_opponent() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    let dice_value = 6 + 8;
+    let opponent_color = Color::Green;
+    let starting_pos = game.get_starting_position();
+    let field_size = game.fields.len();
+
+    game
+      .fields
+      .set(starting_pos + dice_value - 6, Some(opponent_color));
+
+    let opponent = game.get_player_mut(opponent_color);
+    opponent.pawns_at_start = 3;
+
+    print_game(&game);
+
+    let mut game = game.clone();
+    assert!(matches!(
+      game.promote_piece(dice_value),
+      MoveResult::Success(_)
+    ));
+
+    print_game(&game);
+
+    assert_eq!(game.get_current_player().pawns_at_start, 3);
+    assert_eq!(game.get_player(opponent_color).pawns_at_start, 4);
+    assert!(is_empty_square(&game.fields, 8));
+    assert!(is_occupied_square_by(&game.fields, 8 + 8, Color::Yellow));
+    assert_eq!(empty_squares_count(&game.fields), field_size - 1);
+  }
+
+  // #[test]
+  // fn blocked_move() {
+  //
+  // }
+
+  #[test]
+  fn remove_opponent() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    let dice_value = 5;
+    let opponent_color = Color::Green;
+    let starting_pos = 20;
+
+    game.fields.set(starting_pos, Some(game.current_player));
+    game
+      .fields
+      .set(starting_pos + dice_value, Some(opponent_color));
+    game
+      .fields
+      .set(starting_pos + dice_value + 1, Some(opponent_color));
+    game
+      .fields
+      .set(starting_pos + dice_value - 1, Some(opponent_color));
+
+    let opponent = game.get_player_mut(opponent_color);
+    opponent.pawns_at_start = 1;
+
+    let opponent = game.get_current_player_mut();
+    opponent.pawns_at_start = 3;
+
+    print_game(&game);
+
+    let mut game = game.clone();
+    assert!(matches!(
+      game.execute_move(starting_pos, dice_value, false),
+      MoveResult::Success(_)
+    ));
+
+    print_game(&game);
+
+    assert_eq!(game.get_current_player().pawns_at_start, 3);
+    assert_eq!(game.get_player(opponent_color).pawns_at_start, 2);
+    assert!(is_empty_square(&game.fields, starting_pos));
+    assert!(is_occupied_square_by(
+      &game.fields,
+      starting_pos + dice_value,
+      Color::Yellow
+    ));
+    assert!(is_occupied_square_by(
+      &game.fields,
+      starting_pos + dice_value + 1,
+      Color::Green
+    ));
+    assert!(is_occupied_square_by(
+      &game.fields,
+      starting_pos + dice_value - 1,
+      Color::Green
+    ));
+
+    // normally, Blue would follow
+    game.current_player = Color::Green;
+
+    let dice_value = 1;
+    let starting_pos = 24;
+
+    let mut game = game.clone();
+    assert!(matches!(
+      game.execute_move(starting_pos, dice_value, false),
+      MoveResult::Success(_)
+    ));
+
+    print_game(&game);
+
+    assert_eq!(game.get_player(Color::Green).pawns_at_start, 2);
+    // assert_eq!(game.get_player(Color::Yellow).pawns_at_start, 4);
+    assert!(is_empty_square(&game.fields, starting_pos));
+    assert!(is_occupied_square_by(
+      &game.fields,
+      starting_pos + dice_value,
+      Color::Green
+    ));
+    assert!(is_occupied_square_by(
+      &game.fields,
+      starting_pos + dice_value + 1,
+      Color::Green
+    ));
+  }
+
+  fn get_game_with_safe_squares() -> Game {
+    let rules = RuleSet::default();
+    get_empty_game_with_rules(RuleSet {
+      safe_squares: rules.standard_safe_squares(),
+      ..rules
+    })
+  }
+
+  #[test]
+  fn safe_square_is_shared() {
+    let mut game = get_game_with_safe_squares();
+    game.current_player = Color::Yellow;
+
+    let dice_value = 5;
+    let opponent_color = Color::Green;
+    let starting_pos = 11;
+    let safe_square = starting_pos + dice_value;
+    assert!(game.is_safe_square(safe_square));
+
+    game.fields.set(starting_pos, Some(game.current_player));
+    game.fields.set(safe_square, Some(opponent_color));
+    game.get_player_mut(opponent_color).pawns_at_start = 3;
+
+    assert!(game.can_jump(starting_pos, dice_value));
+    assert!(!game.will_remove_enemy(starting_pos, dice_value));
+    let (positions, _, _) = get_available_positions(&game, dice_value);
+    assert!(positions.contains(&starting_pos));
+
+    assert!(matches!(
+      game.execute_move(starting_pos, dice_value, false),
+      MoveResult::Success(_)
+    ));
+    // the opponent isn't captured, both pieces stand on the safe square
+    assert_eq!(game.get_player(opponent_color).pawns_at_start, 3);
+    assert!(is_occupied_square_by(
+      &game.fields,
+      safe_square,
+      opponent_color
+    ));
+    assert!(is_occupied_square_by(
+      &game.fields,
+      safe_square,
+      Color::Yellow
+    ));
+  }
+
+  #[test]
+  fn safe_square_allows_landing() {
+    let mut game = get_game_with_safe_squares();
+    game.current_player = Color::Yellow;
+
+    let dice_value = 5;
+    let starting_pos = 11;
+    game.fields.set(starting_pos, Some(game.current_player));
+
+    assert!(game.can_jump(starting_pos, dice_value));
+    assert!(matches!(
+      game.execute_move(starting_pos, dice_value, false),
+      MoveResult::Success(_)
+    ));
+    assert!(is_occupied_square_by(
+      &game.fields,
+      starting_pos + dice_value,
+      Color::Yellow
+    ));
+  }
+
+  #[test]
+  fn safe_square_is_shared_on_promotion() {
+    let mut game = get_game_with_safe_squares();
+    game.current_player = Color::Yellow;
+
+    // 6 + 6 + 2 enters on the star field 8 fields after the start
+    let dice_value = 6 + 8;
+    let star_square = game.get_starting_position() + 8;
+    assert!(game.is_safe_square(star_square));
+    game.fields.set(star_square, Some(Color::Green));
+    game.get_player_mut(Color::Green).pawns_at_start = 3;
+
+    assert!(game.can_promote_piece(dice_value));
+    assert!(matches!(
+      game.promote_piece(dice_value),
+      MoveResult::Success(_)
+    ));
+    assert_eq!(game.get_player(Color::Green).pawns_at_start, 3);
+    assert!(is_occupied_square_by(
+      &game.fields,
+      star_square,
+      Color::Green
+    ));
+    assert!(is_occupied_square_by(
+      &game.fields,
+      star_square,
+      Color::Yellow
+    ));
+  }
+
+  #[test]
+  fn stacking_own_pieces() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    let starting_pos = 20;
+    set_blockade(&mut game.fields, starting_pos, Color::Yellow);
+    game.fields.set(starting_pos + 3, Some(Color::Yellow));
+
+    // moving one piece of the stack leaves the other one behind
+    assert!(matches!(
+      game.execute_move(starting_pos, 3, false),
+      MoveResult::Success(_)
+    ));
+    assert_eq!(game.fields.get(starting_pos).count(Color::Yellow), 1);
+    assert_eq!(game.fields.get(starting_pos + 3).count(Color::Yellow), 2);
+    assert_eq!(
+      game.get_players_pieces_positions(Color::Yellow),
+      vec![20, 23]
+    );
+  }
+
+  #[test]
+  fn blockade_cannot_be_passed() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    let starting_pos = 20;
+    game.fields.set(starting_pos, Some(Color::Yellow));
+    set_blockade(&mut game.fields, starting_pos + 2, Color::Green);
+
+    assert!(game.can_jump(starting_pos, 1));
+    assert!(!game.can_jump(starting_pos, 2));
+    assert!(!game.can_jump(starting_pos, 5));
+    assert!(!game.will_remove_enemy(starting_pos, 2));
+    let (positions, _, _) = get_available_positions(&game, 5);
+    assert!(positions.is_empty());
+
+    assert!(matches!(
+      game.execute_move(starting_pos, 5, false),
+      MoveResult::Error(_)
+    ));
+    assert!(matches!(
+      game.execute_move(starting_pos, 2, false),
+      MoveResult::Error(_)
+    ));
+    assert!(is_occupied_square_by(
+      &game.fields,
+      starting_pos,
+      Color::Yellow
+    ));
+    assert_eq!(game.fields.get(starting_pos + 2).count(Color::Green), 2);
+
+    // the blockade doesn't stop its own pieces
+    game.current_player = Color::Green;
+    game.fields.set(starting_pos - 1, Some(Color::Green));
+    assert!(game.can_jump(starting_pos - 1, 5));
+  }
+
+  #[test]
+  fn blockade_in_front_of_home() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    // Yellow's last field before home is 6
+    let starting_pos = 4;
+    game.fields.set(starting_pos, Some(Color::Yellow));
+    set_blockade(&mut game.fields, 5, Color::Red);
+
+    assert!(!game.can_jump_to_home(starting_pos, 4));
+    assert!(!game.can_jump_to_finish(starting_pos, 3 + 5));
+    assert!(matches!(
+      game.execute_move(starting_pos, 4, false),
+      MoveResult::Error(_)
+    ));
+    assert!(is_empty_fields(&game.get_current_player().home));
+
+    // once it's broken up, the way is free again
+    game.fields.set(5, Some(Color::Red));
+    assert!(game.can_jump_to_home(starting_pos, 4));
+  }
+
+  #[test]
+  fn move_board_to_home_success() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    let dice_value = 1;
+    let starting_pos = 6; // right in front of home
+    game.fields.set(starting_pos, Some(game.current_player));
+
+    let mut game = game.clone();
+    assert!(matches!(
+      game.execute_move(starting_pos, dice_value, false),
+      MoveResult::Success(_)
+    ));
+
+    let player = game.get_current_player();
+    assert!(is_empty_square(&game.fields, starting_pos));
+    assert!(is_occupied_field_by(&player.home, 0, Color::Yellow));
+  }
+
+  #[test]
+  fn move_board_to_home_overjump() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    let dice_value = 9;
+    let starting_pos = 6; // right in front of home
+    game.fields.set(starting_pos, Some(game.current_player));
+
+    let mut game = game.clone();
+    assert!(matches!(
+      game.execute_move(starting_pos, dice_value, false),
+      MoveResult::Error(_)
+    ));
+
+    let player = game.get_current_player();
+    assert!(is_occupied_square_by(
+      &game.fields,
+      starting_pos,
+      Color::Yellow
+    ));
+    assert!(is_empty_fields(&player.home));
+  }
+
+  #[test]
+  fn move_board_to_home_blocked() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    let dice_value = 1;
+    let starting_pos = 6; // right in front of home
+    game.fields.set(starting_pos, Some(game.current_player));
+    let player = game.get_current_player_mut();
+    player.home[0] = Some(Color::Yellow);
+
+    let mut game = game.clone();
+    assert!(matches!(
+      game.execute_move(starting_pos, dice_value, false),
+      MoveResult::Error(_)
+    ));
+
+    let player = game.get_current_player();
+    assert!(is_occupied_square_by(
+      &game.fields,
+      starting_pos,
+      Color::Yellow
+    ));
+    assert!(is_occupied_field_by(&player.home, 0, Color::Yellow));
+  }
+
+  #[test]
+  fn move_home_to_home() {}
+
+  #[test]
+  fn move_home_to_home_blocked() {}
+
+  #[test]
+  fn move_board_to_finish() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    let dice_value = 6;
+    let starting_pos = 6; // right in front of home
+    game.fields.set(starting_pos, Some(game.current_player));
+
+    let mut game = game.clone();
+    assert!(matches!(
+      game.execute_move(starting_pos, dice_value, false),
+      MoveResult::Success(_)
+    ));
+
+    let player = game.get_current_player();
+    assert!(is_empty_square(&game.fields, starting_pos));
+    assert_eq!(player.pawns_at_finish, 1);
+  }
+
+  #[test]
+  fn move_home_to_finish() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    let dice_value = 3;
+    let starting_pos = 2;
+    let player = game.get_current_player_mut();
+    player.home[starting_pos] = Some(Color::Yellow);
+
+    let mut game = game.clone();
+    assert!(matches!(
+      game.execute_move(starting_pos, dice_value, true),
+      MoveResult::Success(_)
+    ));
+
+    let player = game.get_current_player();
+    assert!(is_empty_field(&player.home, starting_pos));
+    assert_eq!(player.pawns_at_finish, 1);
+  }
+
+  #[test]
+  fn house_rules() {
+    let rules = RuleSet {
+      field_count: 40,
+      start_offset: 2,
+      pieces_count: 2,
+      home_size: 3,
+      entry_roll: 5,
+      ..RuleSet::default()
+    };
+    let mut game = get_empty_game_with_rules(rules);
+    game.current_player = Color::Yellow;
+
+    assert_eq!(game.field_size(), 40);
+    assert_eq!(game.get_current_player().pawns_at_start, 2);
+    assert_eq!(game.get_current_player().home.len(), 3);
+    assert_eq!(game.get_starting_position(), 2);
+    assert_eq!(game.get_end_position(), 0);
+
+    // a 6 is not enough to enter, an entry roll followed by any roll is
+    assert!(!game.can_promote_piece(5));
+    assert!(matches!(game.promote_piece(5 + 2), MoveResult::Success(_)));
+    assert!(is_occupied_square_by(&game.fields, 4, Color::Yellow));
+
+    // the board wraps around after 40 fields, the home column has 3 fields
+    game.fields.set(4, None);
+    game.fields.set(0, Some(Color::Yellow));
+    assert!(game.can_jump_to_finish(0, 1 + 3));
+    game.get_current_player_mut().pawns_at_finish = 1;
+    assert!(matches!(
+      game.execute_move(0, 1 + 3, false),
+      MoveResult::Success(_)
+    ));
+    assert_eq!(game.check_winner(), Some(Color::Yellow));
+  }
+
+  fn board_move(position: usize) -> MoveType {
+    MoveType::Move(Position {
+      position,
+      is_home: false,
+    })
+  }
+
+  #[test]
+  fn standard_entry() {
+    let mut game = get_empty_game_with_rules(RuleSet {
+      entry: EntryRule::Standard,
+      ..RuleSet::default()
+    });
+    game.current_player = Color::Yellow;
+    let start = game.get_starting_position();
+
+    // a single 6 brings a piece out on the start field, the next die moves on its own
+    game.pending_moves = game.rules.pending_moves(&[6, 3]);
+    assert!(game.can_promote_piece(6));
+    assert!(matches!(
+      make_a_move(&mut game, MoveType::Promote, None),
+      MoveResult::Success(_)
+    ));
+    assert!(is_occupied_square_by(&game.fields, start, Color::Yellow));
+    assert_eq!(game.pending_moves, vec![3]);
+
+    let die_moves = next_available_positions(&mut game).unwrap();
+    assert_eq!(die_moves.len(), 1);
+    assert_eq!(die_moves[0].positions_on_board, vec![start]);
+    assert!(!die_moves[0].can_promote);
+    assert!(matches!(
+      make_a_move(&mut game, board_move(start), None),
+      MoveResult::Success(_)
+    ));
+    assert!(is_occupied_square_by(&game.fields, start + 3, Color::Yellow));
+    assert!(next_available_positions(&mut game).is_none());
+
+    // dice that can't be played are forfeited
+    game.fields.set(start + 3, None);
+    game.get_current_player_mut().pawns_at_start = 4;
+    game.pending_moves = vec![4];
+    assert!(next_available_positions(&mut game).is_none());
+    assert!(game.pending_moves.is_empty());
+  }
+
+  #[test]
+  fn separate_dice() {
+    let mut game = get_empty_game_with_rules(RuleSet {
+      entry: EntryRule::Standard,
+      separate_dice: true,
+      ..RuleSet::default()
+    });
+    game.current_player = Color::Yellow;
+    let start = game.get_starting_position();
+    game.fields.set(start + 10, Some(Color::Yellow));
+    game.get_current_player_mut().pawns_at_start = 3;
+
+    // the 4 can't be played before the 6, but it isn't forfeited while the 6 can be
+    game.pending_moves = vec![4, 6];
+    game.fields.set(start + 14, Some(Color::Green));
+    game.fields.get_mut(start + 14).add(Color::Green);
+    let die_moves = next_available_positions(&mut game).unwrap();
+    assert_eq!(die_moves[0].dice_value, 4);
+    assert!(die_moves[0].is_empty());
+    assert!(die_moves[1].can_promote);
+
+    // the pieces are moved by the dice one by one, not by their sum
+    assert!(matches!(
+      make_a_move(&mut game, MoveType::Promote, None),
+      MoveResult::Success(_)
+    ));
+    assert_eq!(game.pending_moves, vec![4]);
+    let die_moves = next_available_positions(&mut game).unwrap();
+    assert_eq!(die_moves[0].positions_on_board, vec![start]);
+    assert!(matches!(
+      make_a_move(&mut game, board_move(start), None),
+      MoveResult::Success(_)
+    ));
+    assert!(is_occupied_square_by(&game.fields, start + 4, Color::Yellow));
+    assert!(is_occupied_square_by(&game.fields, start + 10, Color::Yellow));
+    assert!(game.pending_moves.is_empty());
+    assert!(matches!(
+      make_a_move(&mut game, board_move(start + 4), None),
+      MoveResult::Error(_)
+    ));
+  }
+
+  #[test]
+  fn chosen_die() {
+    let mut game = get_empty_game_with_rules(RuleSet {
+      entry: EntryRule::Standard,
+      separate_dice: true,
+      ..RuleSet::default()
+    });
+    game.current_player = Color::Yellow;
+    let start = game.get_starting_position();
+    game.fields.set(start, Some(Color::Yellow));
+    game.fields.set(start + 20, Some(Color::Yellow));
+    game.get_current_player_mut().pawns_at_start = 2;
+
+    // the 4 goes to the first piece and the 6 to the second one, although the 6 came first
+    game.pending_moves = vec![6, 4];
+    assert!(matches!(
+      make_a_move(&mut game, board_move(start), Some(3)),
+      MoveResult::Error(_)
+    ));
+    assert!(matches!(
+      make_a_move(&mut game, board_move(start), Some(4)),
+      MoveResult::Success(_)
+    ));
+    assert_eq!(game.pending_moves, vec![6]);
+    assert!(matches!(
+      make_a_move(&mut game, board_move(start + 20), Some(6)),
+      MoveResult::Success(_)
+    ));
+    assert!(is_occupied_square_by(&game.fields, start + 4, Color::Yellow));
+    assert!(is_occupied_square_by(&game.fields, start + 26, Color::Yellow));
+  }
+
+  // small generator, so the tests don't need rand
+  fn next_random(seed: &mut u64) -> usize {
+    *seed = seed
+      .wrapping_mul(6364136223846793005)
+      .wrapping_add(1442695040888963407);
+    (*seed >> 33) as usize
+  }
+
+  fn opponents_at_start(game: &Game) -> usize {
+    game
+      .players
+      .iter()
+      .filter(|player| player.color != game.current_player)
+      .map(|player| player.pawns_at_start)
+      .sum()
+  }
+
+  #[test]
+  fn legal_moves_apply() {
+    let standard = RuleSet::default();
+    let house_rules = RuleSet {
+      entry: EntryRule::Standard,
+      safe_squares: standard.standard_safe_squares(),
+      ..RuleSet::default()
+    };
+    let mut seed = 42;
+    let mut generated = 0;
+    for rules in [standard, house_rules] {
+      for _ in 0..200 {
+        // pieces of every color scattered over the board and the home columns
+        let mut game = get_empty_game_with_rules(rules.clone());
+        for color in get_colors() {
+          for _ in 0..rules.pieces_count {
+            match next_random(&mut seed) % 3 {
+              0 => {}
+              1 => {
+                let position = next_random(&mut seed) % rules.field_count;
+                game.fields.get_mut(position).add(color);
+                game.get_player_mut(color).pawns_at_start -= 1;
+              }
+              _ => {
+                let offset = next_random(&mut seed) % rules.home_size;
+                let player = game.get_player_mut(color);
+                if player.home[offset].is_none() {
+                  player.home[offset] = Some(color);
+                  player.pawns_at_start -= 1;
+                }
+              }
+            }
+          }
+        }
+        game.current_player = get_colors()[next_random(&mut seed) % 4];
+
+        for roll in 1..=18 {
+          for legal_move in game.legal_moves(roll) {
+            generated += 1;
+            let mut after = game.clone();
+            assert_eq!(after.move_roll(&legal_move), roll);
+            let result = after.apply(legal_move);
+            assert!(
+              !matches!(result, MoveResult::Error(_)),
+              "{:?} with {} failed: {:?}",
+              legal_move,
+              roll,
+              result
+            );
+            if legal_move.is_capture() {
+              assert!(opponents_at_start(&after) > opponents_at_start(&game));
+            }
+          }
+        }
+      }
+    }
+    assert!(generated > 1000);
+  }
+
+  #[test]
+  fn illegal_move_is_rejected() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+    game.fields.set(10, Some(Color::Yellow));
+
+    assert_eq!(
+      game.legal_moves(3),
+      vec![Move::Advance {
+        from: 10,
+        to: 13,
+        capture: false
+      }]
+    );
+    let not_a_piece = Move::Advance {
+      from: 11,
+      to: 14,
+      capture: false,
+    };
+    assert!(matches!(game.apply(not_a_piece), MoveResult::Error(_)));
+    assert!(matches!(
+      game.apply(Move::Enter {
+        to: game.get_starting_position(),
+        capture: false
+      }),
+      MoveResult::Error(_)
+    ));
+    assert!(is_occupied_square_by(&game.fields, 10, Color::Yellow));
+  }
+
+  #[test]
+  fn move_to_finish_check_winner() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    let dice_value = 3;
+    let starting_pos = 2;
+    let player = game.get_current_player_mut();
+    player.home[starting_pos] = Some(Color::Yellow);
+    player.pawns_at_finish = 3;
+
+    let mut game = game.clone();
+    assert!(!matches!(
+      game.execute_move(starting_pos, dice_value, true),
+      MoveResult::Error(_)
+    ));
+
+    let winner = game.check_winner();
+    assert!(winner.is_some());
+    assert_eq!(winner.unwrap(), Color::Yellow);
+  }
+
+  #[test]
+  fn invalid_moves() {}
+
+  fn compare_vectors(a: &[usize], b: &[usize]) {
+    assert_eq!(a.len(), b.len());
+    for n in a {
+      assert!(b.contains(n));
+    }
+  }
+
+  #[test]
+  fn available_positions() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    // Yellow starts at position 8, the field in front of home is at position 6
+    game.fields.set(9, Some(Color::Yellow));
+    game.fields.set(12, Some(Color::Yellow));
+    game.fields.set(6, Some(Color::Yellow));
+
+    let yellow_player = game.get_player_mut(Color::Yellow);
+    yellow_player.home[2] = Some(Color::Yellow);
+    yellow_player.pawns_at_start = 0;
+
+    let dice_value = 1;
+    let (actual_board_pos, actual_home_pos, actual_can_promote) =
+      get_available_positions(&game, dice_value);
+    let (expected_board_pos, expected_home_pos, expected_can_promote): (
+      Vec<usize>,
+      Vec<usize>,
+      bool,
+    ) = (vec![6, 9, 12], vec![2], false);
+    compare_vectors(&actual_board_pos, &expected_board_pos);
+    compare_vectors(&actual_home_pos, &expected_home_pos);
+    assert_eq!(actual_can_promote, expected_can_promote);
+
+    let dice_value = 3;
+    let (actual_board_pos, actual_home_pos, actual_can_promote) =
+      get_available_positions(&game, dice_value);
+    let (expected_board_pos, expected_home_pos, expected_can_promote): (
+      Vec<usize>,
+      Vec<usize>,
+      bool,
+    ) = (vec![9, 12], vec![2], false);
+    compare_vectors(&actual_board_pos, &expected_board_pos);
+    compare_vectors(&actual_home_pos, &expected_home_pos);
+    assert_eq!(actual_can_promote, expected_can_promote);
+
+    let dice_value = 4;
+    let (actual_board_pos, actual_home_pos, actual_can_promote) =
+      get_available_positions(&game, dice_value);
+    let (expected_board_pos, expected_home_pos, expected_can_promote): (
+      Vec<usize>,
+      Vec<usize>,
+      bool,
+    ) = (vec![6, 9, 12], vec![], false);
+    compare_vectors(&actual_board_pos, &expected_board_pos);
+    compare_vectors(&actual_home_pos, &expected_home_pos);
+    assert_eq!(actual_can_promote, expected_can_promote);
+
+    let dice_value = 6;
+    let (actual_board_pos, actual_home_pos, actual_can_promote) =
+      get_available_positions(&game, dice_value);
+    let (expected_board_pos, expected_home_pos, expected_can_promote): (
+      Vec<usize>,
+      Vec<usize>,
+      bool,
+    ) = (vec![6, 9, 12], vec![], false);
+    compare_vectors(&actual_board_pos, &expected_board_pos);
+    compare_vectors(&actual_home_pos, &expected_home_pos);
+    assert_eq!(actual_can_promote, expected_can_promote);
+  }
+
+  #[test]
+  fn available_positions_promote() {
+    let mut game = get_empty_game();
+    game.current_player = Color::Yellow;
+
+    // Yellow starts at position 8
+    game.fields.set(9, Some(Color::Yellow));
+
+    let yellow_player = game.get_player_mut(Color::Yellow);
+    yellow_player.pawns_at_start = 3;
+
+    let dice_value = 11;
+    let (actual_board_pos, actual_home_pos, actual_can_promote) =
+      get_available_positions(&game, dice_value);
+    let (expected_board_pos, expected_home_pos, expected_can_promote): (
+      Vec<usize>,
+      Vec<usize>,
+      bool,
+    ) = (vec![9], vec![], true);
+    compare_vectors(&actual_board_pos, &expected_board_pos);
+    compare_vectors(&actual_home_pos, &expected_home_pos);
+    assert_eq!(actual_can_promote, expected_can_promote);
+
+    // stacked onto our piece
+    let dice_value = 7;
+    let (actual_board_pos, actual_home_pos, actual_can_promote) =
+      get_available_positions(&game, dice_value);
+    let (expected_board_pos, expected_home_pos, expected_can_promote): (
+      Vec<usize>,
+      Vec<usize>,
+      bool,
+    ) = (vec![9], vec![], true);
+    compare_vectors(&actual_board_pos, &expected_board_pos);
+    compare_vectors(&actual_home_pos, &expected_home_pos);
+    assert_eq!(actual_can_promote, expected_can_promote);
+
+    // // if there is an opponent piece, we don't get blocked (promotion)
+    game.fields.set(9, Some(Color::Green));
+    let dice_value = 7;
+    let (actual_board_pos, actual_home_pos, actual_can_promote) =
+      get_available_positions(&game, dice_value);
+    let (expected_board_pos, expected_home_pos, expected_can_promote): (
+      Vec<usize>,
+      Vec<usize>,
+      bool,
+    ) = (vec![], vec![], true);
+    compare_vectors(&actual_board_pos, &expected_board_pos);
+    compare_vectors(&actual_home_pos, &expected_home_pos);
+    assert_eq!(actual_can_promote, expected_can_promote);
+
+    // if there is an opponent piece, we don't get blocked (on board)
+    game.fields.set(9, Some(Color::Yellow));
+    game.fields.set(12, Some(Color::Green));
+    let dice_value = 3;
+    let (actual_board_pos, actual_home_pos, actual_can_promote) =
+      get_available_positions(&game, dice_value);
+    let (expected_board_pos, expected_home_pos, expected_can_promote): (
+      Vec<usize>,
+      Vec<usize>,
+      bool,
+    ) = (vec![9], vec![], false);
+    compare_vectors(&actual_board_pos, &expected_board_pos);
+    compare_vectors(&actual_home_pos, &expected_home_pos);
+    assert_eq!(actual_can_promote, expected_can_promote);
+  }
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+use super::{color::Color, game::Game, player::Player, square::Square};
+use crate::utils::enums::RoundPhase;
+
+// What changed in a game between two updates, everything else stays as it was
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct GameDiff {
+  // index and new content of every square that changed
+  pub fields: Vec<(usize, Square)>,
+  // the players that changed, as they are now
+  pub players: Vec<Player>,
+  pub current_player: Option<Color>,
+  pub round_phase: Option<RoundPhase>,
+  pub dice_throws: Option<Vec<usize>>,
+  pub pending_moves: Option<Vec<usize>>,
+  pub dice_rolls: Option<u64>,
+}
+
+// the new value if it differs from the old one
+fn changed<T: Clone + PartialEq>(old: &T, new: &T) -> Option<T> {
+  (old != new).then(|| new.clone())
+}
+
+impl GameDiff {
+  // None when the games differ in more than a diff holds (start, winner, rules, seats), the whole game has to be sent then
+  pub fn between(old: &Game, new: &Game) -> Option<GameDiff> {
+    let same_seats = old.players.len() == new.players.len()
+      && old
+        .players
+        .iter()
+        .zip(&new.players)
+        .all(|(old, new)| old.color == new.color);
+    if old.started != new.started
+      || old.winner != new.winner
+      || old.rules != new.rules
+      || old.dice_seed != new.dice_seed
+      || old.fields.len() != new.fields.len()
+      || !same_seats
+    {
+      return None;
+    }
+
+    Some(GameDiff {
+      fields: old
+        .fields
+        .iter()
+        .zip(new.fields.iter())
+        .enumerate()
+        .filter(|(_, (old, new))| old != new)
+        .map(|(index, (_, new))| (index, new.clone()))
+        .collect(),
+      players: old
+        .players
+        .iter()
+        .zip(&new.players)
+        .filter(|(old, new)| old != new)
+        .map(|(_, new)| new.clone())
+        .collect(),
+      current_player: changed(&old.current_player, &new.current_player),
+      round_phase: changed(&old.round_phase, &new.round_phase),
+      dice_throws: changed(&old.dice_throws, &new.dice_throws),
+      pending_moves: changed(&old.pending_moves, &new.pending_moves),
+      dice_rolls: changed(&old.dice_rolls, &new.dice_rolls),
+    })
+  }
+
+  pub fn is_empty(&self) -> bool {
+    *self == GameDiff::default()
+  }
+}
+
+impl Game {
+  pub fn apply_diff(&mut self, diff: &GameDiff) {
+    for (index, square) in &diff.fields {
+      *self.fields.get_mut(*index) = square.clone();
+    }
+    for player in &diff.players {
+      *self.get_player_mut(player.color) = player.clone();
+    }
+    if let Some(current_player) = diff.current_player {
+      self.current_player = current_player;
+    }
+    if let Some(round_phase) = diff.round_phase {
+      self.round_phase = round_phase;
+    }
+    if let Some(dice_throws) = &diff.dice_throws {
+      self.dice_throws = dice_throws.clone();
+    }
+    if let Some(pending_moves) = &diff.pending_moves {
+      self.pending_moves = pending_moves.clone();
+    }
+    if let Some(dice_rolls) = diff.dice_rolls {
+      self.dice_rolls = dice_rolls;
+    }
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::utils::player::play_round;
+
+  fn started_game() -> Game {
+    let mut game = Game::new();
+    game.players = Color::ordered()
+      .into_iter()
+      .map(|color| {
+        Player::new(
+          color.to_string(),
+          color.to_string(),
+          color,
+          false,
+          &game.rules,
+        )
+      })
+      .collect();
+    game.started = true;
+    game
+  }
+
+  #[test]
+  fn diff_rebuilds_the_new_game() {
+    let old = started_game();
+    let mut new = old.clone();
+    new.dice_rolls = 2;
+    new.add_roll(6);
+    new.add_roll(2);
+    let entered = new.legal_moves(8)[0];
+    play_round(&mut new, entered);
+
+    let diff = GameDiff::between(&old, &new).unwrap();
+    assert_eq!(diff.fields.len(), 1);
+    assert_eq!(diff.players.len(), 1);
+    assert_eq!(diff.current_player, Some(Color::Yellow));
+    assert_eq!(diff.round_phase, None);
+
+    let mut game = old;
+    game.apply_diff(&diff);
+    assert_eq!(game, new);
+  }
+
+  #[test]
+  fn same_games_have_an_empty_diff() {
+    let game = started_game();
+    assert!(GameDiff::between(&game, &game).unwrap().is_empty());
+  }
+
+  #[test]
+  fn start_and_winner_need_the_whole_game() {
+    let game = started_game();
+    let mut lobby = game.clone();
+    lobby.started = false;
+    assert_eq!(GameDiff::between(&lobby, &game), None);
+
+    let mut won = game.clone();
+    won.winner = Some(Color::Green);
+    assert_eq!(GameDiff::between(&game, &won), None);
+  }
+}
@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+
+use super::{color::Color, game::Game, player::Player, rule_set::RuleSet};
+use crate::utils::{enums::Move, player::play_round};
+
+// Everything that changes a game, the game can be rebuilt by applying its events in order
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum GameEvent {
+  Created {
+    rules: RuleSet,
+    dice_seed: u64,
+    #[serde(default)]
+    join_code: Option<String>,
+  },
+  Joined(Player),
+  // the host sent the player of the color out of the lobby
+  Kicked(Color),
+  // the player of 'from' took the seat of 'to', swapping seats with whoever sat there
+  Seated { from: Color, to: Color },
+  Ready(Color, bool),
+  // players of the game once the free seats were taken by bots
+  Started(Vec<Player>),
+  Rolled(usize),
+  Moved(Move),
+  // the rolls forfeited the turn or there was no move to make
+  Skipped,
+  // a move was taken back, the game is as it was after the first 'events' events of the log
+  Undone { events: u64 },
+  // the player was disconnected for too long, a bot plays for them
+  Left(Color),
+  // the player reconnected and got the seat back from the bot
+  Returned(Color),
+}
+
+// Event as it is stored in the log of a game
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LoggedEvent {
+  // position in the log of the game, starting at 0
+  pub sequence: u64,
+  // milliseconds since the unix epoch
+  pub timestamp: i64,
+  pub event: GameEvent,
+}
+
+// the events that still count, without the ones that were taken back and the Undone events themselves
+pub fn effective_events<'a>(events: impl IntoIterator<Item = &'a GameEvent>) -> Vec<&'a GameEvent> {
+  let events = events.into_iter().collect::<Vec<_>>();
+  let mut kept = vec![];
+  for (index, event) in events.iter().enumerate() {
+    match event {
+      GameEvent::Undone { events: undone } => {
+        let undone = (*undone as usize).min(index);
+        kept = effective_events(events[..undone].iter().copied());
+      }
+      event => kept.push(*event),
+    }
+  }
+  kept
+}
+
+impl Game {
+  pub fn from_events<'a>(events: impl IntoIterator<Item = &'a GameEvent>) -> Game {
+    effective_events(events)
+      .into_iter()
+      .fold(Game::new(), |mut game, event| {
+        game.apply_event(event);
+        game
+      })
+  }
+
+  pub fn apply_event(&mut self, event: &GameEvent) {
+    match event {
+      GameEvent::Created {
+        rules,
+        dice_seed,
+        join_code,
+      } => {
+        *self = Game::with_rules(rules.clone());
+        self.dice_seed = *dice_seed;
+        self.join_code = join_code.clone();
+      }
+      GameEvent::Joined(player) => self.players.push(player.clone()),
+      GameEvent::Kicked(color) => self.remove_player(*color),
+      GameEvent::Seated { from, to } => self.seat_player(*from, *to),
+      GameEvent::Ready(color, ready) => self.get_player_mut(*color).ready = *ready,
+      GameEvent::Started(players) => {
+        self.started = true;
+        self.players = players.clone();
+      }
+      GameEvent::Rolled(roll) => {
+        self.dice_rolls += 1;
+        self.add_roll(*roll);
+      }
+      GameEvent::Moved(player_move) => {
+        play_round(self, *player_move);
+      }
+      GameEvent::Skipped => self.skip_player(),
+      // the event doesn't hold the game it goes back to, from_events resolves it with the earlier events
+      GameEvent::Undone { .. } => {}
+      GameEvent::Left(color) => self.get_player_mut(*color).set_away(true),
+      GameEvent::Returned(color) => self.get_player_mut(*color).set_away(false),
+    }
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::models::color::Color;
+  use crate::utils::enums::RoundPhase;
+
+  fn players(rules: &RuleSet) -> Vec<Player> {
+    Color::ordered()
+      .into_iter()
+      .map(|color| Player::new(color.to_string(), color.to_string(), color, false, rules))
+      .collect()
+  }
+
+  #[test]
+  fn fold_events() {
+    let rules = RuleSet::default();
+    let players = players(&rules);
+    let events = vec![
+      GameEvent::Created {
+        rules: rules.clone(),
+        dice_seed: 7,
+        join_code: None,
+      },
+      GameEvent::Joined(players[0].clone()),
+      GameEvent::Started(players.clone()),
+      GameEvent::Rolled(6),
+      GameEvent::Rolled(2),
+    ];
+
+    let game = Game::from_events(&events);
+    assert!(game.started);
+    assert_eq!(game.dice_seed, 7);
+    assert_eq!(game.dice_rolls, 2);
+    assert_eq!(game.players, players);
+    assert_eq!(game.round_phase, RoundPhase::Moving);
+    assert_eq!(game.pending_moves, vec![8]);
+
+    let entered = game.legal_moves(8)[0];
+    let mut events = events;
+    events.push(GameEvent::Moved(entered));
+    let game = Game::from_events(&events);
+    assert_eq!(game.get_player(Color::Green).pawns_at_start, 3);
+    assert_eq!(game.current_player, Color::Yellow);
+    assert_eq!(game.round_phase, RoundPhase::Rolling);
+    assert!(game.dice_throws.is_empty());
+
+    events.push(GameEvent::Rolled(3));
+    events.push(GameEvent::Skipped);
+    let game = Game::from_events(&events);
+    assert_eq!(game.current_player, Color::Blue);
+    assert!(game.pending_moves.is_empty());
+
+    events.push(GameEvent::Left(Color::Blue));
+    let game = Game::from_events(&events);
+    assert!(game.is_current_player_ai());
+    assert!(game.get_player(Color::Blue).away);
+    events.push(GameEvent::Returned(Color::Blue));
+    let game = Game::from_events(&events);
+    assert_eq!(game.get_player(Color::Blue), &players[2]);
+  }
+
+  #[test]
+  fn lobby_events() {
+    let rules = RuleSet::default();
+    let players = players(&rules);
+    let mut events = vec![GameEvent::Created {
+      rules: rules.clone(),
+      dice_seed: 7,
+      join_code: None,
+    }];
+    events.extend(players[..3].iter().cloned().map(GameEvent::Joined));
+    events.push(GameEvent::Kicked(Color::Yellow));
+    events.push(GameEvent::Seated {
+      from: Color::Blue,
+      to: Color::Green,
+    });
+    events.push(GameEvent::Ready(Color::Green, true));
+
+    let game = Game::from_events(&events);
+    assert_eq!(game.players.len(), 2);
+    assert!(game.is_host(&players[0].id));
+    assert_eq!(game.get_host().unwrap().color, Color::Blue);
+    assert_eq!(game.get_player(Color::Green).id, players[2].id);
+    assert!(game.get_player(Color::Green).ready);
+    assert!(!game.is_color_taken(Color::Yellow));
+    assert!(!game.everyone_ready());
+  }
+
+  #[test]
+  fn undone_events_are_dropped() {
+    let rules = RuleSet::default();
+    let players = players(&rules);
+    let events = vec![
+      GameEvent::Created {
+        rules: rules.clone(),
+        dice_seed: 7,
+        join_code: None,
+      },
+      GameEvent::Started(players),
+      GameEvent::Rolled(6),
+      GameEvent::Rolled(2),
+      GameEvent::Moved(Move::Enter {
+        to: 8,
+        capture: false,
+      }),
+      GameEvent::Undone { events: 4 },
+      GameEvent::Rolled(3),
+    ];
+    let before_move = Game::from_events(&events[..4]);
+    assert_eq!(Game::from_events(&events[..6]), before_move);
+    assert_eq!(effective_events(&events).len(), 5);
+
+    // taking back a move again goes further back, past the earlier undo
+    let mut events = events;
+    events.push(GameEvent::Undone { events: 3 });
+    assert_eq!(Game::from_events(&events), Game::from_events(&events[..3]));
+    assert_eq!(effective_events(&events).len(), 3);
+  }
+
+  #[test]
+  fn event_round_trip() {
+    let event = LoggedEvent {
+      sequence: 3,
+      timestamp: 1_650_000_000_000,
+      event: GameEvent::Moved(Move::Advance {
+        from: 10,
+        to: 14,
+        capture: true,
+      }),
+    };
+    let json = serde_json::to_string(&event).unwrap();
+    assert_eq!(serde_json::from_str::<LoggedEvent>(&json).unwrap(), event);
+  }
+}
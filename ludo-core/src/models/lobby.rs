@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use super::{color::Color, rule_set::RuleSet};
+
+// A seat of the room as the lobby shows it, the id of the player stays on the server
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LobbyPlayer {
+  pub name: String,
+  pub color: Color,
+  pub is_bot: bool,
+  // has an open websocket, bots are always connected
+  pub connected: bool,
+  pub is_host: bool,
+  pub ready: bool,
+}
+
+// Answer to joining a game, the token is the credential the websocket is opened with,
+// the id is public and identifies the player in the game
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct JoinResponse {
+  pub player_id: String,
+  pub token: String,
+}
+
+// A game that waits for players, as the lobby listing shows it
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LobbySummary {
+  pub id: String,
+  pub host: String,
+  pub players: usize,
+  pub free_seats: usize,
+  pub rules: RuleSet,
+  // seconds since the game was created
+  pub age: u64,
+}
+
+// A page of the lobby listing, 'next_cursor' asks for the page after it and is None on the last one
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LobbyPage {
+  pub lobbies: Vec<LobbySummary>,
+  pub next_cursor: Option<String>,
+}
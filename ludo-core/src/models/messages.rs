@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+  color::Color, die_moves::DieMoves, game::Game, game_diff::GameDiff, lobby::LobbyPlayer,
+};
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ClientMessage {
+  ThrowDice,
+  MoveFigure(usize, Option<Color>, Option<usize>), // the last value picks the die to move with, None takes the first one that fits
+  PromotePiece(Option<usize>), // shouldn't need to pass color, since server should has attr current_player
+  StartGame,
+  RequestUndo, // takes back the last move of the sender, once every other human agreed
+  AnswerUndo(bool),
+  RequestSnapshot, // the client missed a message, the server answers with a GameUpdate
+  ChooseColor(Color), // takes the free seat of the color while in the lobby
+  Ready(bool),     // the host can only start once every human is ready
+  KickPlayer(Color), // host only, sends the player of the color out of the lobby
+  SeatPlayer(Color, Color), // host only, moves the player of the first color to the second one
+  Authenticate(String), // the first message of a websocket opened without the token in a header
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "command", content = "payload")]
+pub enum ServerMessage {
+  DiceValue(usize, bool, Option<u64>), // response to ThrowDice - bool: whether player should throw again, u64: milliseconds left of the turn
+  AvailablePositions(Vec<DieMoves>), // position of pieces that can make a valid move, for every die that is still to be played
+  SkipPlayer,
+  PiecePromoted, // response to PromotePiece - maybe use MoveSuccessful("Piece promoted") instead ?
+  GameUpdate(Game, Option<u64>), // milliseconds the current player has left, None while no timer runs
+  GameDiff(GameDiff, Option<u64>), // what changed since the last GameUpdate or GameDiff of the room
+  PlayerCountChange(usize),      // players of the room with an open websocket
+  SpectatorCountChange(usize),   // spectators of the room, sent whenever one comes or goes
+  LobbyState(Vec<LobbyPlayer>), // everyone who joined the room, sent whenever someone connects or disconnects
+  GameStarted(Game),
+  Error(String),
+  ConnectResponse(Game, Color),
+  Spectating(Game), // the answer to a spectator connecting, the messages of the room follow
+  UndoRequested(Color), // the player of the color asks to take back their last move, answer with AnswerUndo
+  UndoResult(bool),     // whether the move was taken back
+  PlayerLeft(Color),    // the player was disconnected for too long, a bot plays for them now
+  PlayerReturned(Color), // the player reconnected and plays again
+  Kicked,               // the host sent the player out of the lobby
+}
+
+// Every message goes over the websocket with the number of the last message sent to the whole room,
+// the numbers of room messages follow each other, so the client sees when it missed one
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SequencedMessage {
+  pub sequence: u64,
+  pub message: ServerMessage,
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::models::player::Player;
+
+  fn get_game() -> Game {
+    let mut game = Game::new();
+    game.players = Color::ordered()
+      .into_iter()
+      .map(|color| {
+        Player::new(
+          color.to_string(),
+          color.to_string(),
+          color,
+          false,
+          &game.rules,
+        )
+      })
+      .collect();
+    game
+  }
+
+  fn round_trip<T>(message: &T) -> T
+  where
+    T: Serialize + serde::de::DeserializeOwned,
+  {
+    let json = serde_json::to_string(message).unwrap();
+    serde_json::from_str(json.as_str()).unwrap()
+  }
+
+  #[test]
+  fn client_message_round_trip() {
+    let messages = vec![
+      ClientMessage::ThrowDice,
+      ClientMessage::MoveFigure(12, None, None),
+      ClientMessage::MoveFigure(3, Some(Color::Blue), Some(4)),
+      ClientMessage::PromotePiece(None),
+      ClientMessage::PromotePiece(Some(6)),
+      ClientMessage::StartGame,
+      ClientMessage::RequestUndo,
+      ClientMessage::AnswerUndo(false),
+      ClientMessage::RequestSnapshot,
+      ClientMessage::ChooseColor(Color::Red),
+      ClientMessage::Ready(true),
+      ClientMessage::KickPlayer(Color::Blue),
+      ClientMessage::SeatPlayer(Color::Yellow, Color::Green),
+      ClientMessage::Authenticate("token".into()),
+    ];
+    for message in messages {
+      assert_eq!(round_trip(&message), message);
+    }
+  }
+
+  #[test]
+  fn server_message_round_trip() {
+    let messages = vec![
+      ServerMessage::DiceValue(6, true, Some(59_000)),
+      ServerMessage::AvailablePositions(vec![
+        DieMoves {
+          dice_value: 6,
+          positions_on_board: vec![1, 2],
+          positions_in_home: vec![0],
+          can_promote: true,
+        },
+        DieMoves {
+          dice_value: 4,
+          positions_on_board: vec![],
+          positions_in_home: vec![],
+          can_promote: false,
+        },
+      ]),
+      ServerMessage::SkipPlayer,
+      ServerMessage::PiecePromoted,
+      ServerMessage::GameUpdate(get_game(), None),
+      ServerMessage::GameDiff(
+        GameDiff {
+          current_player: Some(Color::Red),
+          dice_throws: Some(vec![]),
+          ..GameDiff::default()
+        },
+        Some(1000),
+      ),
+      ServerMessage::PlayerCountChange(3),
+      ServerMessage::LobbyState(vec![LobbyPlayer {
+        name: "alice".into(),
+        color: Color::Green,
+        is_bot: false,
+        connected: true,
+        is_host: true,
+        ready: false,
+      }]),
+      ServerMessage::GameStarted(get_game()),
+      ServerMessage::Error("error".into()),
+      ServerMessage::ConnectResponse(get_game(), Color::Yellow),
+      ServerMessage::UndoRequested(Color::Red),
+      ServerMessage::UndoResult(true),
+      ServerMessage::PlayerLeft(Color::Blue),
+      ServerMessage::PlayerReturned(Color::Blue),
+      ServerMessage::Kicked,
+      ServerMessage::SpectatorCountChange(2),
+      ServerMessage::Spectating(get_game()),
+    ];
+    for message in messages {
+      assert_eq!(round_trip(&message), message);
+    }
+  }
+
+  // the client matches on the `command` / `payload` shape, so it shouldn't change by accident
+  #[test]
+  fn server_message_is_tagged() {
+    let json = serde_json::to_value(ServerMessage::DiceValue(4, false, None)).unwrap();
+    assert_eq!(json["command"], "DiceValue");
+    assert_eq!(json["payload"], serde_json::json!([4, false, null]));
+  }
+
+  // the client draws the safe squares from the rules sent with every update
+  #[test]
+  fn game_update_contains_safe_squares() {
+    let mut game = get_game();
+    game.rules.safe_squares = vec![8, 21];
+    let json = serde_json::to_value(ServerMessage::GameUpdate(game, None)).unwrap();
+    assert_eq!(
+      json["payload"][0]["rules"]["safe_squares"],
+      serde_json::json!([8, 21])
+    );
+  }
+
+  #[test]
+  fn sequenced_message_shape() {
+    let message = SequencedMessage {
+      sequence: 12,
+      message: ServerMessage::SkipPlayer,
+    };
+    let json = serde_json::to_value(&message).unwrap();
+    assert_eq!(json["sequence"], 12);
+    assert_eq!(json["message"]["command"], "SkipPlayer");
+    assert_eq!(round_trip(&message), message);
+  }
+}
@@ -0,0 +1,12 @@
+pub mod color;
+pub mod die_moves;
+pub mod game;
+pub mod game_diff;
+pub mod game_event;
+pub mod lobby;
+pub mod messages;
+pub mod player;
+pub mod position;
+pub mod replay;
+pub mod rule_set;
+pub mod square;
@@ -2,11 +2,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::Field;
 
-use super::color::Color;
+use super::{color::Color, rule_set::RuleSet};
 
-const PIECES_COUNT: usize = 4;
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Player {
   pub id: String,
   pub name: String,
@@ -15,29 +13,42 @@ pub struct Player {
   pub pawns_at_finish: usize,
   pub home: Vec<Field>,
   pub is_bot: bool,
+  // the human lost the connection, a bot plays for them until they are back
+  #[serde(default)]
+  pub away: bool,
+  // the human is done picking a seat in the lobby, the host can only start once every human is ready
+  #[serde(default)]
+  pub ready: bool,
 }
 
 // TODO change id to name when db is fixed
 impl Player {
-  pub fn new(id: String, name: String, color: Color, is_bot: bool) -> Self {
+  pub fn new(id: String, name: String, color: Color, is_bot: bool, rules: &RuleSet) -> Self {
     Player {
       id,
       name,
       color,
-      pawns_at_start: PIECES_COUNT,
+      pawns_at_start: rules.pieces_count,
       pawns_at_finish: 0,
-      home: vec![None; 5],
+      home: vec![None; rules.home_size],
       is_bot,
+      away: false,
+      ready: false,
     }
   }
 
   // returns whether all player's pieces are in home (occupy fields of home)
-  // we assume there are 4 pieces for each player
-  pub fn check_winner(&self) -> bool {
+  pub fn check_winner(&self, rules: &RuleSet) -> bool {
     // let occupied_fields: Vec<Field> = self.home.iter().filter(|&field| field.is_some()).collect();
     // occupied_fields.len() >= self.pieces_count()
 
-    self.pawns_at_finish == PIECES_COUNT
+    self.pawns_at_finish == rules.pieces_count
+  }
+
+  // a bot takes over the seat of a human who left, and gives it back once they return
+  pub fn set_away(&mut self, away: bool) {
+    self.away = away;
+    self.is_bot = away;
   }
 
   pub fn increase_pieces_at_start(&mut self) {
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+  game::Game,
+  game_event::{GameEvent, LoggedEvent},
+};
+
+// The game as it was started and everything that was played in it afterwards
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Replay {
+  pub initial: Game,
+  // rolls, moves and skipped turns in the order they happened
+  pub steps: Vec<LoggedEvent>,
+}
+
+impl Replay {
+  // None until the game was started
+  pub fn from_events(events: Vec<LoggedEvent>) -> Option<Replay> {
+    let started = events
+      .iter()
+      .position(|logged| matches!(logged.event, GameEvent::Started(_)))?;
+    let mut events = events;
+    let steps = events.split_off(started + 1);
+    Some(Replay {
+      initial: Game::from_events(events.iter().map(|logged| &logged.event)),
+      steps,
+    })
+  }
+
+  // the initial game followed by the game after every step
+  pub fn states(&self) -> Vec<Game> {
+    // the log positions of the steps start after the initial game
+    let offset = self.steps.first().map_or(0, |step| step.sequence);
+    let mut game = self.initial.clone();
+    let mut states = vec![game.clone()];
+    for logged in &self.steps {
+      match logged.event {
+        GameEvent::Undone { events } => {
+          game = states[events.saturating_sub(offset) as usize].clone();
+        }
+        ref event => game.apply_event(event),
+      }
+      states.push(game.clone());
+    }
+    states
+  }
+
+  // index of the state each turn starts at, turns are numbered from 1 on the client
+  pub fn turns(states: &[Game]) -> Vec<usize> {
+    (0..states.len())
+      .filter(|index| {
+        *index == 0 || states[*index].current_player != states[index - 1].current_player
+      })
+      .collect()
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::models::{color::Color, player::Player, rule_set::RuleSet};
+
+  fn logged(events: Vec<GameEvent>) -> Vec<LoggedEvent> {
+    events
+      .into_iter()
+      .enumerate()
+      .map(|(sequence, event)| LoggedEvent {
+        sequence: sequence as u64,
+        timestamp: 0,
+        event,
+      })
+      .collect()
+  }
+
+  #[test]
+  fn replay_from_events() {
+    let rules = RuleSet::default();
+    let players: Vec<Player> = Color::ordered()
+      .into_iter()
+      .map(|color| Player::new(color.to_string(), color.to_string(), color, false, &rules))
+      .collect();
+    let mut events = vec![
+      GameEvent::Created {
+        rules: rules.clone(),
+        dice_seed: 1,
+        join_code: None,
+      },
+      GameEvent::Joined(players[0].clone()),
+    ];
+    assert_eq!(Replay::from_events(logged(events.clone())), None);
+
+    events.extend(vec![
+      GameEvent::Started(players.clone()),
+      GameEvent::Rolled(6),
+      GameEvent::Rolled(6),
+      GameEvent::Rolled(6),
+      GameEvent::Skipped,
+      GameEvent::Rolled(2),
+      GameEvent::Skipped,
+    ]);
+    let replay = Replay::from_events(logged(events.clone())).unwrap();
+    assert!(replay.initial.started);
+    assert_eq!(replay.steps.len(), 6);
+    assert_eq!(replay.steps[0].sequence, 3);
+
+    let states = replay.states();
+    assert_eq!(states.len(), 7);
+    assert_eq!(states[0], replay.initial);
+    assert_eq!(states[6], Game::from_events(&events));
+    assert_eq!(Replay::turns(&states), vec![0, 4, 6]);
+
+    // the step that took a move back shows the game from before it
+    events.push(GameEvent::Undone { events: 5 });
+    let states = Replay::from_events(logged(events.clone())).unwrap().states();
+    assert_eq!(states[7], states[2]);
+    assert_eq!(states[7], Game::from_events(&events));
+  }
+}
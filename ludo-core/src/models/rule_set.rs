@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+
+// How a piece gets from the start onto the board
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum EntryRule {
+  // the entry roll is added to the rolls after it, the piece enters that many fields past the start
+  Sum,
+  // the entry roll alone places a piece on the start field, every die is then spent as its own move
+  Standard,
+}
+
+// House rules of a game, picked when the game is created
+// missing values fall back to the standard rules
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct RuleSet {
+  // number of fields on the main board, split evenly between the 4 colors
+  pub field_count: usize,
+  // where pieces are placed from the start of the color's quarter of the board
+  pub start_offset: usize,
+  // how far the starting position is from the last field before home
+  pub start_end_difference: usize,
+  pub pieces_count: usize,
+  pub home_size: usize,
+  // rolling this value lets the player roll again and is needed to enter a new piece
+  pub entry_roll: usize,
+  // the turn is forfeited once the rolls reach this sum (three sixes)
+  pub forfeit_sum: usize,
+  // fields where pieces can't be captured, none by default
+  pub safe_squares: Vec<usize>,
+  pub entry: EntryRule,
+  // every die of a turn is its own move, possibly with a different piece
+  // a sum can't enter a piece then, so the Standard entry rule is played whatever 'entry' says
+  pub separate_dice: bool,
+  // seconds a human has for a turn before the server plays it for them, 0 turns the timer off
+  pub turn_time: u64,
+}
+
+// the largest boards and piece counts a game can be created with
+pub const MAX_FIELD_COUNT: usize = 200;
+pub const MAX_PIECES: usize = 10;
+// the longest turn in seconds, a day
+pub const MAX_TURN_TIME: u64 = 24 * 60 * 60;
+
+impl RuleSet {
+  pub fn validate(&self) -> Result<(), String> {
+    if self.field_count == 0 || !self.field_count.is_multiple_of(4) {
+      return Err("field_count has to be a positive multiple of 4".into());
+    }
+    if self.field_count > MAX_FIELD_COUNT {
+      return Err(format!("field_count can be at most {}", MAX_FIELD_COUNT));
+    }
+    if self.start_offset >= self.field_count {
+      return Err("start_offset has to be smaller than field_count".into());
+    }
+    if self.start_end_difference == 0 || self.start_end_difference >= self.field_count {
+      return Err("start_end_difference has to be between 1 and field_count".into());
+    }
+    if self.pieces_count == 0 || self.home_size == 0 {
+      return Err("pieces_count and home_size have to be positive".into());
+    }
+    if self.pieces_count > MAX_PIECES || self.home_size > MAX_PIECES {
+      return Err(format!(
+        "pieces_count and home_size can be at most {}",
+        MAX_PIECES
+      ));
+    }
+    if !(1..=6).contains(&self.entry_roll) {
+      return Err("entry_roll has to be a value on the dice".into());
+    }
+    if self.forfeit_sum <= self.entry_roll {
+      return Err("forfeit_sum has to be greater than entry_roll".into());
+    }
+    if self
+      .safe_squares
+      .iter()
+      .any(|position| *position >= self.field_count)
+    {
+      return Err("safe_squares have to be fields of the board".into());
+    }
+    if self.turn_time > MAX_TURN_TIME {
+      return Err(format!("turn_time can be at most {}", MAX_TURN_TIME));
+    }
+    Ok(())
+  }
+
+  // with the Sum rule the entry roll has to be followed by another roll, the piece moves by the second one
+  pub fn can_enter(&self, dice_value: usize) -> bool {
+    match self.entry_rule() {
+      EntryRule::Sum => dice_value > self.entry_roll,
+      EntryRule::Standard => dice_value == self.entry_roll,
+    }
+  }
+
+  // how many fields past the start a piece entering with 'dice_value' lands
+  pub fn entry_steps(&self, dice_value: usize) -> usize {
+    match self.entry_rule() {
+      EntryRule::Sum => dice_value - self.entry_roll,
+      EntryRule::Standard => 0,
+    }
+  }
+
+  // the entry rule that is played, separate dice always enter with the Standard one
+  pub fn entry_rule(&self) -> EntryRule {
+    match self.separate_dice {
+      true => EntryRule::Standard,
+      false => self.entry,
+    }
+  }
+
+  // a single 6 has to be a move on its own to enter with the standard entry rule
+  pub fn spends_dice_separately(&self) -> bool {
+    self.entry_rule() == EntryRule::Standard
+  }
+
+  // the moves a player has to make after the rolling is done
+  pub fn pending_moves(&self, dice_throws: &[usize]) -> Vec<usize> {
+    match self.spends_dice_separately() {
+      true => dice_throws.to_vec(),
+      false => vec![dice_throws.iter().sum()],
+    }
+  }
+
+  pub fn can_roll_again(&self, dice_throws: &[usize]) -> bool {
+    dice_throws.last() == Some(&self.entry_roll) && !self.is_forfeit(dice_throws)
+  }
+
+  // the starting field of every color and the star field 8 fields after it
+  pub fn standard_safe_squares(&self) -> Vec<usize> {
+    let quarter = self.field_count / 4;
+    (0..4)
+      .flat_map(|color| {
+        let start = color * quarter + self.start_offset;
+        vec![start % self.field_count, (start + 8) % self.field_count]
+      })
+      .collect()
+  }
+
+  pub fn is_forfeit(&self, dice_throws: &[usize]) -> bool {
+    dice_throws.iter().sum::<usize>() >= self.forfeit_sum
+  }
+}
+
+impl Default for RuleSet {
+  fn default() -> Self {
+    RuleSet {
+      field_count: 52,
+      start_offset: 8,
+      start_end_difference: 2,
+      pieces_count: 4,
+      home_size: 5,
+      entry_roll: 6,
+      forfeit_sum: 18,
+      safe_squares: vec![],
+      entry: EntryRule::Sum,
+      separate_dice: false,
+      turn_time: 60,
+    }
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn default_rules_are_valid() {
+    assert_eq!(RuleSet::default().validate(), Ok(()));
+  }
+
+  #[test]
+  fn invalid_rules() {
+    let odd_board = RuleSet {
+      field_count: 50,
+      ..RuleSet::default()
+    };
+    assert!(odd_board.validate().is_err());
+
+    let unreachable_entry = RuleSet {
+      entry_roll: 7,
+      ..RuleSet::default()
+    };
+    assert!(unreachable_entry.validate().is_err());
+
+    let huge_board = RuleSet {
+      field_count: 1 << 40,
+      ..RuleSet::default()
+    };
+    assert!(huge_board.validate().is_err());
+
+    let long_home = RuleSet {
+      home_size: MAX_PIECES + 1,
+      ..RuleSet::default()
+    };
+    assert!(long_home.validate().is_err());
+
+    let endless_turn = RuleSet {
+      turn_time: u64::MAX,
+      ..RuleSet::default()
+    };
+    assert!(endless_turn.validate().is_err());
+    let day_long_turn = RuleSet {
+      turn_time: MAX_TURN_TIME,
+      ..RuleSet::default()
+    };
+    assert!(day_long_turn.validate().is_ok());
+  }
+
+  #[test]
+  fn rolling_again() {
+    let rules = RuleSet::default();
+    assert!(rules.can_roll_again(&[6]));
+    assert!(rules.can_roll_again(&[6, 6]));
+    assert!(!rules.can_roll_again(&[6, 6, 6]));
+    assert!(!rules.can_roll_again(&[6, 2]));
+    assert!(rules.is_forfeit(&[6, 6, 6]));
+    assert!(!rules.is_forfeit(&[6, 6, 5]));
+  }
+
+  #[test]
+  fn entry_rules() {
+    let sum = RuleSet::default();
+    assert!(!sum.can_enter(6));
+    assert!(sum.can_enter(9));
+    assert_eq!(sum.entry_steps(9), 3);
+    assert_eq!(sum.pending_moves(&[6, 3]), vec![9]);
+
+    let standard = RuleSet {
+      entry: EntryRule::Standard,
+      ..RuleSet::default()
+    };
+    assert!(standard.can_enter(6));
+    assert!(!standard.can_enter(9));
+    assert_eq!(standard.entry_steps(6), 0);
+    assert_eq!(standard.pending_moves(&[6, 3]), vec![6, 3]);
+  }
+
+  #[test]
+  fn separate_dice() {
+    let separate = RuleSet {
+      separate_dice: true,
+      ..RuleSet::default()
+    };
+    assert_eq!(separate.validate(), Ok(()));
+    assert_eq!(separate.pending_moves(&[6, 4]), vec![6, 4]);
+    // the 6 enters a piece on its own, as with the Standard entry rule
+    assert_eq!(separate.entry_rule(), EntryRule::Standard);
+    assert!(separate.can_enter(6));
+    assert!(!separate.can_enter(10));
+    assert_eq!(separate.entry_steps(6), 0);
+
+    let lower_entry = RuleSet {
+      entry_roll: 5,
+      ..separate
+    };
+    assert_eq!(lower_entry.validate(), Ok(()));
+    assert!(lower_entry.can_enter(5));
+  }
+
+  #[test]
+  fn standard_safe_squares() {
+    let rules = RuleSet::default();
+    assert_eq!(
+      rules.standard_safe_squares(),
+      vec![8, 16, 21, 29, 34, 42, 47, 3]
+    );
+
+    let outside_of_board = RuleSet {
+      safe_squares: vec![52],
+      ..RuleSet::default()
+    };
+    assert!(outside_of_board.validate().is_err());
+  }
+
+  #[test]
+  fn partial_rules_use_defaults() {
+    let rules: RuleSet = serde_json::from_str(r#"{ "home_size": 6 }"#).unwrap();
+    assert_eq!(
+      rules,
+      RuleSet {
+        home_size: 6,
+        ..RuleSet::default()
+      }
+    );
+  }
+}
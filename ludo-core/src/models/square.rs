@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+use crate::types::Field;
+
+use super::color::Color;
+
+// two pieces of the same color on a square form a blockade
+pub const BLOCKADE_SIZE: usize = 2;
+
+// Square of the main board, holds any number of pieces of each color
+// pieces of different colors only share a square if it is a safe square
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(from = "StoredSquare")]
+pub struct Square {
+  pawns: BTreeMap<Color, usize>,
+}
+
+impl Square {
+  pub fn count(&self, color: Color) -> usize {
+    self.pawns.get(&color).copied().unwrap_or(0)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.pawns.is_empty()
+  }
+
+  pub fn pawns(&self) -> impl Iterator<Item = (Color, usize)> + '_ {
+    self.pawns.iter().map(|(color, count)| (*color, *count))
+  }
+
+  // a blockade of any other color stops pieces of 'color' from passing or landing here
+  pub fn is_blockade_for(&self, color: Color) -> bool {
+    self
+      .pawns()
+      .any(|(other, count)| other != color && count >= BLOCKADE_SIZE)
+  }
+
+  pub(crate) fn add(&mut self, color: Color) {
+    *self.pawns.entry(color).or_insert(0) += 1;
+  }
+
+  pub(crate) fn remove(&mut self, color: Color) {
+    if let Some(count) = self.pawns.get_mut(&color) {
+      *count -= 1;
+      if *count == 0 {
+        self.pawns.remove(&color);
+      }
+    }
+  }
+
+  // removes every piece of 'color', returns how many there were
+  pub(crate) fn remove_all(&mut self, color: Color) -> usize {
+    self.pawns.remove(&color).unwrap_or(0)
+  }
+}
+
+impl From<Field> for Square {
+  fn from(field: Field) -> Self {
+    let mut square = Square::default();
+    if let Some(color) = field {
+      square.add(color);
+    }
+    square
+  }
+}
+
+impl Serialize for Square {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.pawns.serialize(serializer)
+  }
+}
+
+// games stored before stacking existed kept a single optional color per square
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredSquare {
+  Single(Field),
+  Stacked(BTreeMap<Color, usize>),
+}
+
+impl From<StoredSquare> for Square {
+  fn from(stored: StoredSquare) -> Self {
+    match stored {
+      StoredSquare::Single(field) => field.into(),
+      StoredSquare::Stacked(mut pawns) => {
+        pawns.retain(|_color, count| *count > 0);
+        Square { pawns }
+      }
+    }
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn stacking() {
+    let mut square = Square::default();
+    square.add(Color::Red);
+    assert!(!square.is_blockade_for(Color::Blue));
+
+    square.add(Color::Red);
+    assert_eq!(square.count(Color::Red), 2);
+    assert!(square.is_blockade_for(Color::Blue));
+    assert!(!square.is_blockade_for(Color::Red));
+
+    square.remove(Color::Red);
+    square.remove(Color::Red);
+    assert!(square.is_empty());
+  }
+
+  #[test]
+  fn reads_stored_squares() {
+    let squares: Vec<Square> =
+      serde_json::from_str(r#"[null, "Red", {}, { "Blue": 2, "Red": 1 }]"#).unwrap();
+    assert!(squares[0].is_empty());
+    assert_eq!(squares[1].count(Color::Red), 1);
+    assert!(squares[2].is_empty());
+    assert_eq!(squares[3].count(Color::Blue), 2);
+    assert_eq!(squares[3].count(Color::Red), 1);
+  }
+
+  #[test]
+  fn round_trip() {
+    let mut square = Square::from(Some(Color::Green));
+    square.add(Color::Green);
+    square.add(Color::Yellow);
+
+    let json = serde_json::to_string(&square).unwrap();
+    assert_eq!(json, r#"{"Green":2,"Yellow":1}"#);
+    assert_eq!(serde_json::from_str::<Square>(&json).unwrap(), square);
+  }
+}
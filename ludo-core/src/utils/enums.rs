@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{color::Color, position::Position};
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize, Clone)]
+pub enum MoveResult {
+  Winner(Color),
+  Success(String),
+  Error(String),
+}
+
+pub enum MoveType {
+  Promote,
+  Move(Position),
+}
+
+// A move that is valid for the current player and a specific roll
+// 'from' and 'to' index the main board, or the home column for moves inside it
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize, Clone, Copy)]
+pub enum Move {
+  // new piece from the start onto the board
+  Enter { to: usize, capture: bool },
+  Advance { from: usize, to: usize, capture: bool },
+  // from the main board into the home column
+  EnterHome { from: usize, to: usize },
+  AdvanceInHome { from: usize, to: usize },
+  // out of the game, either from the main board or from the home column
+  Finish { from: usize, from_home: bool },
+}
+
+impl Move {
+  pub fn is_capture(&self) -> bool {
+    matches!(
+      self,
+      Move::Enter { capture: true, .. } | Move::Advance { capture: true, .. }
+    )
+  }
+
+  // the piece that is moved, None for a new piece
+  pub fn origin(&self) -> Option<Position> {
+    let (position, is_home) = match *self {
+      Move::Enter { .. } => return None,
+      Move::Advance { from, .. } | Move::EnterHome { from, .. } => (from, false),
+      Move::AdvanceInHome { from, .. } => (from, true),
+      Move::Finish { from, from_home } => (from, from_home),
+    };
+    Some(Position { position, is_home })
+  }
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize, Clone, Copy)]
+pub enum RoundPhase {
+  Rolling,
+  Moving,
+}
@@ -0,0 +1,119 @@
+use crate::models::{die_moves::DieMoves, game::Game};
+use crate::utils::enums::MoveResult;
+
+use super::enums::{Move, MoveType};
+
+// the clicked piece (or a new one for Promote) is moved with the chosen die,
+//   or with the first pending die that allows it when none was chosen
+pub fn make_a_move(
+  game: &mut Game,
+  player_move: MoveType,
+  dice_value: Option<usize>,
+) -> MoveResult {
+  if game.pending_moves.is_empty() {
+    return MoveResult::Error(String::from("There are no dice left to move with."));
+  }
+  if dice_value.is_some_and(|value| !game.pending_moves.contains(&value)) {
+    return MoveResult::Error(String::from("None of the dice has this value."));
+  }
+  match find_move(game, player_move, dice_value) {
+    Some(legal_move) => play_move(game, legal_move),
+    None => MoveResult::Error(String::from("This move is not allowed.")),
+  }
+}
+
+// the legal move of the clicked piece (or a new one for Promote) with 'dice_value',
+//   or with the first pending die that allows it when it is None
+pub fn find_move(game: &Game, player_move: MoveType, dice_value: Option<usize>) -> Option<Move> {
+  let origin = match player_move {
+    MoveType::Promote => None,
+    MoveType::Move(position) => Some(position),
+  };
+  game
+    .pending_moves
+    .iter()
+    .filter(|roll| dice_value.is_none_or(|value| **roll == value))
+    .find_map(|roll| {
+      game
+        .legal_moves(*roll)
+        .into_iter()
+        .find(|legal_move| legal_move.origin() == origin)
+    })
+}
+
+// every move of a game is made through here, the turn goes to the next player
+//   once there is nothing left to play
+pub fn play_round(game: &mut Game, player_move: Move) -> MoveResult {
+  let mut move_result = play_move(game, player_move);
+
+  if let Some(winner) = game.check_winner() {
+    move_result = MoveResult::Winner(winner);
+    game.finish_game(winner);
+  }
+
+  if let MoveResult::Success(_) = move_result {
+    if next_available_positions(game).is_none() {
+      game.update_current_player();
+      game.dice_throws.clear();
+    }
+  }
+
+  move_result
+}
+
+// applies a move and spends the die it was made with
+pub fn play_move(game: &mut Game, player_move: Move) -> MoveResult {
+  let roll = game.move_roll(&player_move);
+  if !game.pending_moves.contains(&roll) {
+    return MoveResult::Error(String::from("None of the dice allows this move."));
+  }
+  let move_result = game.apply(player_move);
+  if !matches!(move_result, MoveResult::Error(_)) {
+    game.spend_move(roll);
+  }
+  move_result
+}
+
+// options for every pending die, the dice are forfeited once none of them can be played
+// None once there is nothing left to play this turn
+pub fn next_available_positions(game: &mut Game) -> Option<Vec<DieMoves>> {
+  let die_moves = get_die_moves(game);
+  if die_moves.iter().all(|die| die.is_empty()) {
+    game.pending_moves.clear();
+    return None;
+  }
+  Some(die_moves)
+}
+
+pub fn get_die_moves(game: &Game) -> Vec<DieMoves> {
+  game
+    .pending_moves
+    .iter()
+    .map(|dice_value| {
+      let (positions_on_board, positions_in_home, can_promote) =
+        get_available_positions(game, *dice_value);
+      DieMoves {
+        dice_value: *dice_value,
+        positions_on_board,
+        positions_in_home,
+        can_promote,
+      }
+    })
+    .collect()
+}
+
+// positions of the pieces that can be moved with 'dice_value', on the board and in the home column,
+//   and whether a new piece can enter
+pub fn get_available_positions(game: &Game, dice_value: usize) -> (Vec<usize>, Vec<usize>, bool) {
+  let mut positions_on_board = Vec::new();
+  let mut positions_in_home = Vec::new();
+  let mut can_promote = false;
+  for legal_move in game.legal_moves(dice_value) {
+    match legal_move.origin() {
+      None => can_promote = true,
+      Some(origin) if origin.is_home => positions_in_home.push(origin.position),
+      Some(origin) => positions_on_board.push(origin.position),
+    }
+  }
+  (positions_on_board, positions_in_home, can_promote)
+}
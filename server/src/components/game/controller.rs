@@ -1,26 +1,131 @@
-use actix_web::{get, post, put, web, HttpRequest, HttpResponse};
+use actix_web::{get, http::header, post, put, web, HttpRequest, HttpResponse};
 use actix_web_actors::ws;
-use futures::stream::TryStreamExt;
 use serde::Deserialize;
 use uuid::Uuid;
 
-use crate::models::{app_data::AppData, color::Color, game::Game, player::Player};
+use ludo_core::models::{
+  color::Color, game::Game, game_event::GameEvent, lobby::JoinResponse, player::Player,
+  replay::Replay, rule_set::RuleSet,
+};
+
+use crate::models::app_data::AppData;
+use crate::models::room_lock::lock_room;
+use crate::utils::access::{
+  authorize, hash_password, new_join_code, normalize_join_code, verify_password,
+};
+use crate::utils::dice::{new_seed, MAX_SEED};
+use crate::utils::lobbies::{lobby_page, LobbyQuery};
+use crate::utils::notation;
 
 use super::super::session::actor::GameSession;
-use super::database;
+use super::database::{log_event, DuplicateJoinCode};
+
+#[derive(Deserialize, Default)]
+pub struct CreateGameBody {
+  #[serde(default)]
+  pub rules: RuleSet,
+  // a game created with the seed of another one gets the same dice
+  pub seed: Option<u64>,
+  // private games aren't listed, they are found by their join code
+  #[serde(default)]
+  pub private: bool,
+  pub password: Option<String>,
+}
 
+// the body is optional, games created without one use the standard rules and a random seed
 #[post("")]
-pub async fn create_new_game(data: web::Data<AppData>) -> HttpResponse {
-  let game_res = database::create_game(&data.db).await;
-  match game_res {
-    Ok(id) => HttpResponse::Ok().body(id),
-    Err(_) => HttpResponse::InternalServerError().body("Failed to create new game"),
+pub async fn create_new_game(body: web::Bytes, data: web::Data<AppData>) -> HttpResponse {
+  let body = match body.is_empty() {
+    true => CreateGameBody::default(),
+    false => match serde_json::from_slice::<CreateGameBody>(&body) {
+      Ok(body) => body,
+      Err(_) => return HttpResponse::BadRequest().body("Invalid game settings"),
+    },
+  };
+  if let Err(err) = body.rules.validate() {
+    return HttpResponse::BadRequest().body(err);
+  }
+  if body.seed > Some(MAX_SEED) {
+    return HttpResponse::BadRequest().body("seed has to be smaller than 2^53");
+  }
+  if body.password.is_some() && !body.private {
+    return HttpResponse::BadRequest().body("Only private games can have a password");
+  }
+
+  let mut game = Game::with_rules(body.rules.clone());
+  game.dice_seed = body.seed.unwrap_or_else(new_seed);
+  if body.private {
+    game.join_code = match unused_join_code(&data).await {
+      Some(code) => Some(code),
+      None => return HttpResponse::InternalServerError().body("Failed to create new game"),
+    };
+  }
+  // another game could have been created with the code since it was checked, it gets a new one then
+  let id = loop {
+    match data.db.create_game(game.clone()).await {
+      Ok(id) => break id,
+      Err(err) if err.is::<DuplicateJoinCode>() => {
+        game.join_code = match unused_join_code(&data).await {
+          Some(code) => Some(code),
+          None => return HttpResponse::InternalServerError().body("Failed to create new game"),
+        };
+      }
+      Err(_) => return HttpResponse::InternalServerError().body("Failed to create new game"),
+    }
+  };
+  let created = GameEvent::Created {
+    rules: body.rules,
+    dice_seed: game.dice_seed,
+    join_code: game.join_code,
+  };
+  if let Some(password) = &body.password {
+    let hash = hash_password(password);
+    if data.db.set_password(&id, &hash).await.is_err() {
+      return HttpResponse::InternalServerError().body("Failed to create new game");
+    }
+  }
+  log_event(&data.db, &id, created).await;
+  HttpResponse::Ok().body(id)
+}
+
+// a new code that no other game has, None when the database can't tell
+async fn unused_join_code(data: &AppData) -> Option<String> {
+  loop {
+    let code = new_join_code();
+    match data.db.find_game_id_by_code(&code).await {
+      Ok(None) => return Some(code),
+      Ok(Some(_)) => continue,
+      Err(_) => return None,
+    }
+  }
+}
+
+#[derive(Deserialize)]
+pub struct JoinCodePath {
+  pub code: String,
+}
+
+// the id of the private game with the join code
+#[get("/codes/{code}")]
+pub async fn find_game_by_code(
+  path: web::Path<JoinCodePath>,
+  data: web::Data<AppData>,
+) -> HttpResponse {
+  let code = normalize_join_code(&path.code);
+  match data.db.find_game_id_by_code(&code).await {
+    Ok(Some(id)) => HttpResponse::Ok().body(id),
+    Ok(None) => HttpResponse::NotFound().body("No game with this code"),
+    Err(_) => HttpResponse::InternalServerError().body("Failed to find the game"),
   }
 }
 
 #[derive(Deserialize)]
 pub struct JoinGameBody {
   pub name: String,
+  // the seat the player wants, the first free one when it is missing
+  pub color: Option<Color>,
+  // only checked when the game has one
+  pub password: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -36,45 +141,71 @@ pub async fn join_game(
   data: web::Data<AppData>,
 ) -> HttpResponse {
   let room = path.room.as_str();
-  let game_res = database::find_game(&data.db, room).await;
+  // the lobby services change the players of the room under the same lock
+  let _guard = lock_room(&data.room_locks, room).await;
+  let game_res = data.db.find_game(room).await;
   let game = match game_res {
     Ok(Some(game)) => game,
     _ => return HttpResponse::InternalServerError().body("Failed to join game"),
   };
-  let used_colors = game
-    .players
-    .iter()
-    .map(|player| player.color)
-    .collect::<Vec<_>>();
+  if game.started {
+    return HttpResponse::Conflict().body("The game has already started");
+  }
+  match data.db.find_password(room).await {
+    Ok(Some(stored)) => {
+      let password = body.password.as_deref().unwrap_or_default();
+      if !verify_password(password, &stored) {
+        return HttpResponse::Forbidden().body("Wrong password");
+      }
+    }
+    Ok(None) => {}
+    Err(_) => return HttpResponse::InternalServerError().body("Failed to join game"),
+  }
   let free_colors = Color::ordered()
     .into_iter()
-    .filter(|color| !used_colors.contains(color))
+    .filter(|color| !game.is_color_taken(*color))
     .collect::<Vec<_>>();
 
-  let color = match free_colors.first() {
-    Some(color) => color,
-    None => return HttpResponse::Conflict().body("Game is full"),
+  let color = match (free_colors.first(), body.color) {
+    (None, _) => return HttpResponse::Conflict().body("Game is full"),
+    (Some(_), Some(color)) if !free_colors.contains(&color) => {
+      return HttpResponse::Conflict().body("The color is already taken")
+    }
+    (Some(_), Some(color)) => color,
+    (Some(color), None) => *color,
   };
 
   let player_id = Uuid::new_v4().to_string();
 
-  let new_player = Player::new(player_id.clone(), body.name.clone(), *color, false);
-  let res = database::add_player(&data.db, room, new_player).await;
-
-  if res.is_err() {
-    return HttpResponse::InternalServerError().body("Couldn't add you as a player");
+  let new_player = Player::new(
+    player_id.clone(),
+    body.name.clone(),
+    color,
+    false,
+    &game.rules,
+  );
+  // someone else could have taken the color since the game was read
+  match data.db.add_player(room, new_player.clone()).await {
+    Ok(Some(_)) => {}
+    Ok(None) => return HttpResponse::Conflict().body("The color is already taken"),
+    Err(_) => return HttpResponse::InternalServerError().body("Couldn't add you as a player"),
   }
+  log_event(&data.db, room, GameEvent::Joined(new_player)).await;
 
-  HttpResponse::Ok().body(player_id)
+  let token = data
+    .tokens
+    .issue(room, &player_id, chrono::Utc::now().timestamp());
+  HttpResponse::Ok().json(JoinResponse { player_id, token })
 }
 
 #[derive(Deserialize)]
 pub struct WebsocketPath {
   pub room: String,
-  pub player_id: String,
 }
 
-#[get("/websocket/{room}/{player_id}")]
+// the token from joining the game comes as 'Authorization: Bearer {token}', or as the first
+// message when the client can't set headers, it never goes into the url that gets logged
+#[get("/websocket/{room}")]
 pub async fn init_websocket(
   req: HttpRequest,
   stream: web::Payload,
@@ -82,40 +213,716 @@ pub async fn init_websocket(
   data: web::Data<AppData>,
 ) -> HttpResponse {
   let game_id = &path.room;
-  let player_id = &path.player_id;
 
-  let result = database::find_game(&data.db, game_id).await;
+  let mut session = GameSession::new(
+    game_id.clone(),
+    data.game_server_addr.clone(),
+    data.heartbeat,
+    data.db.clone(),
+    data.tokens.clone(),
+  );
+  let bearer = req
+    .headers()
+    .get(header::AUTHORIZATION)
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.strip_prefix("Bearer "));
+  if let Some(token) = bearer {
+    match authorize(&data.db, &data.tokens, game_id, token).await {
+      Ok(player_id) => session = session.with_player(player_id),
+      Err(err) => return HttpResponse::Forbidden().body(err),
+    }
+  }
+  ws::start(session, &req, stream)
+    .unwrap_or_else(|_| HttpResponse::InternalServerError().body("Whoops"))
+}
 
-  let game = match result {
-    Ok(Some(game)) => game,
+// a read only websocket of the room, anything sent over it is answered with an error
+#[get("/websocket/{room}/spectate")]
+pub async fn spectate_game(
+  req: HttpRequest,
+  stream: web::Payload,
+  path: web::Path<WebsocketPath>,
+  data: web::Data<AppData>,
+) -> HttpResponse {
+  let game_id = &path.room;
+  match data.db.find_game(game_id).await {
+    Ok(Some(_)) => {}
     _ => return HttpResponse::Forbidden().body("Game does not exist"),
-  };
-
-  if !game.players.iter().any(|player| &player.id == player_id) {
-    return HttpResponse::Forbidden().body("You haven't joined this game");
-  };
+  }
+  // the password keeps out everyone who wasn't invited, watching included
+  match data.db.find_password(game_id).await {
+    Ok(None) => {}
+    Ok(Some(_)) => return HttpResponse::Forbidden().body("Games with a password can't be watched"),
+    Err(_) => return HttpResponse::InternalServerError().body("Failed to find the game"),
+  }
 
   let session = GameSession::new(
-    player_id.clone(),
     game_id.clone(),
     data.game_server_addr.clone(),
-  );
-  let resp = ws::start(session, &req, stream);
-  println!("{:?}", resp);
-  resp.unwrap_or_else(|_| HttpResponse::InternalServerError().body("Whoops"))
+    data.heartbeat,
+    data.db.clone(),
+    data.tokens.clone(),
+  )
+  .with_spectator(Uuid::new_v4().to_string());
+  ws::start(session, &req, stream)
+    .unwrap_or_else(|_| HttpResponse::InternalServerError().body("Whoops"))
 }
 
+// the games that wait for players, a page at a time, see utils/lobbies.rs for the query
 #[get("")]
-pub async fn get_games(data: web::Data<AppData>) -> HttpResponse {
-  let db = &data.db.lock().await;
-  let game_collection = db.collection::<Game>("games");
+pub async fn get_lobbies(query: web::Query<LobbyQuery>, data: web::Data<AppData>) -> HttpResponse {
+  let games = match data.db.find_open_games().await {
+    Ok(games) => games,
+    Err(_) => return HttpResponse::InternalServerError().body("Failed to load games"),
+  };
+  match lobby_page(games, &query, chrono::Utc::now().timestamp_millis()) {
+    Ok(page) => HttpResponse::Ok().json(page),
+    Err(err) => HttpResponse::BadRequest().body(err),
+  }
+}
+
+#[derive(Deserialize)]
+pub struct GamePath {
+  pub room: String,
+}
 
-  let mut cursor = game_collection.find(None, None).await.unwrap();
+// everything that happened in the game so far, in the order it happened
+#[get("/{room}/events")]
+pub async fn get_game_events(path: web::Path<GamePath>, data: web::Data<AppData>) -> HttpResponse {
+  match data.db.find_events(&path.room).await {
+    Ok(events) => HttpResponse::Ok().json(events),
+    Err(_) => HttpResponse::InternalServerError().body("Failed to load the game events"),
+  }
+}
 
-  let mut games: Vec<Game> = Vec::new();
-  while let Some(game) = cursor.try_next().await.unwrap() {
-    games.push(game);
+// the game as it was started followed by its rolls and moves, for reviewing it turn by turn
+#[get("/{room}/replay")]
+pub async fn get_game_replay(path: web::Path<GamePath>, data: web::Data<AppData>) -> HttpResponse {
+  let events = match data.db.find_events(&path.room).await {
+    Ok(events) => events,
+    Err(_) => return HttpResponse::InternalServerError().body("Failed to load the game events"),
+  };
+  match Replay::from_events(events) {
+    Some(replay) => HttpResponse::Ok().json(replay),
+    None => HttpResponse::NotFound().body("The game hasn't started yet"),
+  }
+}
+
+// the whole game in the text notation, see utils/notation.rs
+#[get("/{room}/export")]
+pub async fn export_game(path: web::Path<GamePath>, data: web::Data<AppData>) -> HttpResponse {
+  let events = match data.db.find_events(&path.room).await {
+    Ok(events) => events,
+    Err(_) => return HttpResponse::InternalServerError().body("Failed to load the game events"),
+  };
+  match notation::export_game(&events) {
+    Some(text) => HttpResponse::Ok().content_type("text/plain").body(text),
+    None => HttpResponse::NotFound().body("The game hasn't started yet"),
   }
+}
+
+// creates a new game from an exported one, the game is only stored if every turn of it is valid
+#[post("/import")]
+pub async fn import_game(body: String, data: web::Data<AppData>) -> HttpResponse {
+  let events = match notation::import_game(&body) {
+    Ok(events) => events,
+    Err(err) => return HttpResponse::BadRequest().body(err.to_string()),
+  };
+  let game_res = data.db.create_game(Game::from_events(&events)).await;
+  let id = match game_res {
+    Ok(id) => id,
+    Err(_) => return HttpResponse::InternalServerError().body("Failed to import the game"),
+  };
+  for event in events {
+    log_event(&data.db, &id, event).await;
+  }
+  HttpResponse::Ok().body(id)
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use actix_web::{http::StatusCode, test, web};
+  use futures::StreamExt;
+  use std::{sync::Arc, time::Duration};
+  use tokio::time::timeout;
+  use tokio_tungstenite::connect_async;
 
-  HttpResponse::Ok().json(games)
+  use ludo_core::models::{
+    color::Color,
+    game::Game,
+    game_event::GameEvent,
+    messages::{ClientMessage, ServerMessage},
+    replay::Replay,
+    rule_set::RuleSet,
+  };
+  use ludo_core::utils::enums::RoundPhase;
+
+  use crate::components::game::database::{memory::InMemoryGameRepository, Database};
+  use crate::components::game_server::actor::GameServer;
+  use crate::components::session::actor::Heartbeat;
+  use crate::models::app_data::AppData;
+  use crate::utils::dice::{ScriptedDice, SeededDice};
+  use crate::utils::testing::*;
+
+  // creates a game, joins it with one player over the websocket and starts it with 3 bots
+  async fn start_game(app_data: web::Data<AppData>) -> (Socket, String) {
+    let app = app(app_data.clone()).await;
+    let room = create_game(&app, serde_json::json!({})).await;
+    let joined = join_game(&app, &room, "player").await;
+
+    let address = spawn_server(app_data);
+    let (mut socket, _) = connect_async(websocket_request(&address, &room, &joined.token))
+      .await
+      .unwrap();
+
+    assert_eq!(
+      next_message(&mut socket).await,
+      ServerMessage::PlayerCountChange(1)
+    );
+    match next_message(&mut socket).await {
+      ServerMessage::ConnectResponse(game, color) => {
+        assert_eq!(color, Color::Green);
+        assert_eq!(game.players.len(), 1);
+      }
+      message => panic!("unexpected message {:?}", message),
+    }
+    match next_message(&mut socket).await {
+      ServerMessage::LobbyState(roster) => {
+        assert_eq!(roster.len(), 1);
+        assert!(roster[0].connected && roster[0].is_host);
+      }
+      message => panic!("unexpected message {:?}", message),
+    }
+
+    ready_up(std::slice::from_mut(&mut socket)).await;
+    send_message(&mut socket, ClientMessage::StartGame).await;
+    match next_message(&mut socket).await {
+      ServerMessage::GameStarted(game) => {
+        assert!(game.started);
+        assert_eq!(game.players.len(), 4);
+        assert_eq!(game.current_player, Color::Green);
+      }
+      message => panic!("unexpected message {:?}", message),
+    }
+    (socket, room)
+  }
+
+  // Green rolls three sixes and forfeits the turn, Yellow is on turn afterwards
+  async fn forfeit_first_turn(socket: &mut Socket) {
+    for _ in 0..3 {
+      send_message(socket, ClientMessage::ThrowDice).await;
+      next_message(socket).await;
+    }
+    assert_eq!(next_message(socket).await, ServerMessage::SkipPlayer);
+    next_message(socket).await;
+  }
+
+  #[actix_web::test]
+  async fn play_without_mongo() {
+    let (mut socket, _) = start_game(app_data()).await;
+
+    send_message(&mut socket, ClientMessage::ThrowDice).await;
+    match next_message(&mut socket).await {
+      ServerMessage::DiceValue(value, _, _) => assert!((1..=6).contains(&value)),
+      message => panic!("unexpected message {:?}", message),
+    }
+  }
+
+  #[actix_web::test]
+  async fn scripted_rolls_forfeit_turn() {
+    let (mut socket, _) =
+      start_game(app_data_with_dice(Arc::new(ScriptedDice::new(vec![6])))).await;
+
+    for can_roll_again in [true, true, false] {
+      send_message(&mut socket, ClientMessage::ThrowDice).await;
+      match next_message(&mut socket).await {
+        ServerMessage::DiceValue(6, again, Some(time_left)) => {
+          assert_eq!(again, can_roll_again);
+          assert!(time_left <= 60_000);
+        }
+        message => panic!("unexpected message {:?}", message),
+      }
+    }
+    assert_eq!(next_message(&mut socket).await, ServerMessage::SkipPlayer);
+    match next_message(&mut socket).await {
+      // bots play without a timer
+      ServerMessage::GameDiff(diff, None) => {
+        assert_eq!(diff.current_player, Some(Color::Yellow));
+        assert_eq!(diff.dice_rolls, Some(3));
+      }
+      message => panic!("unexpected message {:?}", message),
+    }
+  }
+
+  #[actix_web::test]
+  async fn replay_of_started_game() {
+    let app_data = app_data_with_dice(Arc::new(ScriptedDice::new(vec![6])));
+    let (mut socket, room) = start_game(app_data.clone()).await;
+    forfeit_first_turn(&mut socket).await;
+
+    // the bots wait before they play, so the replay ends with the skipped turn
+    let app = app(app_data).await;
+    let request = test::TestRequest::get()
+      .uri(&format!("/games/{}/replay", room))
+      .to_request();
+    let replay: Replay = test::call_and_read_body_json(&app, request).await;
+    assert!(replay.initial.started);
+    assert_eq!(replay.initial.players.len(), 4);
+    let steps: Vec<GameEvent> = replay.steps.into_iter().map(|step| step.event).collect();
+    assert_eq!(
+      steps,
+      vec![
+        GameEvent::Rolled(6),
+        GameEvent::Rolled(6),
+        GameEvent::Rolled(6),
+        GameEvent::Skipped
+      ]
+    );
+  }
+
+  #[actix_web::test]
+  async fn replay_of_game_in_lobby() {
+    let app = app(app_data()).await;
+    let room = create_game(&app, serde_json::json!({})).await;
+
+    let request = test::TestRequest::get()
+      .uri(&format!("/games/{}/replay", room))
+      .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+  }
+
+  #[actix_web::test]
+  async fn export_and_import() {
+    let app_data = app_data_with_dice(Arc::new(ScriptedDice::new(vec![6])));
+    let (mut socket, room) = start_game(app_data.clone()).await;
+    forfeit_first_turn(&mut socket).await;
+
+    let app = app(app_data.clone()).await;
+    let request = test::TestRequest::get()
+      .uri(&format!("/games/{}/export", room))
+      .to_request();
+    let text = String::from_utf8(test::call_and_read_body(&app, request).await.to_vec()).unwrap();
+    assert!(text.ends_with("\n1. G 6 6 6 : -\n"));
+
+    let request = test::TestRequest::post()
+      .uri("/games/import")
+      .set_payload(text.clone())
+      .to_request();
+    let imported = String::from_utf8(test::call_and_read_body(&app, request).await.to_vec()).unwrap();
+    let game = app_data.db.find_game(&imported).await.unwrap().unwrap();
+    assert!(game.started);
+    assert_eq!(game.current_player, Color::Yellow);
+    assert_eq!(game.dice_rolls, 3);
+
+    let request = test::TestRequest::get()
+      .uri(&format!("/games/{}/export", imported))
+      .to_request();
+    let exported = test::call_and_read_body(&app, request).await;
+    assert_eq!(exported, text);
+
+    // Yellow is on turn after the skip
+    let request = test::TestRequest::post()
+      .uri("/games/import")
+      .set_payload(format!("{}2. B 3 : -\n", text))
+      .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let message = test::read_body(response).await;
+    assert_eq!(message, "turn 2: it is Yellow's turn");
+  }
+
+  #[actix_web::test]
+  async fn takeback_with_consent() {
+    let app_data = app_data_with_dice(Arc::new(ScriptedDice::new(vec![6, 2])));
+    let address = spawn_server(app_data.clone());
+    let (room, mut sockets) = connect_players(
+      app_data.clone(),
+      &address,
+      serde_json::json!({}),
+      &["green", "yellow"],
+    )
+    .await;
+    ready_up(&mut sockets).await;
+    let (green, yellow) = sockets.split_at_mut(1);
+    let (green, yellow) = (&mut green[0], &mut yellow[0]);
+
+    send_message(green, ClientMessage::StartGame).await;
+    wait_for(green, |message| {
+      matches!(message, ServerMessage::GameStarted(_))
+    })
+    .await;
+    for _ in 0..2 {
+      send_message(green, ClientMessage::ThrowDice).await;
+      wait_for(green, |message| {
+        matches!(message, ServerMessage::DiceValue(..))
+      })
+      .await;
+    }
+    send_message(green, ClientMessage::PromotePiece(None)).await;
+    wait_for(green, |message| {
+      matches!(message, ServerMessage::GameDiff(..))
+    })
+    .await;
+
+    // only the player who moved can ask
+    send_message(yellow, ClientMessage::RequestUndo).await;
+    next_error(yellow).await;
+
+    send_message(green, ClientMessage::RequestUndo).await;
+    assert_eq!(
+      wait_for(yellow, |message| matches!(
+        message,
+        ServerMessage::UndoRequested(_)
+      ))
+      .await,
+      ServerMessage::UndoRequested(Color::Green)
+    );
+    send_message(yellow, ClientMessage::AnswerUndo(true)).await;
+    assert_eq!(
+      wait_for(green, |message| matches!(
+        message,
+        ServerMessage::UndoResult(_)
+      ))
+      .await,
+      ServerMessage::UndoResult(true)
+    );
+    assert!(matches!(
+      next_message(green).await,
+      ServerMessage::GameDiff(..)
+    ));
+    send_message(green, ClientMessage::RequestSnapshot).await;
+    let game = match wait_for(green, |message| {
+      matches!(message, ServerMessage::GameUpdate(..))
+    })
+    .await
+    {
+      ServerMessage::GameUpdate(game, _) => game,
+      message => panic!("unexpected message {:?}", message),
+    };
+    assert_eq!(game.current_player, Color::Green);
+    assert_eq!(game.round_phase, RoundPhase::Moving);
+    assert_eq!(game.get_current_player().pawns_at_start, 4);
+
+    // the log leads to the same game as the one that was restored
+    let events = app_data.db.find_events(&room).await.unwrap();
+    assert!(matches!(
+      events.last().unwrap().event,
+      GameEvent::Undone { .. }
+    ));
+    let stored = app_data.db.find_game(&room).await.unwrap().unwrap();
+    assert_eq!(stored, game);
+    assert_eq!(
+      Game::from_events(events.iter().map(|logged| &logged.event)),
+      stored
+    );
+  }
+
+  #[actix_web::test]
+  async fn create_game_with_seed() {
+    let app_data = app_data();
+    let app = app(app_data.clone()).await;
+
+    let room = create_game(&app, serde_json::json!({ "seed": 42 })).await;
+    let game = app_data.db.find_game(&room).await.unwrap().unwrap();
+    assert_eq!(game.dice_seed, 42);
+    assert_eq!(game.rules, RuleSet::default());
+
+    let request = test::TestRequest::post()
+      .uri("/games")
+      .set_json(serde_json::json!({ "seed": 1_u64 << 60 }))
+      .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  }
+
+  #[actix_web::test]
+  async fn create_game_with_rules() {
+    let app_data = app_data();
+    let app = app(app_data.clone()).await;
+
+    let body = serde_json::json!({ "rules": { "home_size": 6, "pieces_count": 2 } });
+    let room = create_game(&app, body).await;
+    let game = app_data.db.find_game(&room).await.unwrap().unwrap();
+    assert_eq!(game.rules.home_size, 6);
+    assert_eq!(game.rules.pieces_count, 2);
+    assert_eq!(game.rules.field_count, 52);
+
+    let request = test::TestRequest::post()
+      .uri("/games")
+      .set_json(serde_json::json!({ "rules": { "field_count": 50 } }))
+      .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // oversized boards, homes and turns are refused before anything is built or timed for them
+    for rules in [
+      serde_json::json!({ "field_count": 4_000_000_000_u64 }),
+      serde_json::json!({ "home_size": 1000 }),
+      serde_json::json!({ "pieces_count": 1000 }),
+      serde_json::json!({ "turn_time": u64::MAX }),
+    ] {
+      let request = test::TestRequest::post()
+        .uri("/games")
+        .set_json(serde_json::json!({ "rules": rules }))
+        .to_request();
+      let response = test::call_service(&app, request).await;
+      assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+  }
+
+  #[actix_web::test]
+  async fn join_missing_game() {
+    let app = app(app_data()).await;
+    let request = test::TestRequest::put()
+      .uri("/games/missing")
+      .set_json(serde_json::json!({ "name": "player" }))
+      .to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.status().is_server_error());
+  }
+
+  #[actix_web::test]
+  async fn join_with_color() {
+    let app = app(app_data()).await;
+    let room = create_game(&app, serde_json::json!({})).await;
+
+    let join = |color: Option<Color>| {
+      test::TestRequest::put()
+        .uri(&format!("/games/{}", room))
+        .set_json(serde_json::json!({ "name": "player", "color": color }))
+        .to_request()
+    };
+    let response = test::call_service(&app, join(Some(Color::Red))).await;
+    assert!(response.status().is_success());
+    let response = test::call_service(&app, join(Some(Color::Red))).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = test::call_service(&app, join(None)).await;
+    assert!(response.status().is_success());
+  }
+
+  #[actix_web::test]
+  async fn turn_is_played_when_the_time_runs_out() {
+    let app_data = app_data_with_dice(Arc::new(ScriptedDice::new(vec![6])));
+    let body = serde_json::json!({ "rules": { "turn_time": 1 } });
+    let address = spawn_server(app_data.clone());
+    let (_, mut sockets) = connect_players(app_data, &address, body, &["green"]).await;
+    ready_up(&mut sockets).await;
+    let socket = &mut sockets[0];
+
+    send_message(socket, ClientMessage::StartGame).await;
+    wait_for(socket, |message| {
+      matches!(message, ServerMessage::GameStarted(_))
+    })
+    .await;
+
+    // nobody rolls, so the server rolls the three sixes for Green and the turn is forfeited
+    for _ in 0..3 {
+      match next_message(socket).await {
+        ServerMessage::DiceValue(6, _, Some(time_left)) => assert!(time_left <= 1000),
+        message => panic!("unexpected message {:?}", message),
+      }
+    }
+    assert_eq!(next_message(socket).await, ServerMessage::SkipPlayer);
+    match next_message(socket).await {
+      ServerMessage::GameDiff(diff, None) => {
+        assert_eq!(diff.current_player, Some(Color::Yellow));
+        assert_eq!(diff.dice_rolls, Some(3));
+      }
+      message => panic!("unexpected message {:?}", message),
+    }
+  }
+
+  #[actix_web::test]
+  async fn bot_keeps_the_seat_until_the_player_is_back() {
+    let db: Database = Arc::new(InMemoryGameRepository::new());
+    let game_server = GameServer::new(db.clone(), Arc::new(SeededDice))
+      .with_grace_period(Duration::from_millis(100));
+    let app_data = app_data_with(db.clone(), game_server, Heartbeat::default());
+    let address = spawn_server(app_data.clone());
+    let (room, mut sockets) = connect_players(
+      app_data.clone(),
+      &address,
+      serde_json::json!({}),
+      &["green", "yellow"],
+    )
+    .await;
+    ready_up(&mut sockets).await;
+    send_message(&mut sockets[0], ClientMessage::StartGame).await;
+    wait_for(&mut sockets[1], |message| {
+      matches!(message, ServerMessage::GameStarted(_))
+    })
+    .await;
+
+    // Yellow leaves while Green is on turn
+    let mut yellow = sockets.pop().unwrap();
+    yellow.close(None).await.unwrap();
+    let green = &mut sockets[0];
+    assert_eq!(
+      wait_for(green, |message| matches!(
+        message,
+        ServerMessage::PlayerLeft(_)
+      ))
+      .await,
+      ServerMessage::PlayerLeft(Color::Yellow)
+    );
+    let game = db.find_game(&room).await.unwrap().unwrap();
+    let player = game.get_player(Color::Yellow);
+    assert!(player.is_bot && player.away);
+
+    let token = token(&app_data, &room, &player.id);
+    let (_, (game, color)) = connect(&address, &room, &token).await;
+    assert_eq!(color, Color::Yellow);
+    assert!(!game.get_player(Color::Yellow).is_bot);
+    assert_eq!(
+      wait_for(green, |message| matches!(
+        message,
+        ServerMessage::PlayerReturned(_)
+      ))
+      .await,
+      ServerMessage::PlayerReturned(Color::Yellow)
+    );
+
+    let events = db.find_events(&room).await.unwrap();
+    let stored = db.find_game(&room).await.unwrap().unwrap();
+    assert_eq!(
+      Game::from_events(events.iter().map(|logged| &logged.event)),
+      stored
+    );
+  }
+
+  #[actix_web::test]
+  async fn silent_client_is_disconnected() {
+    let db: Database = Arc::new(InMemoryGameRepository::new());
+    let game_server = GameServer::new(db.clone(), Arc::new(SeededDice));
+    let heartbeat = Heartbeat {
+      interval: Duration::from_millis(50),
+      timeout: Duration::from_millis(300),
+    };
+    let app_data = app_data_with(db, game_server, heartbeat);
+    let address = spawn_server(app_data.clone());
+    let (_, mut sockets) = connect_players(
+      app_data,
+      &address,
+      serde_json::json!({}),
+      &["green", "yellow"],
+    )
+    .await;
+    ready_up(&mut sockets).await;
+
+    // pongs are only sent while the socket is read, Yellow stops reading and goes silent
+    let green = &mut sockets[0];
+    wait_for(green, |message| {
+      matches!(message, ServerMessage::PlayerCountChange(1))
+    })
+    .await;
+
+    // Green keeps answering the pings for longer than the timeout and stays connected
+    let _ = timeout(Duration::from_millis(500), async {
+      while let Some(Ok(_)) = green.next().await {}
+    })
+    .await;
+    send_message(green, ClientMessage::StartGame).await;
+    wait_for(green, |message| {
+      matches!(message, ServerMessage::GameStarted(_))
+    })
+    .await;
+  }
+
+  #[actix_web::test]
+  async fn reconnect_resumes_the_turn() {
+    let app_data = app_data_with_dice(Arc::new(ScriptedDice::new(vec![6, 2])));
+    let address = spawn_server(app_data.clone());
+    let (room, mut sockets) = connect_players(
+      app_data.clone(),
+      &address,
+      serde_json::json!({}),
+      &["green"],
+    )
+    .await;
+    ready_up(&mut sockets).await;
+    let mut socket = sockets.pop().unwrap();
+    send_message(&mut socket, ClientMessage::StartGame).await;
+    for _ in 0..2 {
+      send_message(&mut socket, ClientMessage::ThrowDice).await;
+      wait_for(&mut socket, |message| {
+        matches!(message, ServerMessage::DiceValue(..))
+      })
+      .await;
+    }
+    let positions = wait_for(&mut socket, |message| {
+      matches!(message, ServerMessage::AvailablePositions(_))
+    })
+    .await;
+    socket.close(None).await.unwrap();
+
+    let game = app_data.db.find_game(&room).await.unwrap().unwrap();
+    let player_id = game.get_player(Color::Green).id.clone();
+    let token = token(&app_data, &room, &player_id);
+    let (mut socket, (game, _)) = connect(&address, &room, &token).await;
+    assert_eq!(game.round_phase, RoundPhase::Moving);
+    match next_message(&mut socket).await {
+      ServerMessage::DiceValue(2, false, Some(_)) => {}
+      message => panic!("unexpected message {:?}", message),
+    }
+    assert_eq!(next_message(&mut socket).await, positions);
+
+    // the turn goes on where it was left, with the sum of the rolls as the only die
+    send_message(&mut socket, ClientMessage::PromotePiece(Some(6))).await;
+    assert_eq!(
+      next_error(&mut socket).await,
+      "None of the dice has this value"
+    );
+    send_message(&mut socket, ClientMessage::PromotePiece(Some(8))).await;
+    wait_for(&mut socket, |message| {
+      matches!(message, ServerMessage::GameDiff(..))
+    })
+    .await;
+  }
+
+  #[actix_web::test]
+  async fn numbered_diffs_rebuild_the_game() {
+    let app_data = app_data_with_dice(Arc::new(ScriptedDice::new(vec![6])));
+    let address = spawn_server(app_data.clone());
+    let (room, mut sockets) = connect_players(
+      app_data.clone(),
+      &address,
+      serde_json::json!({}),
+      &["green"],
+    )
+    .await;
+    ready_up(&mut sockets).await;
+    let socket = &mut sockets[0];
+    send_message(socket, ClientMessage::StartGame).await;
+    let started = next_sequenced(socket).await;
+    let mut game = match started.message {
+      ServerMessage::GameStarted(game) => game,
+      message => panic!("unexpected message {:?}", message),
+    };
+
+    // three rolls, the forfeited turn and the diff of the game all go to the room one after another
+    for _ in 0..3 {
+      send_message(socket, ClientMessage::ThrowDice).await;
+    }
+    let mut sequence = started.sequence;
+    loop {
+      let next = next_sequenced(socket).await;
+      assert_eq!(next.sequence, sequence + 1);
+      sequence = next.sequence;
+      if let ServerMessage::GameDiff(diff, _) = next.message {
+        game.apply_diff(&diff);
+        break;
+      }
+    }
+    assert_eq!(sequence, started.sequence + 5);
+    assert_eq!(game, app_data.db.find_game(&room).await.unwrap().unwrap());
+
+    // a snapshot only goes to the player, it keeps the number of the last room message
+    send_message(socket, ClientMessage::RequestSnapshot).await;
+    let snapshot = next_sequenced(socket).await;
+    assert_eq!(snapshot.sequence, sequence);
+    assert_eq!(snapshot.message, ServerMessage::GameUpdate(game, None));
+  }
 }
@@ -0,0 +1,289 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use ludo_core::models::{
+  game::Game,
+  game_event::{GameEvent, LoggedEvent},
+  player::Player,
+};
+
+use super::{DuplicateJoinCode, GameRepository, OpenGame};
+
+// Keeps the games in a map, used for tests and local development without mongo
+#[derive(Default)]
+pub struct InMemoryGameRepository {
+  games: Mutex<HashMap<String, Game>>,
+  // milliseconds since the unix epoch, by game id
+  created_at: Mutex<HashMap<String, i64>>,
+  passwords: Mutex<HashMap<String, String>>,
+  events: Mutex<HashMap<String, Vec<LoggedEvent>>>,
+}
+
+impl InMemoryGameRepository {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  async fn update_game<F>(&self, game_id: &str, update: F) -> anyhow::Result<Game>
+  where
+    F: FnOnce(&mut Game) + Send,
+  {
+    let mut games = self.games.lock().await;
+    match games.get_mut(game_id) {
+      Some(game) => {
+        update(game);
+        Ok(game.clone())
+      }
+      None => Err(anyhow!("Game doesnt exits")),
+    }
+  }
+}
+
+#[async_trait]
+impl GameRepository for InMemoryGameRepository {
+  async fn create_game(&self, game: Game) -> anyhow::Result<String> {
+    let id = Uuid::new_v4().to_simple().to_string();
+    let mut games = self.games.lock().await;
+    let join_code = game.join_code.as_ref();
+    if join_code.is_some()
+      && games
+        .values()
+        .any(|other| other.join_code.as_ref() == join_code)
+    {
+      return Err(DuplicateJoinCode.into());
+    }
+    games.insert(id.clone(), game);
+    drop(games);
+    self
+      .created_at
+      .lock()
+      .await
+      .insert(id.clone(), chrono::Utc::now().timestamp_millis());
+    Ok(id)
+  }
+
+  async fn find_game(&self, game_id: &str) -> anyhow::Result<Option<Game>> {
+    Ok(self.games.lock().await.get(game_id).cloned())
+  }
+
+  async fn find_open_games(&self) -> anyhow::Result<Vec<OpenGame>> {
+    let games = self.games.lock().await;
+    let created_at = self.created_at.lock().await;
+    Ok(
+      games
+        .iter()
+        .filter(|(_, game)| !game.started)
+        .map(|(id, game)| OpenGame {
+          id: id.clone(),
+          created_at: created_at.get(id).copied().unwrap_or_default(),
+          game: game.clone(),
+        })
+        .collect(),
+    )
+  }
+
+  async fn find_game_id_by_code(&self, join_code: &str) -> anyhow::Result<Option<String>> {
+    let games = self.games.lock().await;
+    Ok(
+      games
+        .iter()
+        .find(|(_, game)| game.join_code.as_deref() == Some(join_code))
+        .map(|(id, _)| id.clone()),
+    )
+  }
+
+  async fn set_password(&self, game_id: &str, password_hash: &str) -> anyhow::Result<()> {
+    if !self.games.lock().await.contains_key(game_id) {
+      return Err(anyhow!("Game doesnt exits"));
+    }
+    self
+      .passwords
+      .lock()
+      .await
+      .insert(game_id.to_string(), password_hash.to_string());
+    Ok(())
+  }
+
+  async fn find_password(&self, game_id: &str) -> anyhow::Result<Option<String>> {
+    Ok(self.passwords.lock().await.get(game_id).cloned())
+  }
+
+  async fn add_player(&self, game_id: &str, new_player: Player) -> anyhow::Result<Option<Game>> {
+    let mut games = self.games.lock().await;
+    let game = games
+      .get_mut(game_id)
+      .ok_or_else(|| anyhow!("Game doesnt exits"))?;
+    if game.is_color_taken(new_player.color) {
+      return Ok(None);
+    }
+    game.players.push(new_player);
+    Ok(Some(game.clone()))
+  }
+
+  async fn add_dice_roll(&self, game_id: &str, roll: usize) -> anyhow::Result<Game> {
+    self
+      .update_game(game_id, |game| {
+        game.dice_throws.push(roll);
+        game.dice_rolls += 1;
+      })
+      .await
+  }
+
+  // mirrors the mongo backend, which only overwrites the parts of the game that change during a round
+  async fn update_game_state(&self, game_id: &str, game: &Game) -> anyhow::Result<Game> {
+    self
+      .update_game(game_id, |stored| {
+        stored.fields = game.fields.clone();
+        stored.players = game.players.clone();
+        stored.current_player = game.current_player;
+        stored.dice_throws = game.dice_throws.clone();
+        stored.round_phase = game.round_phase;
+        stored.winner = game.winner;
+        stored.pending_moves = game.pending_moves.clone();
+        stored.dice_rolls = game.dice_rolls;
+      })
+      .await
+  }
+
+  async fn start_game(&self, game_id: &str) -> anyhow::Result<Game> {
+    self.update_game(game_id, |game| game.started = true).await
+  }
+
+  async fn append_event(&self, game_id: &str, event: GameEvent) -> anyhow::Result<LoggedEvent> {
+    let mut events = self.events.lock().await;
+    let game_events = events.entry(game_id.to_string()).or_default();
+    let logged = LoggedEvent {
+      sequence: game_events.len() as u64,
+      timestamp: chrono::Utc::now().timestamp_millis(),
+      event,
+    };
+    game_events.push(logged.clone());
+    Ok(logged)
+  }
+
+  async fn find_events(&self, game_id: &str) -> anyhow::Result<Vec<LoggedEvent>> {
+    Ok(
+      self
+        .events
+        .lock()
+        .await
+        .get(game_id)
+        .cloned()
+        .unwrap_or_default(),
+    )
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ludo_core::models::{color::Color, rule_set::RuleSet};
+
+  #[actix_web::test]
+  async fn create_and_find_game() {
+    let repository = InMemoryGameRepository::new();
+    let id = repository.create_game(Game::new()).await.unwrap();
+
+    let game = repository.find_game(&id).await.unwrap();
+    assert_eq!(game, Some(Game::new()));
+    assert_eq!(repository.find_game("missing").await.unwrap(), None);
+    assert_eq!(repository.find_open_games().await.unwrap().len(), 1);
+  }
+
+  #[actix_web::test]
+  async fn join_roll_and_start() {
+    let repository = InMemoryGameRepository::new();
+    let id = repository.create_game(Game::new()).await.unwrap();
+
+    let player = Player::new(
+      "id".into(),
+      "name".into(),
+      Color::Green,
+      false,
+      &RuleSet::default(),
+    );
+    let game = repository.add_player(&id, player.clone()).await.unwrap();
+    assert_eq!(game.unwrap().players, vec![player.clone()]);
+    // the seat is taken now
+    let taken = repository.add_player(&id, player).await.unwrap();
+    assert_eq!(taken, None);
+
+    let game = repository.add_dice_roll(&id, 6).await.unwrap();
+    assert_eq!(game.dice_throws, vec![6]);
+    assert_eq!(game.dice_rolls, 1);
+
+    let game = repository.start_game(&id).await.unwrap();
+    assert!(game.started);
+    assert!(repository.find_open_games().await.unwrap().is_empty());
+  }
+
+  #[actix_web::test]
+  async fn update_game_state() {
+    let repository = InMemoryGameRepository::new();
+    let id = repository.create_game(Game::new()).await.unwrap();
+
+    let mut game = Game::new();
+    game.current_player = Color::Blue;
+    game.dice_throws = vec![6, 2];
+    game.pending_moves = vec![8];
+    // not part of the round state, so it is left untouched
+    game.started = true;
+
+    let stored = repository.update_game_state(&id, &game).await.unwrap();
+    assert_eq!(stored.current_player, Color::Blue);
+    assert_eq!(stored.dice_throws, vec![6, 2]);
+    assert_eq!(stored.pending_moves, vec![8]);
+    assert!(!stored.started);
+  }
+
+  #[actix_web::test]
+  async fn append_and_find_events() {
+    let repository = InMemoryGameRepository::new();
+    let id = repository.create_game(Game::new()).await.unwrap();
+
+    repository
+      .append_event(&id, GameEvent::Rolled(6))
+      .await
+      .unwrap();
+    let logged = repository
+      .append_event(&id, GameEvent::Skipped)
+      .await
+      .unwrap();
+    assert_eq!(logged.sequence, 1);
+
+    let events = repository.find_events(&id).await.unwrap();
+    assert_eq!(
+      events
+        .iter()
+        .map(|logged| &logged.event)
+        .collect::<Vec<_>>(),
+      vec![&GameEvent::Rolled(6), &GameEvent::Skipped]
+    );
+    assert!(repository.find_events("missing").await.unwrap().is_empty());
+  }
+
+  #[actix_web::test]
+  async fn missing_game() {
+    let repository = InMemoryGameRepository::new();
+    assert!(repository.add_dice_roll("missing", 6).await.is_err());
+    assert!(repository.start_game("missing").await.is_err());
+  }
+
+  #[actix_web::test]
+  async fn join_codes_are_unique() {
+    let repository = InMemoryGameRepository::new();
+    let mut game = Game::new();
+    game.join_code = Some("ABC234".into());
+    repository.create_game(game.clone()).await.unwrap();
+    repository.create_game(Game::new()).await.unwrap();
+    repository.create_game(Game::new()).await.unwrap();
+
+    let error = repository.create_game(game).await.unwrap_err();
+    assert!(error.is::<DuplicateJoinCode>());
+  }
+}
@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use ludo_core::models::{
+  game::Game,
+  game_event::{GameEvent, LoggedEvent},
+  player::Player,
+};
+
+pub mod memory;
+pub mod mongo;
+pub mod sqlite;
+
+use memory::InMemoryGameRepository;
+use mongo::MongoGameRepository;
+use sqlite::SqliteGameRepository;
+
+// A game that hasn't started yet, together with what the lobby listing needs to know about it
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpenGame {
+  pub id: String,
+  // milliseconds since the unix epoch
+  pub created_at: i64,
+  pub game: Game,
+}
+
+// create_game fails with it when another game already has the join code of the new one
+#[derive(Debug)]
+pub struct DuplicateJoinCode;
+
+impl std::fmt::Display for DuplicateJoinCode {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "another game has the same join code")
+  }
+}
+
+impl std::error::Error for DuplicateJoinCode {}
+
+// Storage used by the controllers and the game server, so the backend can be swapped by config
+#[async_trait]
+pub trait GameRepository: Send + Sync {
+  async fn create_game(&self, game: Game) -> anyhow::Result<String>;
+  async fn find_game(&self, game_id: &str) -> anyhow::Result<Option<Game>>;
+  // the games that haven't started yet, in no particular order
+  async fn find_open_games(&self) -> anyhow::Result<Vec<OpenGame>>;
+  async fn find_game_id_by_code(&self, join_code: &str) -> anyhow::Result<Option<String>>;
+  // the hash of the password is kept apart from the game, so it never reaches the players
+  async fn set_password(&self, game_id: &str, password_hash: &str) -> anyhow::Result<()>;
+  async fn find_password(&self, game_id: &str) -> anyhow::Result<Option<String>>;
+  // None when the color of the player was taken in the meantime, the check and the push are one update
+  async fn add_player(&self, game_id: &str, new_player: Player) -> anyhow::Result<Option<Game>>;
+  async fn add_dice_roll(&self, game_id: &str, roll: usize) -> anyhow::Result<Game>;
+  async fn update_game_state(&self, game_id: &str, game: &Game) -> anyhow::Result<Game>;
+  async fn start_game(&self, game_id: &str) -> anyhow::Result<Game>;
+  // the event log is only ever appended to, every event gets the next sequence number of the game
+  async fn append_event(&self, game_id: &str, event: GameEvent) -> anyhow::Result<LoggedEvent>;
+  async fn find_events(&self, game_id: &str) -> anyhow::Result<Vec<LoggedEvent>>;
+}
+
+pub type Database = Arc<dyn GameRepository>;
+
+// the game itself is already stored when its event is logged, so a failure can't be undone,
+// it is reported as an error since the log of the game no longer replays to the stored game
+pub async fn log_event(db: &Database, game_id: &str, event: GameEvent) -> Option<LoggedEvent> {
+  match db.append_event(game_id, event).await {
+    Ok(logged) => Some(logged),
+    Err(err) => {
+      log::error!("Failed to log event of game {}: {}", game_id, err);
+      None
+    }
+  }
+}
+
+/// picks the backend from `DATABASE_URL`
+/// - `mongodb://` or `mongodb+srv://` connects to mongo
+/// - `sqlite://<path>` opens (or creates) a single file database
+/// - `memory://` or no url keeps the games in memory (they are lost on restart)
+pub async fn connect(database_url: Option<String>) -> anyhow::Result<Database> {
+  match database_url {
+    Some(url) if url.starts_with("mongodb://") || url.starts_with("mongodb+srv://") => {
+      Ok(Arc::new(MongoGameRepository::connect(&url).await?))
+    }
+    Some(url) if url.starts_with("sqlite://") => Ok(Arc::new(SqliteGameRepository::connect(&url)?)),
+    Some(url) if url.starts_with("memory://") => Ok(Arc::new(InMemoryGameRepository::new())),
+    Some(url) => Err(anyhow::anyhow!(
+      "Unsupported DATABASE_URL scheme: {}",
+      url.split("://").next().unwrap_or_default()
+    )),
+    None => {
+      println!("DATABASE_URL is not set, games will be kept in memory");
+      Ok(Arc::new(InMemoryGameRepository::new()))
+    }
+  }
+}
@@ -0,0 +1,512 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::{
+  bson::{self, doc, oid::ObjectId, Bson, Document},
+  error::{ErrorKind, WriteFailure},
+  options::{ClientOptions, FindOneAndUpdateOptions, FindOptions, IndexOptions, ReturnDocument},
+  Client, Database, IndexModel,
+};
+use tokio::sync::Mutex;
+
+use ludo_core::models::{
+  game::{Fields, Game},
+  game_event::{GameEvent, LoggedEvent},
+  player::Player,
+};
+
+use super::{DuplicateJoinCode, GameRepository, OpenGame};
+
+pub struct MongoGameRepository {
+  db: Mutex<Database>,
+}
+
+impl MongoGameRepository {
+  pub async fn connect(database_url: &str) -> anyhow::Result<Self> {
+    let mut client_options = ClientOptions::parse(database_url).await?;
+    client_options.app_name = Some("Ludo".to_string());
+
+    let client = Client::with_options(client_options)?;
+    let db = client.database("main");
+    migrate_fields(&db).await?;
+    create_event_index(&db).await?;
+    create_join_code_index(&db).await?;
+    Ok(MongoGameRepository { db: Mutex::new(db) })
+  }
+}
+
+// the sequence numbers of a game are unique, a concurrent append fails instead of reusing one
+async fn create_event_index(db: &Database) -> anyhow::Result<()> {
+  let index = IndexModel::builder()
+    .keys(doc! { "game_id": 1, "sequence": 1 })
+    .options(IndexOptions::builder().unique(true).build())
+    .build();
+  db.collection::<Document>("game_events")
+    .create_index(index, None)
+    .await?;
+  Ok(())
+}
+
+// no two games share a join code, games without one store null, so only strings are indexed
+// (a sparse index would still index the nulls)
+async fn create_join_code_index(db: &Database) -> anyhow::Result<()> {
+  let options = IndexOptions::builder()
+    .unique(true)
+    .partial_filter_expression(doc! { "join_code": { "$type": "string" } })
+    .build();
+  let index = IndexModel::builder()
+    .keys(doc! { "join_code": 1 })
+    .options(options)
+    .build();
+  db.collection::<Document>("games")
+    .create_index(index, None)
+    .await?;
+  Ok(())
+}
+
+// games stored before stacking existed kept a single optional color per square
+// they can still be read, but are rewritten once so the collection holds a single format
+async fn migrate_fields(db: &Database) -> anyhow::Result<()> {
+  let game_collection = db.collection::<Document>("games");
+  let filter = doc! { "$or": [
+    { "fields.values": { "$type": "string" } },
+    { "fields.values": { "$type": "null" } },
+  ] };
+  let mut cursor = game_collection.find(filter, None).await?;
+  while let Some(game) = cursor.try_next().await? {
+    let id = game.get_object_id("_id")?;
+    let fields: Fields = bson::from_bson(game.get("fields").cloned().unwrap_or(Bson::Null))?;
+    let update = doc! { "$set": { "fields": bson::to_bson(&fields)? } };
+    game_collection
+      .update_one(doc! { "_id": id }, update, None)
+      .await?;
+  }
+  Ok(())
+}
+
+#[async_trait]
+impl GameRepository for MongoGameRepository {
+  async fn create_game(&self, game: Game) -> anyhow::Result<String> {
+    create_game(&self.db, game).await
+  }
+
+  async fn find_game(&self, game_id: &str) -> anyhow::Result<Option<Game>> {
+    find_game(&self.db, game_id).await
+  }
+
+  async fn find_open_games(&self) -> anyhow::Result<Vec<OpenGame>> {
+    find_open_games(&self.db).await
+  }
+
+  async fn find_game_id_by_code(&self, join_code: &str) -> anyhow::Result<Option<String>> {
+    find_game_id_by_code(&self.db, join_code).await
+  }
+
+  async fn set_password(&self, game_id: &str, password_hash: &str) -> anyhow::Result<()> {
+    set_password(&self.db, game_id, password_hash).await
+  }
+
+  async fn find_password(&self, game_id: &str) -> anyhow::Result<Option<String>> {
+    find_password(&self.db, game_id).await
+  }
+
+  async fn add_player(&self, game_id: &str, new_player: Player) -> anyhow::Result<Option<Game>> {
+    add_player(&self.db, game_id, new_player).await
+  }
+
+  async fn add_dice_roll(&self, game_id: &str, roll: usize) -> anyhow::Result<Game> {
+    add_dice_roll(&self.db, game_id, roll).await
+  }
+
+  async fn update_game_state(&self, game_id: &str, game: &Game) -> anyhow::Result<Game> {
+    update_game_state(&self.db, game_id, game).await
+  }
+
+  async fn append_event(&self, game_id: &str, event: GameEvent) -> anyhow::Result<LoggedEvent> {
+    append_event(&self.db, game_id, event).await
+  }
+
+  async fn find_events(&self, game_id: &str) -> anyhow::Result<Vec<LoggedEvent>> {
+    find_events(&self.db, game_id).await
+  }
+
+  async fn start_game(&self, game_id: &str) -> anyhow::Result<Game> {
+    start_game(&self.db, game_id).await
+  }
+}
+
+async fn create_game(db: &Mutex<Database>, game: Game) -> anyhow::Result<String> {
+  let db_mutex = db.lock().await;
+  let game_collection = db_mutex.collection::<Game>("games");
+  let res = game_collection.insert_one(game, None).await;
+  match res {
+    Ok(result) => {
+      if let Bson::ObjectId(id) = result.inserted_id {
+        Ok(format!("{}", id))
+      } else {
+        Err(anyhow!("game id couldn't be parsed"))
+      }
+    }
+    Err(e) if is_duplicate_key(&e) => Err(DuplicateJoinCode.into()),
+    Err(e) => Err(anyhow!(e)),
+  }
+}
+
+fn is_duplicate_key(error: &mongodb::error::Error) -> bool {
+  matches!(
+    &*error.kind,
+    ErrorKind::Write(WriteFailure::WriteError(write_error)) if write_error.code == 11000
+  )
+}
+
+// the player is only pushed while no other player has their color, in a single update
+async fn add_player(
+  db: &Mutex<Database>,
+  game_id: &str,
+  new_player: Player,
+) -> anyhow::Result<Option<Game>> {
+  let color = bson::to_bson(&new_player.color)?;
+  let serialized_player = bson::to_bson(&new_player)?;
+  let update = doc! { "$push": { "players": serialized_player } };
+  let oid = match ObjectId::parse_str(game_id) {
+    Ok(res) => res,
+    Err(err) => return Err(anyhow!(err)),
+  };
+  let filter = doc! { "_id" : oid, "players.color": { "$ne": color } };
+  match update_game(db, filter, update).await {
+    Ok(game) => Ok(Some(game)),
+    // nothing matched, either the color is taken or the game is gone
+    Err(_) if find_game(db, game_id).await?.is_some() => Ok(None),
+    Err(err) => Err(err),
+  }
+}
+
+async fn find_game(db: &Mutex<Database>, game_id: &str) -> anyhow::Result<Option<Game>> {
+  let db_mutex = db.lock().await;
+  let game_collection = db_mutex.collection::<Game>("games");
+  let oid = match ObjectId::parse_str(game_id) {
+    Ok(res) => res,
+    Err(err) => return Err(anyhow!(err)),
+  };
+  let filter = doc! { "_id" : oid };
+  let found = game_collection.find_one(filter, None).await;
+  match found {
+    Ok(result) => Ok(result),
+    Err(e) => Err(anyhow!(e)),
+  }
+}
+
+// the id of a game says when it was created, so it doesn't have to be stored
+async fn find_open_games(db: &Mutex<Database>) -> anyhow::Result<Vec<OpenGame>> {
+  let db_mutex = db.lock().await;
+  let game_collection = db_mutex.collection::<Document>("games");
+  let mut cursor = game_collection
+    .find(doc! { "started": false }, None)
+    .await?;
+  let mut games = vec![];
+  while let Some(document) = cursor.try_next().await? {
+    let id = document.get_object_id("_id")?;
+    games.push(OpenGame {
+      id: id.to_hex(),
+      created_at: id.timestamp().timestamp_millis(),
+      game: bson::from_document(document)?,
+    });
+  }
+  Ok(games)
+}
+
+async fn find_game_id_by_code(
+  db: &Mutex<Database>,
+  join_code: &str,
+) -> anyhow::Result<Option<String>> {
+  let db_mutex = db.lock().await;
+  let game_collection = db_mutex.collection::<Document>("games");
+  let found = game_collection
+    .find_one(doc! { "join_code": join_code }, None)
+    .await?;
+  match found {
+    Some(document) => Ok(Some(document.get_object_id("_id")?.to_hex())),
+    None => Ok(None),
+  }
+}
+
+// the hash is a field of the game document that the game model doesn't know about
+async fn set_password(
+  db: &Mutex<Database>,
+  game_id: &str,
+  password_hash: &str,
+) -> anyhow::Result<()> {
+  let db_mutex = db.lock().await;
+  let game_collection = db_mutex.collection::<Document>("games");
+  let oid = ObjectId::parse_str(game_id)?;
+  let update = doc! { "$set": { "password_hash": password_hash } };
+  let result = game_collection
+    .update_one(doc! { "_id": oid }, update, None)
+    .await?;
+  match result.matched_count {
+    0 => Err(anyhow!("Game doesnt exits")),
+    _ => Ok(()),
+  }
+}
+
+async fn find_password(db: &Mutex<Database>, game_id: &str) -> anyhow::Result<Option<String>> {
+  let db_mutex = db.lock().await;
+  let game_collection = db_mutex.collection::<Document>("games");
+  let oid = ObjectId::parse_str(game_id)?;
+  let found = game_collection.find_one(doc! { "_id": oid }, None).await?;
+  Ok(
+    found
+      .as_ref()
+      .and_then(|document| document.get_str("password_hash").ok())
+      .map(String::from),
+  )
+}
+
+// pub async fn update_board(
+//   db: &Mutex<Database>,
+//   game_id: &str,
+//   fields: Vec<Field>,
+// ) -> anyhow::Result<Game> {
+//   let serialized_fields = bson::to_bson(&fields)?;
+//   let oid = match ObjectId::parse_str(game_id) {
+//     Ok(res) => res,
+//     Err(err) => return Err(anyhow!(err)),
+//   };
+//   let filter = doc! { "_id" : oid };
+//   let update = doc! { "fields": serialized_fields };
+
+//   update_game(db, filter, update).await
+// }
+
+// pub async fn update_player(
+//   db: &Mutex<Database>,
+//   game_id: &str,
+//   player: Player,
+// ) -> anyhow::Result<Game> {
+//   let oid = match ObjectId::parse_str(game_id) {
+//     Ok(res) => res,
+//     Err(err) => return Err(anyhow!(err)),
+//   };
+//   let serialized_player = bson::to_bson(&player)?;
+//   let serialized_color = bson::to_bson(&player.color)?;
+
+//   let filter = doc! { "_id" : oid, "players.color": serialized_color };
+//   let update = doc! { "$set": { "players.$" : serialized_player } };
+//   update_game(db, filter, update).await
+// }
+
+async fn start_game(db: &Mutex<Database>, game_id: &str) -> anyhow::Result<Game> {
+  let oid = match ObjectId::parse_str(game_id) {
+    Ok(res) => res,
+    Err(err) => return Err(anyhow!(err)),
+  };
+  let filter = doc! { "_id" : oid };
+  let update = doc! { "$set": { "started" : true } };
+  update_game(db, filter, update).await
+}
+
+// pub async fn finish_game(db: &Mutex<Database>, game_id: &str) -> anyhow::Result<Game> {
+//   let oid = match ObjectId::parse_str(game_id) {
+//     Ok(res) => res,
+//     Err(err) => return Err(anyhow!(err)),
+//   };
+//   let filter = doc! { "_id" : oid };
+//   let update = doc! { "$set": { "finished_at" : mongodb::bson::DateTime::now() } };
+//   update_game(db, filter, update).await
+// }
+
+async fn add_dice_roll(db: &Mutex<Database>, game_id: &str, roll: usize) -> anyhow::Result<Game> {
+  let oid = match ObjectId::parse_str(game_id) {
+    Ok(res) => res,
+    Err(err) => return Err(anyhow!(err)),
+  };
+  let serialized_roll = bson::to_bson(&roll)?;
+  let filter = doc! { "_id" : oid };
+  let update = doc! { "$push": { "dice_throws": serialized_roll }, "$inc": { "dice_rolls": 1_i64 } };
+  update_game(db, filter, update).await
+}
+
+// pub async fn update_current_player(
+//   db: &Mutex<Database>,
+//   game_id: &str,
+//   current_player: Color,
+// ) -> anyhow::Result<Game> {
+//   let oid = match ObjectId::parse_str(game_id) {
+//     Ok(res) => res,
+//     Err(err) => return Err(anyhow!(err)),
+//   };
+//   let current_player_bson = bson::to_bson(&current_player)?;
+//   let filter = doc! { "_id" : oid };
+//   let update = doc! { "$set": { "current_player": current_player_bson } };
+//   update_game(db, filter, update).await
+// }
+
+async fn update_game(
+  db: &Mutex<Database>,
+  filter: Document,
+  update: Document,
+) -> anyhow::Result<Game> {
+  let db_mutex = db.lock().await;
+  let game_collection = db_mutex.collection::<Game>("games");
+  let option = FindOneAndUpdateOptions::builder()
+    .return_document(ReturnDocument::After)
+    .build();
+  let res = game_collection
+    .find_one_and_update(filter, update, option)
+    .await;
+  match res {
+    Ok(Some(game)) => Ok(game),
+    Ok(None) => Err(anyhow!("Game doesnt exits")),
+    Err(e) => Err(anyhow!(e)),
+  }
+}
+
+async fn update(db: &Mutex<Database>, game_id: &str, update: Document) -> anyhow::Result<Game> {
+  let oid = match ObjectId::parse_str(game_id) {
+    Ok(res) => res,
+    Err(err) => return Err(anyhow!(err)),
+  };
+  let filter = doc! { "_id" : oid };
+  let res = update_game(db, filter, update).await;
+  match res {
+    Ok(game) => Ok(game),
+    Err(e) => Err(anyhow!(e)),
+  }
+}
+
+async fn update_game_state(
+  db: &Mutex<Database>,
+  game_id: &str,
+  game: &Game,
+) -> anyhow::Result<Game> {
+  let update_doc = match make_doc(game) {
+    Ok(doc) => doc,
+    _ => return Err(anyhow!("Failed to create document")),
+  };
+  update(db, game_id, update_doc).await
+}
+
+// the sequence is the number of events logged before, when another server appended one in the
+// meantime the unique index refuses the insert and the next sequence is tried
+async fn append_event(
+  db: &Mutex<Database>,
+  game_id: &str,
+  event: GameEvent,
+) -> anyhow::Result<LoggedEvent> {
+  let db_mutex = db.lock().await;
+  let event_collection = db_mutex.collection::<Document>("game_events");
+  let serialized_event = bson::to_bson(&event)?;
+  loop {
+    let sequence = event_collection
+      .count_documents(doc! { "game_id": game_id }, None)
+      .await?;
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let stored = doc! {
+      "game_id": game_id,
+      "sequence": sequence as i64,
+      "timestamp": timestamp,
+      "event": serialized_event.clone(),
+    };
+    match event_collection.insert_one(stored, None).await {
+      Ok(_) => {
+        return Ok(LoggedEvent {
+          sequence,
+          timestamp,
+          event,
+        })
+      }
+      Err(e) if is_duplicate_key(&e) => continue,
+      Err(e) => return Err(anyhow!(e)),
+    }
+  }
+}
+
+async fn find_events(db: &Mutex<Database>, game_id: &str) -> anyhow::Result<Vec<LoggedEvent>> {
+  let db_mutex = db.lock().await;
+  let options = FindOptions::builder().sort(doc! { "sequence": 1 }).build();
+  let stored: Vec<Document> = db_mutex
+    .collection::<Document>("game_events")
+    .find(doc! { "game_id": game_id }, options)
+    .await?
+    .try_collect()
+    .await?;
+  stored
+    .iter()
+    .map(|stored| {
+      Ok(LoggedEvent {
+        sequence: stored.get_i64("sequence")? as u64,
+        timestamp: stored.get_i64("timestamp")?,
+        event: bson::from_bson(stored.get("event").cloned().unwrap_or(Bson::Null))?,
+      })
+    })
+    .collect()
+}
+
+fn make_doc(game: &Game) -> anyhow::Result<Document> {
+  let fields = bson::to_bson(&game.fields)?;
+  let players = bson::to_bson(&game.players)?;
+  let current_player = bson::to_bson(&game.current_player)?;
+  let bson_dice_throws = bson::to_bson(&game.dice_throws)?;
+  let phase = bson::to_bson(&game.round_phase)?;
+  let pending_moves = bson::to_bson(&game.pending_moves)?;
+  let dice_rolls = bson::to_bson(&game.dice_rolls)?;
+  let winner = bson::to_bson(&game.winner)?;
+  let doc = doc! { "$set": { "fields": fields, "players": players, "current_player": current_player, "dice_throws": &bson_dice_throws, "round_phase":phase, "pending_moves": pending_moves, "dice_rolls": dice_rolls, "winner": winner } };
+  Ok(doc)
+}
+// pub fn make_bson<T>(values: &[&T]) -> anyhow::Result<Box<[Bson]>>
+//   where T: ?Sized + Serialize {
+//     match values.into_iter().map(|value| {
+//       bson::to_bson(value)
+//     }).collect::<Result<Box<[_]>, _>>() {
+//       Ok(values) => Ok(values),
+//       Err(err) => Err(anyhow!(err)),
+//     }
+//   }
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ludo_core::models::color::Color;
+
+  #[test]
+  fn game_round_trip() {
+    let mut game = Game::new();
+    game.players = Color::ordered()
+      .into_iter()
+      .map(|color| {
+        Player::new(
+          color.to_string(),
+          color.to_string(),
+          color,
+          false,
+          &game.rules,
+        )
+      })
+      .collect();
+
+    let document = bson::to_bson(&game).unwrap();
+    assert_eq!(bson::from_bson::<Game>(document).unwrap(), game);
+  }
+
+  #[test]
+  fn reads_single_piece_squares() {
+    let mut values = vec![Bson::Null; 52];
+    values[3] = Bson::String("Blue".into());
+    let fields: Fields = bson::from_bson(Bson::Document(doc! { "values": values })).unwrap();
+
+    assert_eq!(fields.get(3).count(Color::Blue), 1);
+    assert!(fields.get(4).is_empty());
+    assert_eq!(
+      bson::to_bson(&fields)
+        .unwrap()
+        .as_document()
+        .unwrap()
+        .get_array("values")
+        .unwrap()[3],
+      Bson::Document(doc! { "Blue": 1_i64 })
+    );
+  }
+}
@@ -0,0 +1,529 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use ludo_core::models::{
+  game::Game,
+  game_event::{GameEvent, LoggedEvent},
+  player::Player,
+};
+
+use super::{DuplicateJoinCode, GameRepository, OpenGame};
+
+enum Migration {
+  Sql(&'static str),
+  // for changes of the stored json documents
+  Rust(fn(&Transaction) -> anyhow::Result<()>),
+}
+
+// every entry is applied once, in order, the number of applied entries is kept in `user_version`
+// never edit an entry that was already released, add a new one instead
+const MIGRATIONS: &[Migration] = &[
+  // the whole game is stored as a json document, the same way mongo keeps it
+  Migration::Sql(
+    "CREATE TABLE games (
+      id TEXT PRIMARY KEY NOT NULL,
+      started INTEGER NOT NULL DEFAULT 0,
+      state TEXT NOT NULL,
+      created_at TEXT NOT NULL
+    );
+    CREATE INDEX games_created_at ON games (created_at);",
+  ),
+  // board squares hold a count of pieces per color instead of a single optional color
+  Migration::Rust(rewrite_games),
+  // append only log of everything that happened in a game
+  Migration::Sql(
+    "CREATE TABLE game_events (
+      game_id TEXT NOT NULL,
+      sequence INTEGER NOT NULL,
+      timestamp INTEGER NOT NULL,
+      event TEXT NOT NULL,
+      PRIMARY KEY (game_id, sequence)
+    );",
+  ),
+  // private games are found by their join code, the password hash never goes into the state
+  Migration::Sql(
+    "ALTER TABLE games ADD COLUMN join_code TEXT;
+    ALTER TABLE games ADD COLUMN password_hash TEXT;
+    CREATE UNIQUE INDEX games_join_code ON games (join_code);",
+  ),
+];
+
+// Single file database for self-hosted deployments
+pub struct SqliteGameRepository {
+  connection: Mutex<Connection>,
+}
+
+impl SqliteGameRepository {
+  /// accepts `sqlite://<path>`, `sqlite://:memory:` opens a database that lives until the server stops
+  pub fn connect(database_url: &str) -> anyhow::Result<Self> {
+    let path = database_url
+      .strip_prefix("sqlite://")
+      .ok_or_else(|| anyhow!("SQLite url has to start with sqlite://"))?;
+    let mut connection = if path == ":memory:" {
+      Connection::open_in_memory()?
+    } else {
+      Connection::open(path)?
+    };
+    migrate(&mut connection)?;
+    Ok(SqliteGameRepository {
+      connection: Mutex::new(connection),
+    })
+  }
+
+  async fn update_game<F>(&self, game_id: &str, update: F) -> anyhow::Result<Game>
+  where
+    F: FnOnce(&mut Game) + Send,
+  {
+    let mut connection = self.connection.lock().await;
+    let transaction = connection.transaction()?;
+    let mut game = match load_game(&transaction, game_id)? {
+      Some(game) => game,
+      None => return Err(anyhow!("Game doesnt exits")),
+    };
+    update(&mut game);
+    transaction.execute(
+      "UPDATE games SET started = ?2, state = ?3 WHERE id = ?1",
+      params![game_id, game.started, serde_json::to_string(&game)?],
+    )?;
+    transaction.commit()?;
+    Ok(game)
+  }
+}
+
+fn migrate(connection: &mut Connection) -> anyhow::Result<()> {
+  let version: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+  for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+    let transaction = connection.transaction()?;
+    match migration {
+      Migration::Sql(sql) => transaction.execute_batch(sql)?,
+      Migration::Rust(migrate) => migrate(&transaction)?,
+    }
+    transaction.pragma_update(None, "user_version", index + 1)?;
+    transaction.commit()?;
+  }
+  Ok(())
+}
+
+// reading a game accepts the older formats, so saving it again upgrades the document
+fn rewrite_games(transaction: &Transaction) -> anyhow::Result<()> {
+  let mut statement = transaction.prepare("SELECT id, state FROM games")?;
+  let games = statement
+    .query_map([], |row| {
+      Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+  for (id, state) in games {
+    let game: Game = serde_json::from_str(&state)?;
+    transaction.execute(
+      "UPDATE games SET state = ?2 WHERE id = ?1",
+      params![id, serde_json::to_string(&game)?],
+    )?;
+  }
+  Ok(())
+}
+
+fn load_game(connection: &Connection, game_id: &str) -> anyhow::Result<Option<Game>> {
+  let state: Option<String> = connection
+    .query_row(
+      "SELECT state FROM games WHERE id = ?1",
+      params![game_id],
+      |row| row.get(0),
+    )
+    .optional()?;
+  match state {
+    Some(state) => Ok(Some(serde_json::from_str(&state)?)),
+    None => Ok(None),
+  }
+}
+
+#[async_trait]
+impl GameRepository for SqliteGameRepository {
+  async fn create_game(&self, game: Game) -> anyhow::Result<String> {
+    let id = Uuid::new_v4().to_simple().to_string();
+    let inserted = self.connection.lock().await.execute(
+      "INSERT INTO games (id, started, state, created_at, join_code) VALUES (?1, ?2, ?3, ?4, ?5)",
+      params![
+        id,
+        game.started,
+        serde_json::to_string(&game)?,
+        chrono::Utc::now().to_rfc3339(),
+        game.join_code
+      ],
+    );
+    match inserted {
+      // the id is a fresh uuid, only the unique index of the join codes can be violated
+      Err(rusqlite::Error::SqliteFailure(error, _))
+        if error.code == rusqlite::ErrorCode::ConstraintViolation =>
+      {
+        Err(DuplicateJoinCode.into())
+      }
+      Err(error) => Err(error.into()),
+      Ok(_) => Ok(id),
+    }
+  }
+
+  async fn find_game(&self, game_id: &str) -> anyhow::Result<Option<Game>> {
+    load_game(&*self.connection.lock().await, game_id)
+  }
+
+  async fn find_open_games(&self) -> anyhow::Result<Vec<OpenGame>> {
+    let connection = self.connection.lock().await;
+    let mut statement =
+      connection.prepare("SELECT id, state, created_at FROM games WHERE started = 0")?;
+    let rows = statement
+      .query_map([], |row| {
+        Ok((
+          row.get::<_, String>(0)?,
+          row.get::<_, String>(1)?,
+          row.get::<_, String>(2)?,
+        ))
+      })?
+      .collect::<Result<Vec<_>, _>>()?;
+    rows
+      .into_iter()
+      .map(|(id, state, created_at)| {
+        Ok(OpenGame {
+          id,
+          created_at: chrono::DateTime::parse_from_rfc3339(&created_at)?.timestamp_millis(),
+          game: serde_json::from_str(&state)?,
+        })
+      })
+      .collect()
+  }
+
+  async fn find_game_id_by_code(&self, join_code: &str) -> anyhow::Result<Option<String>> {
+    let connection = self.connection.lock().await;
+    let id = connection
+      .query_row(
+        "SELECT id FROM games WHERE join_code = ?1",
+        params![join_code],
+        |row| row.get(0),
+      )
+      .optional()?;
+    Ok(id)
+  }
+
+  async fn set_password(&self, game_id: &str, password_hash: &str) -> anyhow::Result<()> {
+    let updated = self.connection.lock().await.execute(
+      "UPDATE games SET password_hash = ?2 WHERE id = ?1",
+      params![game_id, password_hash],
+    )?;
+    match updated {
+      0 => Err(anyhow!("Game doesnt exits")),
+      _ => Ok(()),
+    }
+  }
+
+  async fn find_password(&self, game_id: &str) -> anyhow::Result<Option<String>> {
+    let connection = self.connection.lock().await;
+    let hash = connection
+      .query_row(
+        "SELECT password_hash FROM games WHERE id = ?1",
+        params![game_id],
+        |row| row.get(0),
+      )
+      .optional()?;
+    Ok(hash.flatten())
+  }
+
+  async fn add_player(&self, game_id: &str, new_player: Player) -> anyhow::Result<Option<Game>> {
+    let mut connection = self.connection.lock().await;
+    let transaction = connection.transaction()?;
+    let mut game = match load_game(&transaction, game_id)? {
+      Some(game) => game,
+      None => return Err(anyhow!("Game doesnt exits")),
+    };
+    if game.is_color_taken(new_player.color) {
+      return Ok(None);
+    }
+    game.players.push(new_player);
+    transaction.execute(
+      "UPDATE games SET state = ?2 WHERE id = ?1",
+      params![game_id, serde_json::to_string(&game)?],
+    )?;
+    transaction.commit()?;
+    Ok(Some(game))
+  }
+
+  async fn add_dice_roll(&self, game_id: &str, roll: usize) -> anyhow::Result<Game> {
+    self
+      .update_game(game_id, |game| {
+        game.dice_throws.push(roll);
+        game.dice_rolls += 1;
+      })
+      .await
+  }
+
+  // mirrors the mongo backend, which only overwrites the parts of the game that change during a round
+  async fn update_game_state(&self, game_id: &str, game: &Game) -> anyhow::Result<Game> {
+    self
+      .update_game(game_id, |stored| {
+        stored.fields = game.fields.clone();
+        stored.players = game.players.clone();
+        stored.current_player = game.current_player;
+        stored.dice_throws = game.dice_throws.clone();
+        stored.round_phase = game.round_phase;
+        stored.winner = game.winner;
+        stored.pending_moves = game.pending_moves.clone();
+        stored.dice_rolls = game.dice_rolls;
+      })
+      .await
+  }
+
+  async fn start_game(&self, game_id: &str) -> anyhow::Result<Game> {
+    self.update_game(game_id, |game| game.started = true).await
+  }
+
+  async fn append_event(&self, game_id: &str, event: GameEvent) -> anyhow::Result<LoggedEvent> {
+    let mut connection = self.connection.lock().await;
+    let transaction = connection.transaction()?;
+    let sequence: u64 = transaction.query_row(
+      "SELECT COUNT(*) FROM game_events WHERE game_id = ?1",
+      params![game_id],
+      |row| row.get(0),
+    )?;
+    let logged = LoggedEvent {
+      sequence,
+      timestamp: chrono::Utc::now().timestamp_millis(),
+      event,
+    };
+    transaction.execute(
+      "INSERT INTO game_events (game_id, sequence, timestamp, event) VALUES (?1, ?2, ?3, ?4)",
+      params![
+        game_id,
+        logged.sequence,
+        logged.timestamp,
+        serde_json::to_string(&logged.event)?
+      ],
+    )?;
+    transaction.commit()?;
+    Ok(logged)
+  }
+
+  async fn find_events(&self, game_id: &str) -> anyhow::Result<Vec<LoggedEvent>> {
+    let connection = self.connection.lock().await;
+    let mut statement = connection.prepare(
+      "SELECT sequence, timestamp, event FROM game_events WHERE game_id = ?1 ORDER BY sequence",
+    )?;
+    let rows = statement
+      .query_map(params![game_id], |row| {
+        Ok((row.get(0)?, row.get(1)?, row.get::<_, String>(2)?))
+      })?
+      .collect::<Result<Vec<_>, _>>()?;
+    rows
+      .into_iter()
+      .map(|(sequence, timestamp, event)| {
+        Ok(LoggedEvent {
+          sequence,
+          timestamp,
+          event: serde_json::from_str(&event)?,
+        })
+      })
+      .collect()
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ludo_core::models::{color::Color, rule_set::RuleSet};
+
+  fn repository() -> SqliteGameRepository {
+    SqliteGameRepository::connect("sqlite://:memory:").unwrap()
+  }
+
+  #[actix_web::test]
+  async fn create_and_find_game() {
+    let repository = repository();
+    let first = repository.create_game(Game::new()).await.unwrap();
+    let second = repository.create_game(Game::new()).await.unwrap();
+    assert_ne!(first, second);
+
+    let game = repository.find_game(&first).await.unwrap();
+    assert_eq!(game, Some(Game::new()));
+    assert_eq!(repository.find_game("missing").await.unwrap(), None);
+    assert_eq!(repository.find_open_games().await.unwrap().len(), 2);
+  }
+
+  #[actix_web::test]
+  async fn join_roll_and_start() {
+    let repository = repository();
+    let id = repository.create_game(Game::new()).await.unwrap();
+
+    let player = Player::new(
+      "id".into(),
+      "name".into(),
+      Color::Green,
+      false,
+      &RuleSet::default(),
+    );
+    let game = repository.add_player(&id, player.clone()).await.unwrap();
+    assert_eq!(game.unwrap().players, vec![player.clone()]);
+    // the seat is taken now
+    let taken = repository.add_player(&id, player).await.unwrap();
+    assert_eq!(taken, None);
+
+    let game = repository.add_dice_roll(&id, 6).await.unwrap();
+    assert_eq!(game.dice_throws, vec![6]);
+    assert_eq!(game.dice_rolls, 1);
+
+    let game = repository.start_game(&id).await.unwrap();
+    assert!(game.started);
+    assert!(repository.find_open_games().await.unwrap().is_empty());
+    assert_eq!(repository.find_game(&id).await.unwrap(), Some(game));
+  }
+
+  #[actix_web::test]
+  async fn update_game_state() {
+    let repository = repository();
+    let id = repository.create_game(Game::new()).await.unwrap();
+
+    let mut game = Game::new();
+    game.current_player = Color::Blue;
+    game.dice_throws = vec![6, 2];
+    // not part of the round state, so it is left untouched
+    game.started = true;
+
+    let stored = repository.update_game_state(&id, &game).await.unwrap();
+    assert_eq!(stored.current_player, Color::Blue);
+    assert_eq!(stored.dice_throws, vec![6, 2]);
+    assert!(!stored.started);
+    assert_eq!(repository.find_game(&id).await.unwrap(), Some(stored));
+  }
+
+  #[actix_web::test]
+  async fn append_and_find_events() {
+    let repository = repository();
+    let first = repository.create_game(Game::new()).await.unwrap();
+    let second = repository.create_game(Game::new()).await.unwrap();
+
+    repository
+      .append_event(&first, GameEvent::Rolled(6))
+      .await
+      .unwrap();
+    repository
+      .append_event(&second, GameEvent::Skipped)
+      .await
+      .unwrap();
+    let logged = repository
+      .append_event(&first, GameEvent::Rolled(2))
+      .await
+      .unwrap();
+    assert_eq!(logged.sequence, 1);
+
+    let events = repository.find_events(&first).await.unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].sequence, 0);
+    assert_eq!(events[0].event, GameEvent::Rolled(6));
+    assert_eq!(events[1], logged);
+    assert_eq!(repository.find_events(&second).await.unwrap().len(), 1);
+  }
+
+  #[actix_web::test]
+  async fn missing_game() {
+    let repository = repository();
+    assert!(repository.add_dice_roll("missing", 6).await.is_err());
+    assert!(repository.start_game("missing").await.is_err());
+  }
+
+  #[actix_web::test]
+  async fn join_codes_and_passwords() {
+    let repository = repository();
+    let mut game = Game::new();
+    game.join_code = Some("ABC234".into());
+    let private = repository.create_game(game).await.unwrap();
+    let public = repository.create_game(Game::new()).await.unwrap();
+    // games without a code don't collide, a second game with the code does
+    repository.create_game(Game::new()).await.unwrap();
+    let mut duplicate = Game::new();
+    duplicate.join_code = Some("ABC234".into());
+    let error = repository.create_game(duplicate).await.unwrap_err();
+    assert!(error.is::<DuplicateJoinCode>());
+
+    let found = repository.find_game_id_by_code("ABC234").await.unwrap();
+    assert_eq!(found, Some(private.clone()));
+    let missing = repository.find_game_id_by_code("XYZ789").await.unwrap();
+    assert_eq!(missing, None);
+
+    repository.set_password(&private, "hash").await.unwrap();
+    let hash = repository.find_password(&private).await.unwrap();
+    assert_eq!(hash.as_deref(), Some("hash"));
+    assert_eq!(repository.find_password(&public).await.unwrap(), None);
+    assert!(repository.set_password("missing", "hash").await.is_err());
+  }
+
+  #[test]
+  fn migrations_are_applied_once() {
+    let mut connection = Connection::open_in_memory().unwrap();
+    migrate(&mut connection).unwrap();
+    migrate(&mut connection).unwrap();
+
+    let version: usize = connection
+      .query_row("PRAGMA user_version", [], |row| row.get(0))
+      .unwrap();
+    assert_eq!(version, MIGRATIONS.len());
+  }
+
+  #[test]
+  fn migrates_single_piece_squares() {
+    let mut connection = Connection::open_in_memory().unwrap();
+    let mut game = serde_json::to_value(Game::new()).unwrap();
+    let mut values = vec![serde_json::Value::Null; 52];
+    values[10] = serde_json::json!("Red");
+    game["fields"]["values"] = serde_json::Value::Array(values);
+
+    // a game stored right after the first migration
+    let transaction = connection.transaction().unwrap();
+    if let Migration::Sql(sql) = &MIGRATIONS[0] {
+      transaction.execute_batch(sql).unwrap();
+    }
+    transaction.pragma_update(None, "user_version", 1).unwrap();
+    transaction
+      .execute(
+        "INSERT INTO games (id, started, state, created_at) VALUES ('old', 0, ?1, '')",
+        params![game.to_string()],
+      )
+      .unwrap();
+    transaction.commit().unwrap();
+
+    migrate(&mut connection).unwrap();
+
+    let state: String = connection
+      .query_row("SELECT state FROM games WHERE id = 'old'", [], |row| {
+        row.get(0)
+      })
+      .unwrap();
+    let state: serde_json::Value = serde_json::from_str(&state).unwrap();
+    assert_eq!(
+      state["fields"]["values"][10],
+      serde_json::json!({ "Red": 1 })
+    );
+    assert_eq!(state["fields"]["values"][11], serde_json::json!({}));
+  }
+
+  #[test]
+  fn keeps_games_in_file() {
+    let path = std::env::temp_dir().join(format!("ludo-{}.db", Uuid::new_v4()));
+    let url = format!("sqlite://{}", path.display());
+
+    let id = actix_web::rt::System::new().block_on(async {
+      let repository = SqliteGameRepository::connect(&url).unwrap();
+      let id = repository.create_game(Game::new()).await.unwrap();
+      repository.start_game(&id).await.unwrap();
+      id
+    });
+
+    let game = actix_web::rt::System::new().block_on(async {
+      let repository = SqliteGameRepository::connect(&url).unwrap();
+      repository.find_game(&id).await.unwrap()
+    });
+    std::fs::remove_file(path).unwrap();
+
+    assert!(game.unwrap().started);
+  }
+}
@@ -5,9 +5,15 @@ use super::controller;
 pub fn attach_routes(config: &mut web::ServiceConfig) {
   config.service(
     web::scope("/games")
-      .service(controller::get_games)
+      .service(controller::get_lobbies)
+      .service(controller::find_game_by_code)
       .service(controller::create_new_game)
+      .service(controller::import_game)
       .service(controller::join_game)
-      .service(controller::init_websocket),
+      .service(controller::get_game_events)
+      .service(controller::get_game_replay)
+      .service(controller::export_game)
+      .service(controller::init_websocket)
+      .service(controller::spectate_game),
   );
 }
@@ -1,53 +1,129 @@
-use actix::prelude::{Actor, Context, Handler, Recipient};
-use mongodb::Database;
-use std::{
-  collections::{HashMap, HashSet},
-  sync::Arc,
-};
-use tokio::sync::Mutex;
+use actix::prelude::{Actor, AsyncContext, Context, Handler, Recipient, SpawnHandle};
+use log::debug;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use super::{
   services::{
-    connect_client::connect_client, move_piece::move_piece, promote_piece::promote_piece,
-    roll_die::roll_dice, start_game::start_game,
+    connect_client::{connect_client, connect_spectator},
+    lobby::{choose_color, kick_player, seat_player, send_lobby_state, set_ready},
+    move_piece::move_piece,
+    promote_piece::promote_piece,
+    roll_die::roll_dice,
+    seats::hand_seat_to_bot,
+    snapshot::send_snapshot,
+    start_game::start_game,
+    turn_timer::turn_timeout,
+    undo::{answer_undo, request_undo},
   },
-  utils::send_message_to_room,
+  utils::{send_message, send_message_to_room},
+};
+use crate::components::game::database::Database;
+use crate::utils::dice::Dice;
+use crate::models::actor_messages::{
+  ClientActorMessage, Connect, Disconnect, Kick, Role, TurnStarted, WsMessage,
 };
-use crate::models::{
-  actor_messages::{ClientActorMessage, Connect, Disconnect, WsMessage},
+use crate::models::room_lock::{lock_room, RoomLocks};
+use crate::models::sequence::Sequences;
+use crate::models::turn_timer::TurnTimers;
+use crate::models::undo::Undo;
+use ludo_core::models::{
+  messages::{ClientMessage, ServerMessage},
   position::Position,
 };
-use crate::utils::enums::ClientMessage;
-use crate::utils::enums::ServerMessage;
 
 type Session = Recipient<WsMessage>;
 
+// how long a disconnected player has to come back before a bot takes over their seat
+const GRACE_PERIOD: Duration = Duration::from_secs(30);
+
 #[derive(Clone)]
 pub struct GameServerState {
-  pub db: Arc<Mutex<Database>>,
+  pub db: Database,
+  pub dice: Dice,
+  pub undo: Undo,
+  pub timers: TurnTimers,
+  pub sequences: Sequences,
+  pub room_locks: RoomLocks,
+  // None until the GameServer is started, the turns aren't timed then
+  pub turn_started: Option<Recipient<TurnStarted>>,
+  pub kick: Option<Recipient<Kick>>,
   pub sessions: HashMap<String, Session>,
-  pub rooms: HashMap<String, HashSet<String>>,
+  pub rooms: HashMap<String, HashMap<String, Role>>,
 }
 
-// GameServer actor which keeps track of all the sessions and game rooms (each game room has up to 4 sessions)
+// GameServer actor which keeps track of all the sessions and game rooms (each game room has up to 4 player
+// sessions and any number of spectators)
 pub struct GameServer {
-  db: Arc<Mutex<Database>>,
+  db: Database,
+  dice: Dice,
+  undo: Undo, // snapshots for taking back moves, shared with the services
+  timers: TurnTimers, // deadlines of the turns, shared with the services
+  sequences: Sequences, // numbers of the messages of every room, shared with the services
+  room_locks: RoomLocks, // the game of a room is changed by one service at a time
+  turn_started: Option<Recipient<TurnStarted>>,
+  kick: Option<Recipient<Kick>>,
+  turn_handles: HashMap<String, SpawnHandle>, // room_id => the timer of the current turn
+  grace_period: Duration,
+  leave_handles: HashMap<String, SpawnHandle>, // player_id => the timer handing their seat to a bot
   sessions: HashMap<String, Session>, // player_id => Addres to send messages
-  rooms: HashMap<String, HashSet<String>>, // room_id / game_id => player_id
+  rooms: HashMap<String, HashMap<String, Role>>, // room_id / game_id => player_id => role
 }
 
 impl GameServer {
-  pub fn new(db: Arc<Mutex<Database>>) -> Self {
+  pub fn new(db: Database, dice: Dice) -> Self {
     GameServer {
       db,
+      dice,
+      undo: Undo::default(),
+      timers: TurnTimers::default(),
+      sequences: Sequences::default(),
+      room_locks: RoomLocks::default(),
+      turn_started: None,
+      kick: None,
+      turn_handles: HashMap::new(),
+      grace_period: GRACE_PERIOD,
+      leave_handles: HashMap::new(),
       sessions: HashMap::new(),
       rooms: HashMap::new(),
     }
   }
 
+  pub fn with_grace_period(mut self, grace_period: Duration) -> Self {
+    self.grace_period = grace_period;
+    self
+  }
+
+  // the routes that change a game take the same locks as the services
+  pub fn room_locks(&self) -> RoomLocks {
+    self.room_locks.clone()
+  }
+
+  // sessions of the role connected to the room
+  fn room_size(&self, room_id: &str, role: Role) -> usize {
+    self.rooms.get(room_id).map_or(0, |sessions| {
+      sessions.values().filter(|other| **other == role).count()
+    })
+  }
+
+  fn count_change(&self, room_id: &str, role: Role) -> ServerMessage {
+    let count = self.room_size(room_id, role);
+    match role {
+      Role::Player => ServerMessage::PlayerCountChange(count),
+      Role::Spectator => ServerMessage::SpectatorCountChange(count),
+    }
+  }
+
   pub fn get_state(&self) -> GameServerState {
     GameServerState {
       db: self.db.clone(),
+      dice: self.dice.clone(),
+      undo: self.undo.clone(),
+      timers: self.timers.clone(),
+      sequences: self.sequences.clone(),
+      room_locks: self.room_locks.clone(),
+      turn_started: self.turn_started.clone(),
+      kick: self.kick.clone(),
       sessions: self.sessions.clone(),
       rooms: self.rooms.clone(),
     }
@@ -57,41 +133,68 @@ impl GameServer {
 // Make the game server an actor so it can recieve and send messages to sessions
 impl Actor for GameServer {
   type Context = Context<Self>;
+
+  fn started(&mut self, ctx: &mut Context<Self>) {
+    self.turn_started = Some(ctx.address().recipient());
+    self.kick = Some(ctx.address().recipient());
+  }
+}
+
+// Plays the turn for the player once it runs out, the timer of the previous turn in the room is dropped
+impl Handler<TurnStarted> for GameServer {
+  type Result = ();
+
+  fn handle(&mut self, msg: TurnStarted, ctx: &mut Context<Self>) {
+    let delay = msg
+      .deadline
+      .ends_at
+      .saturating_duration_since(Instant::now());
+    let room_id = msg.room_id.clone();
+    let handle = ctx.run_later(delay, move |server, _| {
+      server.turn_handles.remove(&msg.room_id);
+      let state = server.get_state();
+      actix_web::rt::spawn(async move {
+        turn_timeout(state, msg.room_id, msg.deadline).await;
+      });
+    });
+    if let Some(previous) = self.turn_handles.insert(room_id, handle) {
+      ctx.cancel_future(previous);
+    }
+  }
 }
 
 // Connect a session to the GameServer
 impl Handler<Connect> for GameServer {
   type Result = ();
 
-  fn handle(&mut self, msg: Connect, _: &mut Context<Self>) {
-    println!("connected player with id: {}", msg.player_id);
+  fn handle(&mut self, msg: Connect, ctx: &mut Context<Self>) {
+    debug!("connected {:?} with id {}", msg.role, msg.player_id);
+    // the player came back in time, the seat stays theirs
+    if let Some(leave_handle) = self.leave_handles.remove(&msg.player_id) {
+      ctx.cancel_future(leave_handle);
+    }
     self
       .sessions
       .insert(msg.player_id.clone(), msg.address.clone());
     self
       .rooms
       .entry(msg.room_id.clone())
-      .or_insert_with(HashSet::new)
-      .insert(msg.player_id.clone());
-
-    let count = self.sessions.len();
-    let server_msg = ServerMessage::PlayerCountChange(count);
-    let json = serde_json::to_string(&server_msg).unwrap();
+      .or_default()
+      .insert(msg.player_id.clone(), msg.role);
 
-    send_message_to_room(
-      json.as_str(),
-      self.sessions.clone(),
-      self.rooms.clone(),
-      msg.room_id.as_str(),
-    );
+    let server_msg = self.count_change(&msg.room_id, msg.role);
+    send_message_to_room(&self.get_state(), &msg.room_id, &server_msg);
 
     let state = self.get_state();
+    if msg.role == Role::Spectator {
+      actix_web::rt::spawn(async move { connect_spectator(state, &msg).await });
+      return;
+    }
     actix_web::rt::spawn(async move {
-      println!(
-        "responded with connect message to player: {}",
-        msg.player_id
-      );
-      connect_client(state, &msg).await;
+      let guard = lock_room(&state.room_locks, &msg.room_id).await;
+      connect_client(state.clone(), &msg).await;
+      drop(guard);
+      send_lobby_state(state, &msg.room_id).await;
     });
   }
 }
@@ -100,30 +203,69 @@ impl Handler<Connect> for GameServer {
 impl Handler<Disconnect> for GameServer {
   type Result = ();
 
-  fn handle(&mut self, msg: Disconnect, _: &mut Context<Self>) {
-    println!("Someone left the game");
+  fn handle(&mut self, msg: Disconnect, ctx: &mut Context<Self>) {
+    debug!("{} left room {}", msg.player_id, msg.room_id);
 
-    let mut rooms: Vec<String> = Vec::new();
+    // a session that timed out after the player reconnected doesn't end the new one
+    if self.sessions.get(&msg.player_id) != Some(&msg.address) {
+      return;
+    }
+    self.sessions.remove(&msg.player_id);
+    let role = self
+      .rooms
+      .get_mut(&msg.room_id)
+      .and_then(|sessions| sessions.remove(&msg.player_id));
+    // spectators have no seat to keep
+    if role == Some(Role::Spectator) {
+      let server_msg = self.count_change(&msg.room_id, Role::Spectator);
+      send_message_to_room(&self.get_state(), &msg.room_id, &server_msg);
+      return;
+    }
 
-    if self.sessions.remove(&msg.player_id).is_some() {
-      for (game_id, sessions) in &mut self.rooms {
-        if sessions.remove(&msg.player_id) {
-          rooms.push(game_id.to_owned());
-        }
-      }
+    let (room_id, player_id) = (msg.room_id.clone(), msg.player_id.clone());
+    let leave_handle = ctx.run_later(self.grace_period, move |server, _| {
+      server.leave_handles.remove(&player_id);
+      let state = server.get_state();
+      actix_web::rt::spawn(async move {
+        hand_seat_to_bot(state, room_id, player_id).await;
+      });
+    });
+    if let Some(previous) = self
+      .leave_handles
+      .insert(msg.player_id.clone(), leave_handle)
+    {
+      ctx.cancel_future(previous);
     }
 
-    let server_msg = ServerMessage::PlayerCountChange(self.sessions.len());
-    let json = serde_json::to_string(&server_msg).unwrap();
+    let server_msg = self.count_change(&msg.room_id, Role::Player);
+    send_message_to_room(&self.get_state(), &msg.room_id, &server_msg);
 
-    for room in rooms {
-      send_message_to_room(
-        json.as_str(),
-        self.sessions.clone(),
-        self.rooms.clone(),
-        room.as_str(),
-      );
+    let state = self.get_state();
+    actix_web::rt::spawn(async move {
+      send_lobby_state(state, &msg.room_id).await;
+    });
+  }
+}
+
+// The kicked player already got Kicked, their session leaves the room without keeping the seat
+impl Handler<Kick> for GameServer {
+  type Result = ();
+
+  fn handle(&mut self, msg: Kick, _ctx: &mut Context<Self>) {
+    let in_room = self
+      .rooms
+      .get_mut(&msg.room_id)
+      .and_then(|sessions| sessions.remove(&msg.player_id))
+      .is_some();
+    if !in_room {
+      return;
+    }
+    // the Disconnect of the closed session finds no session anymore and is ignored
+    if let Some(session) = self.sessions.remove(&msg.player_id) {
+      session.do_send(WsMessage::Close("Kicked".into()));
     }
+    let server_msg = self.count_change(&msg.room_id, Role::Player);
+    send_message_to_room(&self.get_state(), &msg.room_id, &server_msg);
   }
 }
 
@@ -141,10 +283,19 @@ impl Handler<ClientActorMessage> for GameServer {
     };
 
     let state = self.get_state();
+    let role = self
+      .rooms
+      .get(&msg.room_id)
+      .and_then(|sessions| sessions.get(&msg.player_id));
+    if role == Some(&Role::Spectator) {
+      let error = ServerMessage::Error("Spectators can only watch the game".into());
+      return send_message(&state, &msg.room_id, &msg.player_id, &error);
+    }
     actix_web::rt::spawn(async move {
+      let _guard = lock_room(&state.room_locks, &msg.room_id).await;
       match message {
         ClientMessage::ThrowDice => roll_dice(state, msg).await,
-        ClientMessage::MoveFigure(position, color) => {
+        ClientMessage::MoveFigure(position, color, dice_value) => {
           move_piece(
             state,
             msg,
@@ -152,11 +303,21 @@ impl Handler<ClientActorMessage> for GameServer {
               position,
               is_home: color.is_some(),
             },
+            dice_value,
           )
           .await
         }
-        ClientMessage::PromotePiece => promote_piece(state, msg).await,
+        ClientMessage::PromotePiece(dice_value) => promote_piece(state, msg, dice_value).await,
         ClientMessage::StartGame => start_game(state, msg).await,
+        ClientMessage::RequestUndo => request_undo(state, msg).await,
+        ClientMessage::AnswerUndo(accept) => answer_undo(state, msg, accept).await,
+        ClientMessage::RequestSnapshot => send_snapshot(state, msg).await,
+        ClientMessage::ChooseColor(color) => choose_color(state, msg, color).await,
+        ClientMessage::Ready(ready) => set_ready(state, msg, ready).await,
+        ClientMessage::KickPlayer(color) => kick_player(state, msg, color).await,
+        ClientMessage::SeatPlayer(from, to) => seat_player(state, msg, from, to).await,
+        // the session already checked the token it was opened with
+        ClientMessage::Authenticate(_) => {}
       };
     });
 
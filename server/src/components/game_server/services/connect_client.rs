@@ -1,47 +1,195 @@
 use crate::{
-  components::{
-    game::database,
-    game_server::{actor::GameServerState, utils::send_message},
+  components::game_server::{
+    actor::GameServerState,
+    services::{
+      seats::return_seat,
+      utils::{send_available_positions, sync_turn_timer},
+    },
+    utils::send_message,
   },
   models::actor_messages::Connect,
-  utils::enums::ServerMessage,
+};
+use ludo_core::{
+  models::{color::Color, game::Game, messages::ServerMessage},
+  utils::enums::RoundPhase,
 };
 
 pub async fn connect_client(state: GameServerState, msg: &Connect) {
-  let game = database::find_game(&state.db, &msg.room_id).await;
+  let game = state.db.find_game(&msg.room_id).await;
 
   let game = match game {
     Ok(Some(game)) => game,
     Ok(None) => {
-      let message = serde_json::to_string(&ServerMessage::Error("Game not found".into())).unwrap();
-      send_message(message.as_str(), state.sessions, &msg.player_id);
+      send_message(
+        &state,
+        &msg.room_id,
+        &msg.player_id,
+        &ServerMessage::Error("Game not found".into()),
+      );
       return;
     }
     Err(_) => {
-      let message = serde_json::to_string(&ServerMessage::Error(
-        "Server couldn't connect to database".into(),
-      ))
-      .unwrap();
-      send_message(message.as_str(), state.sessions, &msg.player_id);
+      send_message(
+        &state,
+        &msg.room_id,
+        &msg.player_id,
+        &ServerMessage::Error("Server couldn't connect to database".into()),
+      );
       return;
     }
   };
 
   let player = game.get_player_by_id(&msg.player_id);
 
-  let player = match player {
-    Some(player) => player,
+  let (color, away) = match player {
+    Some(player) => (player.color, player.away),
     None => {
-      let message = serde_json::to_string(&ServerMessage::Error(
-        "Player with given id not found".into(),
-      ))
-      .unwrap();
-      send_message(message.as_str(), state.sessions, &msg.player_id);
+      send_message(
+        &state,
+        &msg.room_id,
+        &msg.player_id,
+        &ServerMessage::Error("Player with given id not found".into()),
+      );
+      return;
+    }
+  };
+
+  // a bot was playing for the player while they were gone
+  let game = match away {
+    true => return_seat(&state, &msg.room_id, game, color).await,
+    false => game,
+  };
+
+  send_message(
+    &state,
+    &msg.room_id,
+    &msg.player_id,
+    &ServerMessage::ConnectResponse(game.clone(), color),
+  );
+  resync_turn(state, msg, &game, color);
+}
+
+// sends what the player missed of the turn being played, so they can go on with it after reconnecting
+fn resync_turn(state: GameServerState, msg: &Connect, game: &Game, color: Color) {
+  if !game.started || game.winner.is_some() {
+    return;
+  }
+  send_last_roll(&state, msg, game);
+  if game.round_phase == RoundPhase::Moving && game.current_player == color {
+    send_available_positions(state, &msg.room_id, game);
+  }
+}
+
+// spectators get the game and the roll of the turn being played, then the messages of the room
+pub async fn connect_spectator(state: GameServerState, msg: &Connect) {
+  let game = match state.db.find_game(&msg.room_id).await {
+    Ok(Some(game)) => game,
+    _ => {
+      send_message(
+        &state,
+        &msg.room_id,
+        &msg.player_id,
+        &ServerMessage::Error("Game not found".into()),
+      );
       return;
     }
   };
 
-  let message =
-    serde_json::to_string(&ServerMessage::ConnectResponse(game.clone(), player.color)).unwrap();
-  send_message(message.as_str(), state.sessions, &msg.player_id);
+  send_message(
+    &state,
+    &msg.room_id,
+    &msg.player_id,
+    &ServerMessage::Spectating(game.clone()),
+  );
+  if game.started && game.winner.is_none() {
+    send_last_roll(&state, msg, &game);
+  }
+}
+
+fn send_last_roll(state: &GameServerState, msg: &Connect, game: &Game) {
+  if let Some(roll) = game.dice_throws.last() {
+    let can_roll_again =
+      game.round_phase == RoundPhase::Rolling && game.rules.can_roll_again(&game.dice_throws);
+    let time_left = sync_turn_timer(state, &msg.room_id, game);
+    send_message(
+      state,
+      &msg.room_id,
+      &msg.player_id,
+      &ServerMessage::DiceValue(*roll, can_roll_again, time_left),
+    );
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use tokio_tungstenite::connect_async;
+
+  use ludo_core::models::messages::{ClientMessage, ServerMessage};
+
+  use crate::utils::testing::*;
+
+  #[actix_web::test]
+  async fn spectators_only_watch() {
+    let app_data = app_data();
+    let address = spawn_server(app_data.clone());
+    let (room, mut sockets) = connect_players(
+      app_data.clone(),
+      &address,
+      serde_json::json!({}),
+      &["green"],
+    )
+    .await;
+    let url = format!("ws://{}/games/websocket/{}/spectate", address, room);
+    let (mut spectator, _) = connect_async(url).await.unwrap();
+    wait_for(&mut spectator, |message| {
+      matches!(message, ServerMessage::Spectating(_))
+    })
+    .await;
+    let player = &mut sockets[0];
+    wait_for(player, |message| {
+      matches!(message, ServerMessage::SpectatorCountChange(1))
+    })
+    .await;
+
+    send_message(&mut spectator, ClientMessage::ThrowDice).await;
+    assert_eq!(
+      next_error(&mut spectator).await,
+      "Spectators can only watch the game"
+    );
+
+    // the spectator sees the game being played
+    send_message(player, ClientMessage::Ready(true)).await;
+    wait_for(player, ready_count(1)).await;
+    send_message(player, ClientMessage::StartGame).await;
+    let started = |message: &ServerMessage| matches!(message, ServerMessage::GameStarted(_));
+    wait_for(player, started).await;
+    wait_for(&mut spectator, started).await;
+    send_message(player, ClientMessage::ThrowDice).await;
+    wait_for(&mut spectator, |message| {
+      matches!(message, ServerMessage::DiceValue(..))
+    })
+    .await;
+
+    spectator.close(None).await.unwrap();
+    wait_for(player, |message| {
+      matches!(message, ServerMessage::SpectatorCountChange(0))
+    })
+    .await;
+    let game = app_data.db.find_game(&room).await.unwrap().unwrap();
+    let humans = game.players.iter().filter(|player| !player.is_bot);
+    assert_eq!(humans.count(), 1);
+  }
+
+  #[actix_web::test]
+  async fn games_with_a_password_cant_be_watched() {
+    let app_data = app_data();
+    let address = spawn_server(app_data.clone());
+    let app = app(app_data).await;
+    let body = serde_json::json!({ "private": true, "password": "secret" });
+    let room = create_game(&app, body).await;
+    let url = format!("ws://{}/games/websocket/{}/spectate", address, room);
+    assert!(connect_async(url).await.is_err());
+  }
 }
@@ -0,0 +1,433 @@
+use crate::components::{
+  game::database::log_event,
+  game_server::{
+    actor::GameServerState,
+    utils::{send_message, send_message_to_room},
+  },
+};
+use crate::models::actor_messages::{ClientActorMessage, Kick};
+use ludo_core::models::{
+  color::Color, game::Game, game_event::GameEvent, lobby::LobbyPlayer, messages::ServerMessage,
+};
+
+// sends the room who joined it, which of them are connected and who is the host
+pub async fn send_lobby_state(state: GameServerState, room_id: &str) {
+  let game = match state.db.find_game(room_id).await {
+    Ok(Some(game)) => game,
+    _ => return,
+  };
+  let connected = state.rooms.get(room_id);
+  let roster = game
+    .players
+    .iter()
+    .map(|player| LobbyPlayer {
+      name: player.name.clone(),
+      color: player.color,
+      is_bot: player.is_bot,
+      connected: player.is_bot || connected.is_some_and(|ids| ids.contains_key(&player.id)),
+      is_host: game.is_host(&player.id),
+      ready: player.ready,
+    })
+    .collect();
+  send_message_to_room(&state, room_id, &ServerMessage::LobbyState(roster));
+}
+
+fn send_error(state: &GameServerState, msg: &ClientActorMessage, error: &str) {
+  send_message(
+    state,
+    &msg.room_id,
+    &msg.player_id,
+    &ServerMessage::Error(error.into()),
+  );
+}
+
+// the game of the room, as long as it is still in the lobby
+async fn find_lobby(state: &GameServerState, msg: &ClientActorMessage) -> Option<Game> {
+  let game = match state.db.find_game(&msg.room_id).await {
+    Ok(Some(game)) => game,
+    _ => {
+      send_error(state, msg, "Cannot find game");
+      return None;
+    }
+  };
+  if game.started {
+    send_error(state, msg, "The game has already started");
+    return None;
+  }
+  Some(game)
+}
+
+// the lobby of the room when the sender is its host
+async fn find_lobby_as_host(state: &GameServerState, msg: &ClientActorMessage) -> Option<Game> {
+  let game = find_lobby(state, msg).await?;
+  if !game.is_host(&msg.player_id) {
+    send_error(state, msg, "Only the host can change the seats of others");
+    return None;
+  }
+  Some(game)
+}
+
+fn sender_color(state: &GameServerState, msg: &ClientActorMessage, game: &Game) -> Option<Color> {
+  let color = game
+    .get_player_by_id(&msg.player_id)
+    .map(|player| player.color);
+  if color.is_none() {
+    send_error(state, msg, "You are not playing in this game");
+  }
+  color
+}
+
+// stores the changed lobby, logs what changed and sends the room the new roster
+async fn update_lobby(
+  state: &GameServerState,
+  msg: &ClientActorMessage,
+  game: &Game,
+  event: GameEvent,
+) -> bool {
+  if state
+    .db
+    .update_game_state(&msg.room_id, game)
+    .await
+    .is_err()
+  {
+    send_error(state, msg, "Cannot update the game");
+    return false;
+  }
+  log_event(&state.db, &msg.room_id, event).await;
+  send_lobby_state(state.clone(), &msg.room_id).await;
+  true
+}
+
+// the players who sat at the colors are told where they sit now
+fn send_seats(state: &GameServerState, room_id: &str, game: &Game, colors: &[Color]) {
+  for player in game
+    .players
+    .iter()
+    .filter(|player| colors.contains(&player.color))
+  {
+    let message = ServerMessage::ConnectResponse(game.clone(), player.color);
+    send_message(state, room_id, &player.id, &message);
+  }
+}
+
+pub async fn choose_color(state: GameServerState, msg: ClientActorMessage, color: Color) {
+  let mut game = match find_lobby(&state, &msg).await {
+    Some(game) => game,
+    None => return,
+  };
+  let from = match sender_color(&state, &msg, &game) {
+    Some(from) => from,
+    None => return,
+  };
+  if game.is_color_taken(color) {
+    return send_error(&state, &msg, "The color is already taken");
+  }
+
+  game.seat_player(from, color);
+  let event = GameEvent::Seated { from, to: color };
+  if update_lobby(&state, &msg, &game, event).await {
+    send_seats(&state, &msg.room_id, &game, &[color]);
+  }
+}
+
+pub async fn set_ready(state: GameServerState, msg: ClientActorMessage, ready: bool) {
+  let mut game = match find_lobby(&state, &msg).await {
+    Some(game) => game,
+    None => return,
+  };
+  let color = match sender_color(&state, &msg, &game) {
+    Some(color) => color,
+    None => return,
+  };
+
+  game.get_player_mut(color).ready = ready;
+  update_lobby(&state, &msg, &game, GameEvent::Ready(color, ready)).await;
+}
+
+pub async fn kick_player(state: GameServerState, msg: ClientActorMessage, color: Color) {
+  let mut game = match find_lobby_as_host(&state, &msg).await {
+    Some(game) => game,
+    None => return,
+  };
+  let player_id = match game.players.iter().find(|player| player.color == color) {
+    Some(player) if player.id == msg.player_id => {
+      return send_error(&state, &msg, "The host cannot kick themselves")
+    }
+    Some(player) => player.id.clone(),
+    None => return send_error(&state, &msg, "Nobody sits at the color"),
+  };
+
+  game.remove_player(color);
+  // the player is told before the roster without them goes out, they can't connect again
+  send_message(&state, &msg.room_id, &player_id, &ServerMessage::Kicked);
+  if let Some(kick) = &state.kick {
+    kick.do_send(Kick {
+      room_id: msg.room_id.clone(),
+      player_id,
+    });
+  }
+  update_lobby(&state, &msg, &game, GameEvent::Kicked(color)).await;
+}
+
+// the player moves to the other color, whoever sat there gets the old color of the player
+pub async fn seat_player(state: GameServerState, msg: ClientActorMessage, from: Color, to: Color) {
+  let mut game = match find_lobby_as_host(&state, &msg).await {
+    Some(game) => game,
+    None => return,
+  };
+  if !game.is_color_taken(from) {
+    return send_error(&state, &msg, "Nobody sits at the color");
+  }
+
+  game.seat_player(from, to);
+  if update_lobby(&state, &msg, &game, GameEvent::Seated { from, to }).await {
+    send_seats(&state, &msg.room_id, &game, &[from, to]);
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use actix_web::http::StatusCode;
+  use actix_web::test::{call_service, read_body, TestRequest};
+  use futures::StreamExt;
+  use std::time::Duration;
+  use tokio::time::timeout;
+  use tokio_tungstenite::tungstenite::Message;
+
+  use ludo_core::models::{
+    color::Color,
+    game::Game,
+    game_event::GameEvent,
+    messages::{ClientMessage, ServerMessage},
+  };
+
+  use crate::models::room_lock::lock_room;
+  use crate::utils::testing::*;
+
+  #[actix_web::test]
+  async fn join_while_changing_color() {
+    let app_data = app_data();
+    let address = spawn_server(app_data.clone());
+    let app = app(app_data.clone()).await;
+    let (room, mut sockets) =
+      connect_players(app_data.clone(), &address, serde_json::json!({}), &["host"]).await;
+    let host = &mut sockets[0];
+
+    // the join and the color change both wait while the lobby is being changed
+    let guard = lock_room(&app_data.room_locks, &room).await;
+    send_message(host, ClientMessage::ChooseColor(Color::Red)).await;
+    let join = join_game(&app, &room, "guest");
+    tokio::pin!(join);
+    assert!(timeout(Duration::from_millis(100), &mut join)
+      .await
+      .is_err());
+    drop(guard);
+    let joined = join.await;
+    wait_for(host, |message| {
+      matches!(message, ServerMessage::ConnectResponse(_, Color::Red))
+    })
+    .await;
+
+    // neither of them is lost and the log agrees with the stored game
+    let game = app_data.db.find_game(&room).await.unwrap().unwrap();
+    assert_eq!(game.players.len(), 2);
+    assert_eq!(game.get_player(Color::Red).name, "host");
+    let guest = game.get_player_by_id(&joined.player_id).unwrap();
+    assert_ne!(guest.color, Color::Red);
+    let events = app_data.db.find_events(&room).await.unwrap();
+    assert_eq!(
+      Game::from_events(events.iter().map(|logged| &logged.event)),
+      game
+    );
+
+    // nobody joins a game that has started
+    let (room, mut sockets) =
+      connect_players(app_data.clone(), &address, serde_json::json!({}), &["host"]).await;
+    ready_up(&mut sockets).await;
+    send_message(&mut sockets[0], ClientMessage::StartGame).await;
+    wait_for(&mut sockets[0], |message| {
+      matches!(message, ServerMessage::GameStarted(_))
+    })
+    .await;
+    let request = TestRequest::put()
+      .uri(&format!("/games/{}", room))
+      .set_json(serde_json::json!({ "name": "late" }))
+      .to_request();
+    let response = call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(read_body(response).await, "The game has already started");
+  }
+
+  #[actix_web::test]
+  async fn lobby_counts_only_the_room() {
+    let app_data = app_data();
+    let address = spawn_server(app_data.clone());
+    let (_, mut sockets) = connect_players(
+      app_data.clone(),
+      &address,
+      serde_json::json!({}),
+      &["green", "yellow"],
+    )
+    .await;
+    // a player of another room doesn't change the count
+    let (_, _other) = connect_players(app_data, &address, serde_json::json!({}), &["red"]).await;
+
+    let mut yellow = sockets.pop().unwrap();
+    yellow.close(None).await.unwrap();
+    let green = &mut sockets[0];
+    assert_eq!(
+      wait_for(green, |message| matches!(
+        message,
+        ServerMessage::PlayerCountChange(_)
+      ))
+      .await,
+      ServerMessage::PlayerCountChange(2)
+    );
+    assert_eq!(
+      wait_for(green, |message| matches!(
+        message,
+        ServerMessage::PlayerCountChange(_)
+      ))
+      .await,
+      ServerMessage::PlayerCountChange(1)
+    );
+    let roster = match wait_for(green, |message| {
+      matches!(message, ServerMessage::LobbyState(_))
+    })
+    .await
+    {
+      ServerMessage::LobbyState(roster) => roster,
+      message => panic!("unexpected message {:?}", message),
+    };
+    let status = roster
+      .iter()
+      .map(|player| (player.name.as_str(), player.connected, player.is_host))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      status,
+      vec![("green", true, true), ("yellow", false, false)]
+    );
+  }
+
+  #[actix_web::test]
+  async fn host_runs_the_lobby() {
+    let app_data = app_data();
+    let address = spawn_server(app_data.clone());
+    let (room, mut sockets) = connect_players(
+      app_data.clone(),
+      &address,
+      serde_json::json!({}),
+      &["host", "guest", "other"],
+    )
+    .await;
+
+    // the guest can only move themselves
+    send_message(&mut sockets[1], ClientMessage::StartGame).await;
+    assert_eq!(
+      next_error(&mut sockets[1]).await,
+      "Only the host can start the game"
+    );
+    send_message(&mut sockets[1], ClientMessage::KickPlayer(Color::Blue)).await;
+    next_error(&mut sockets[1]).await;
+    send_message(&mut sockets[1], ClientMessage::ChooseColor(Color::Blue)).await;
+    assert_eq!(
+      next_error(&mut sockets[1]).await,
+      "The color is already taken"
+    );
+    send_message(&mut sockets[1], ClientMessage::ChooseColor(Color::Red)).await;
+    assert!(matches!(
+      wait_for(&mut sockets[1], |message| {
+        matches!(message, ServerMessage::ConnectResponse(..))
+      })
+      .await,
+      ServerMessage::ConnectResponse(_, Color::Red)
+    ));
+
+    // the host moves the other player to the seat of the host
+    send_message(
+      &mut sockets[0],
+      ClientMessage::SeatPlayer(Color::Blue, Color::Green),
+    )
+    .await;
+    assert!(matches!(
+      wait_for(&mut sockets[2], |message| {
+        matches!(message, ServerMessage::ConnectResponse(..))
+      })
+      .await,
+      ServerMessage::ConnectResponse(_, Color::Green)
+    ));
+    send_message(&mut sockets[0], ClientMessage::KickPlayer(Color::Red)).await;
+    wait_for(&mut sockets[1], |message| {
+      matches!(message, ServerMessage::Kicked)
+    })
+    .await;
+    // the server closes the websocket of the kicked player
+    let mut guest = sockets.remove(1);
+    let closed = timeout(Duration::from_secs(5), async {
+      while let Some(Ok(frame)) = guest.next().await {
+        if let Message::Close(_) = frame {
+          return;
+        }
+      }
+    });
+    closed.await.unwrap();
+    // the count of the room goes down without waiting for the seat to be handed to a bot
+    wait_for(&mut sockets[0], |message| {
+      matches!(message, ServerMessage::PlayerCountChange(3))
+    })
+    .await;
+    assert_eq!(
+      wait_for(&mut sockets[0], |message| matches!(
+        message,
+        ServerMessage::PlayerCountChange(_)
+      ))
+      .await,
+      ServerMessage::PlayerCountChange(2)
+    );
+
+    send_message(&mut sockets[0], ClientMessage::StartGame).await;
+    assert_eq!(
+      next_error(&mut sockets[0]).await,
+      "Not every player is ready"
+    );
+    ready_up(&mut sockets).await;
+    send_message(&mut sockets[0], ClientMessage::StartGame).await;
+    let game = match wait_for(&mut sockets[0], |message| {
+      matches!(message, ServerMessage::GameStarted(_))
+    })
+    .await
+    {
+      ServerMessage::GameStarted(game) => game,
+      _ => unreachable!(),
+    };
+    let seats = game
+      .players
+      .iter()
+      .map(|player| (player.name.as_str(), player.color, player.is_bot))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      seats[..2],
+      [("host", Color::Blue, false), ("other", Color::Green, false)]
+    );
+    assert_eq!(game.players.len(), 4);
+
+    send_message(&mut sockets[0], ClientMessage::StartGame).await;
+    assert_eq!(
+      next_error(&mut sockets[0]).await,
+      "The game has already started"
+    );
+
+    // the lobby is part of the log as well
+    let events = app_data.db.find_events(&room).await.unwrap();
+    assert!(events
+      .iter()
+      .any(|logged| logged.event == GameEvent::Kicked(Color::Red)));
+    let lobby = events
+      .iter()
+      .take_while(|logged| !matches!(logged.event, GameEvent::Started(_)))
+      .map(|logged| &logged.event);
+    assert_eq!(Game::from_events(lobby).players.len(), 2);
+  }
+}
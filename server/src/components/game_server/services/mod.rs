@@ -1,7 +1,119 @@
 pub mod connect_client;
+pub mod lobby;
 pub mod move_bot;
 pub mod move_piece;
 pub mod promote_piece;
 pub mod roll_die;
+pub mod seats;
+pub mod snapshot;
 pub mod start_game;
+pub mod turn_timer;
+pub mod undo;
 pub mod utils;
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::HashMap, sync::Arc};
+
+  use ludo_core::models::{game::Game, position::Position};
+  use ludo_core::utils::{enums::RoundPhase, player::get_die_moves};
+
+  use super::{
+    lobby::set_ready, move_piece::move_piece, promote_piece::promote_piece, roll_die::roll_dice,
+    start_game::start_game,
+  };
+  use crate::components::game::database::Database;
+  use crate::components::game_server::actor::GameServerState;
+  use crate::models::actor_messages::ClientActorMessage;
+  use crate::models::{room_lock::RoomLocks, sequence::Sequences};
+  use crate::models::{turn_timer::TurnTimers, undo::Undo};
+  use crate::utils::dice::SeededDice;
+  use crate::utils::testing::{app, app_data, create_game, join_game};
+
+  fn message(room: &str, player_id: &str) -> ClientActorMessage {
+    ClientActorMessage {
+      content: String::new(),
+      room_id: room.to_string(),
+      player_id: player_id.to_string(),
+    }
+  }
+
+  // the log has no gaps and folding it gives the stored game
+  async fn assert_consistent(db: &Database, room: &str) -> Game {
+    let game = db.find_game(room).await.unwrap().unwrap();
+    let events = db.find_events(room).await.unwrap();
+    for (sequence, logged) in events.iter().enumerate() {
+      assert_eq!(logged.sequence, sequence as u64);
+    }
+    assert_eq!(
+      Game::from_events(events.iter().map(|logged| &logged.event)),
+      game
+    );
+    game
+  }
+
+  // four players without bots, every one of them plays the first option offered
+  #[actix_web::test]
+  async fn event_log_matches_snapshot() {
+    let app_data = app_data();
+    let db = app_data.db.clone();
+    let app = app(app_data).await;
+
+    let room = create_game(&app, serde_json::json!({ "seed": 2022 })).await;
+    let mut player_ids = vec![];
+    for name in ["a", "b", "c", "d"] {
+      player_ids.push(join_game(&app, &room, name).await.player_id);
+    }
+
+    let state = GameServerState {
+      db: db.clone(),
+      dice: Arc::new(SeededDice),
+      undo: Undo::default(),
+      timers: TurnTimers::default(),
+      sequences: Sequences::default(),
+      room_locks: RoomLocks::default(),
+      turn_started: None,
+      kick: None,
+      sessions: HashMap::new(),
+      rooms: HashMap::new(),
+    };
+    for player_id in &player_ids {
+      set_ready(state.clone(), message(&room, player_id), true).await;
+    }
+    start_game(state.clone(), message(&room, &player_ids[0])).await;
+
+    let mut game = assert_consistent(&db, &room).await;
+    for _ in 0..300 {
+      if game.winner.is_some() {
+        break;
+      }
+      let msg = message(&room, &game.get_current_player_id());
+      match game.round_phase {
+        RoundPhase::Rolling => roll_dice(state.clone(), msg).await,
+        RoundPhase::Moving => {
+          let die_moves = get_die_moves(&game);
+          let die = die_moves.iter().find(|die| !die.is_empty()).unwrap();
+          if die.can_promote {
+            promote_piece(state.clone(), msg, Some(die.dice_value)).await;
+          } else if let Some(position) = die.positions_on_board.first() {
+            let position = Position {
+              position: *position,
+              is_home: false,
+            };
+            move_piece(state.clone(), msg, position, Some(die.dice_value)).await;
+          } else {
+            let position = Position {
+              position: die.positions_in_home[0],
+              is_home: true,
+            };
+            move_piece(state.clone(), msg, position, Some(die.dice_value)).await;
+          }
+        }
+      }
+      game = assert_consistent(&db, &room).await;
+    }
+    assert!(game.dice_rolls > 100);
+  }
+}
@@ -1,11 +1,12 @@
+use crate::components::game::database::log_event;
 use crate::components::game_server::actor::GameServerState;
 use crate::components::game_server::services::utils::{
   send_game_update_message, send_roll_message, skip_player,
 };
-use crate::models::actor_messages::ClientActorMessage;
-use crate::models::game::Game;
-use crate::utils::dice::get_dice_value;
-use crate::utils::enums::MoveResult;
+use crate::models::{actor_messages::ClientActorMessage, room_lock::lock_room};
+use ludo_core::models::{game::Game, game_event::GameEvent};
+use ludo_core::utils::enums::{Move, MoveResult, RoundPhase};
+use ludo_core::utils::player::{next_available_positions, play_round};
 use tokio::time::{sleep, Duration};
 
 // TODO:
@@ -19,23 +20,9 @@ use tokio::time::{sleep, Duration};
 // let message = serde_json::to_string(&ServerMessage::Error("something".into())).unwrap();
 //       send_message(message.as_str(), state.sessions, &msg.player_id);
 //
-fn move_result_update_game(game: &mut Game, move_result: MoveResult) {
-  match move_result {
-    MoveResult::Success(_) => {
-      game.update_current_player();
-      game.dice_throws.clear();
-    }
-    MoveResult::Winner(winner) => game.finish_game(winner),
-    MoveResult::Error(msg) => {
-      game.update_current_player();
-      game.dice_throws.clear();
-      println!("move_result_update_game - MoveResult::Error: {}", msg);
-    }
-  }
-}
 
 /// Bot move algorithm:
-/// 0. throw dice, skip turn if 18, otherwise
+/// 0. throw dice, skip turn if the rolls forfeit it, otherwise
 /// 1. jump to finish (from main field), if possible, otherwise
 /// 2. jump to home (from main field),
 /// 3. add new piece if only 1 is in main field
@@ -45,198 +32,136 @@ fn move_result_update_game(game: &mut Game, move_result: MoveResult) {
 /// 7. jump from home to finish
 /// 8. move piece forward in home
 /// 9. If none of the above possible, there are no valid moves, skip turn.
-
+///
+/// When the dice are spent separately steps 1-8 are repeated for every die.
 pub async fn move_bot(state: GameServerState, msg: &ClientActorMessage, game: &mut Game) {
   let mut game = game.clone();
   while game.is_current_player_ai() {
     sleep(Duration::from_millis(3000)).await; // TODO: add sleep to other bot messages?
 
-    let throw_sum = throw_dice_bot_messages(state.clone(), msg).await;
-    // skip bot's move
-    if throw_sum == 18 {
-      game = skip_player(state.clone(), msg, &mut game).await;
-      continue;
+    // the move that handed the turn to the bot could have been taken back in the meantime
+    let _guard = lock_room(&state.room_locks, &msg.room_id).await;
+    match state.db.find_game(&msg.room_id).await {
+      Ok(Some(stored)) if stored == game => {}
+      _ => return,
     }
 
-    // ----------[ handles jumping from main field ]----------
-
-    let player = game.get_current_player();
-    let positions = game.get_players_pieces_positions(player.color);
+    game = play_turn(state.clone(), msg, game).await;
+    if game.winner.is_some() {
+      return;
+    }
+  }
+}
 
-    // -----[ 1. jump to finish ]-----
+// the bots play in the background, the room isn't locked while they wait before their turns
+pub fn spawn_bots(state: GameServerState, msg: &ClientActorMessage, game: &Game) {
+  if !game.is_current_player_ai() {
+    return;
+  }
+  let msg = ClientActorMessage {
+    content: String::new(),
+    room_id: msg.room_id.clone(),
+    player_id: msg.player_id.clone(),
+  };
+  let mut game = game.clone();
+  actix_web::rt::spawn(async move {
+    move_bot(state, &msg, &mut game).await;
+  });
+}
 
-    let piece_positions_to_jump_to_finish: Vec<usize> = positions
-      .clone()
-      .into_iter()
-      .filter(|position| game.can_jump_to_finish(*position, throw_sum))
-      .collect();
+// plays the rest of the current player's turn with the bot move algorithm, returns the game after it
+pub async fn play_turn(state: GameServerState, msg: &ClientActorMessage, mut game: Game) -> Game {
+  if game.round_phase == RoundPhase::Rolling {
+    throw_dice_bot_messages(state.clone(), msg, &mut game).await;
+    // skip bot's move
+    if game.rules.is_forfeit(&game.dice_throws) {
+      return skip_player(state.clone(), msg, &mut game).await;
+    }
+  }
 
-    if !piece_positions_to_jump_to_finish.is_empty() {
-      let move_result = game.execute_move(piece_positions_to_jump_to_finish[0], throw_sum, false);
-      game = update_game_bot(state.clone(), msg, &mut game, move_result.clone()).await;
-      if let MoveResult::Winner(_) = move_result.clone() {
-        return;
+  // the turn is over once play_round hands it to the next player
+  while game.round_phase == RoundPhase::Moving {
+    let bot_move = match next_bot_move(&mut game) {
+      Some(bot_move) => bot_move,
+      None => break,
+    };
+    match play_round(&mut game, bot_move) {
+      MoveResult::Error(err) => {
+        println!("move_bot - MoveResult::Error: {}", err);
+        break;
+      }
+      move_result => {
+        log_event(&state.db, &msg.room_id, GameEvent::Moved(bot_move)).await;
+        game = send_game_update_message(state.clone(), msg, &game).await;
+        if let MoveResult::Winner(_) = move_result {
+          return game;
+        }
       }
-      continue;
     }
+  }
 
-    // -----[ 2. jump to home ]-----
+  // -----[ 9. no valid moves available, skip turn ]-----
+  if game.round_phase == RoundPhase::Moving {
+    game = skip_player(state.clone(), msg, &mut game).await;
+  }
+  game
+}
 
-    let piece_positions_to_jump_home: Vec<usize> = positions
-      .clone()
-      .into_iter()
-      .filter(|position| game.can_jump_to_home(*position, throw_sum))
-      .collect();
+// picks the move for the first pending die that can be played, None once there is nothing left to play
+fn next_bot_move(game: &mut Game) -> Option<Move> {
+  next_available_positions(game)?;
+  game
+    .pending_moves
+    .iter()
+    .find_map(|dice_value| choose_move(game, *dice_value))
+}
 
-    if !piece_positions_to_jump_home.is_empty() {
-      let move_result = game.execute_move(piece_positions_to_jump_home[0], throw_sum, false);
-      game = update_game_bot(state.clone(), msg, &mut game, move_result.clone()).await;
-      // shouldn't be necessary - player/bot should never become a winner in this branch
-      if let MoveResult::Winner(_) = move_result.clone() {
-        return;
-      }
-      continue;
-    }
+// steps 1-8 of the bot move algorithm
+fn choose_move(game: &Game, dice_value: usize) -> Option<Move> {
+  let moves = game.legal_moves(dice_value);
+  let first = |condition: &dyn Fn(&Move) -> bool| moves.iter().copied().find(|m| condition(m));
+  let last = |condition: &dyn Fn(&Move) -> bool| moves.iter().copied().rev().find(|m| condition(m));
+  let player = game.get_current_player();
 
+  // -----[ 1. jump to finish ]-----
+  first(&|m| matches!(m, Move::Finish { from_home: false, .. }))
+    // -----[ 2. jump to home ]-----
+    .or_else(|| first(&|m| matches!(m, Move::EnterHome { .. })))
     // -----[ 3. add new piece if only 1 is in main field ]-----
-
-    if player.pawns_at_start + player.pawns_at_finish >= 3 && game.can_promote_piece(throw_sum) {
-      let move_result = game.promote_piece(throw_sum);
-      game = update_game_bot(state.clone(), msg, &mut game, move_result).await;
-      continue;
-    }
-
+    .or_else(|| {
+      match player.pawns_at_start + player.pawns_at_finish + 1 >= game.rules.pieces_count {
+        true => first(&|m| matches!(m, Move::Enter { .. })),
+        false => None,
+      }
+    })
     // -----[ 4. remove enemy's piece ]-----
-
-    let piece_positions_to_remove_enemy: Vec<usize> = positions
-      .clone()
-      .into_iter()
-      .filter(|position| game.will_remove_enemy(*position, throw_sum))
-      .collect();
-
-    if !piece_positions_to_remove_enemy.is_empty() {
-      let move_result = game.execute_move(piece_positions_to_remove_enemy[0], throw_sum, false);
-      game = update_game_bot(state.clone(), msg, &mut game, move_result.clone()).await;
-      continue;
-    }
-
+    .or_else(|| first(&|m| matches!(m, Move::Advance { capture: true, .. })))
     // -----[ 5. add new piece to game ]-----
-
-    if game.can_promote_piece(throw_sum) {
-      let move_result = game.promote_piece(throw_sum);
-      game = update_game_bot(state.clone(), msg, &mut game, move_result.clone()).await;
-      continue;
-    }
-
+    .or_else(|| first(&|m| matches!(m, Move::Enter { .. })))
     // -----[ 6. move any piece (on main field) ]-----
-
-    let piece_positions_to_move: Vec<usize> = positions
-      .into_iter()
-      .filter(|position| game.can_jump(*position, throw_sum))
-      .collect();
-
-    if !piece_positions_to_move.is_empty() {
-      let move_result =
-        game.execute_move(*piece_positions_to_move.last().unwrap(), throw_sum, false);
-      game = update_game_bot(state.clone(), msg, &mut game, move_result.clone()).await;
-
-      // shouldn't be necessary, since we can only move in board
-      if let MoveResult::Winner(_) = move_result.clone() {
-        return;
-      }
-      continue;
-    }
-
-    // ----------[ handles jumping from home ]----------
-
-    let piece_positions_in_home: Vec<usize> =
-      game.get_players_pieces_positions_in_home(player.color);
-
-    let piece_positions_in_home_to_jump = piece_positions_in_home
-      .clone()
-      .into_iter()
-      .filter(|position| game.can_jump_from_home(*position, throw_sum))
-      .collect::<Vec<usize>>();
-
+    .or_else(|| last(&|m| matches!(m, Move::Advance { .. })))
     // -----[ 7. jump from home to finish ]-----
-
-    let piece_positions_in_home_to_jump_to_finish = piece_positions_in_home_to_jump
-      .clone()
-      .into_iter()
-      .filter(|position| game.can_jump_from_home_to_finish(*position, throw_sum))
-      .collect::<Vec<usize>>();
-
-    if !piece_positions_in_home_to_jump_to_finish.is_empty() {
-      let move_result = game.execute_move(
-        *piece_positions_in_home_to_jump_to_finish.last().unwrap(),
-        throw_sum,
-        true,
-      );
-      game = update_game_bot(state.clone(), msg, &mut game, move_result.clone()).await;
-      if let MoveResult::Winner(_) = move_result.clone() {
-        return;
-      }
-      continue;
-    }
-
+    .or_else(|| last(&|m| matches!(m, Move::Finish { from_home: true, .. })))
     // -----[ 8. move piece forward in home ]-----
-
-    if !piece_positions_in_home_to_jump.is_empty() {
-      let move_result = game.execute_move(
-        *piece_positions_in_home_to_jump.last().unwrap(),
-        throw_sum,
-        true,
-      );
-      game = update_game_bot(state.clone(), msg, &mut game, move_result.clone()).await;
-
-      // shouldn't be necessary, since we can only move forward in home
-      if let MoveResult::Winner(_) = move_result.clone() {
-        return;
-      }
-      continue;
-    }
-
-    // -----[ 9. no valid moves available, skip turn ]-----
-    game = skip_player(state.clone(), msg, &mut game).await;
-  }
+    .or_else(|| last(&|m| matches!(m, Move::AdvanceInHome { .. })))
 }
 
-// updates game based on move_result (set winner / change current player and empty dice_throws)
-pub async fn update_game_bot(
+// inform player about value on dice after each roll
+// we don't need to keep updating game, since we perform rolling and move in the function / 'time frame'
+pub async fn throw_dice_bot_messages(
   state: GameServerState,
   msg: &ClientActorMessage,
   game: &mut Game,
-  move_result: MoveResult,
-) -> Game {
-  move_result_update_game(game, move_result);
-  send_game_update_message(state.clone(), msg, game).await
-}
-
-// inform player about value on dice after each roll
-// we don't need to keep updating game, since we perform rolling and move in the function / 'time frame'
-pub async fn throw_dice_bot_messages(state: GameServerState, msg: &ClientActorMessage) -> usize {
-  let mut throw_sum: usize = 0;
-
-  let roll = get_dice_value();
-  // can_roll_again is irrelevant
-  send_roll_message(state.clone(), msg, roll, false).await;
-  throw_sum += roll;
-
-  if roll < 6 {
-    return throw_sum;
-  }
-
-  let roll = get_dice_value();
-  send_roll_message(state.clone(), msg, roll, false).await;
-  throw_sum += roll;
-
-  if roll < 6 {
-    return throw_sum;
+) {
+  loop {
+    let roll = state.dice.roll(game);
+    game.add_roll(roll);
+    log_event(&state.db, &msg.room_id, GameEvent::Rolled(roll)).await;
+    // can_roll_again is irrelevant
+    send_roll_message(state.clone(), msg, game, roll, false).await;
+
+    if !game.rules.can_roll_again(&game.dice_throws) {
+      return;
+    }
   }
-
-  let roll = get_dice_value();
-  send_roll_message(state.clone(), msg, roll, false).await;
-  throw_sum += roll;
-
-  throw_sum
 }
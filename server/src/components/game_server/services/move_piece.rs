@@ -1,81 +1,116 @@
-use crate::components::game_server::services::move_bot::move_bot;
-use crate::utils::enums::RoundPhase;
+use crate::components::game_server::services::move_bot::spawn_bots;
+use crate::components::game_server::services::utils::{
+  log_human_move, send_available_positions, send_game_update,
+};
 use crate::{
-  components::{
-    game::database,
-    game_server::{
-      actor::GameServerState,
-      utils::{send_message, send_message_to_room},
-    },
-  },
-  models::{actor_messages::ClientActorMessage, position::Position},
+  components::game_server::{actor::GameServerState, utils::send_message},
+  models::actor_messages::ClientActorMessage,
+};
+use ludo_core::{
+  models::{messages::ServerMessage, position::Position},
   utils::{
-    enums::{MoveResult, MoveType, ServerMessage},
-    game::play_round,
+    enums::{MoveResult, MoveType, RoundPhase},
+    player::{find_move, play_round},
   },
 };
 
-pub async fn move_piece(state: GameServerState, msg: ClientActorMessage, position: Position) {
-  let db_game = database::find_game(&state.db, &msg.room_id).await;
+pub async fn move_piece(
+  state: GameServerState,
+  msg: ClientActorMessage,
+  position: Position,
+  dice_value: Option<usize>,
+) {
+  let db_game = state.db.find_game(&msg.room_id).await;
   let mut game = match db_game {
     Ok(Some(game)) => game,
     _ => {
-      let message =
-        serde_json::to_string(&ServerMessage::Error("Cannot find game".into())).unwrap();
-      send_message(message.as_str(), state.sessions, &msg.player_id);
+      send_message(
+        &state,
+        &msg.room_id,
+        &msg.player_id,
+        &ServerMessage::Error("Cannot find game".into()),
+      );
       return;
     }
   };
   if game.round_phase != RoundPhase::Moving {
-    let message = serde_json::to_string(&ServerMessage::Error(
-      "Moving a piece is not allowed now".into(),
-    ))
-    .unwrap();
-    send_message(message.as_str(), state.sessions, &msg.player_id);
+    send_message(
+      &state,
+      &msg.room_id,
+      &msg.player_id,
+      &ServerMessage::Error("Moving a piece is not allowed now".into()),
+    );
     return;
   }
   let current_player_id = game.get_current_player_id(); //TODO probably shouldn't unwrap
   if current_player_id != msg.player_id {
-    let message =
-      serde_json::to_string(&ServerMessage::Error("It is not your turn".into())).unwrap();
-    send_message(message.as_str(), state.sessions, &msg.player_id);
+    send_message(
+      &state,
+      &msg.room_id,
+      &msg.player_id,
+      &ServerMessage::Error("It is not your turn".into()),
+    );
     return;
   };
-  let result = play_round(&mut game, MoveType::Move(position)).await;
+  if dice_value.is_some_and(|value| !game.pending_moves.contains(&value)) {
+    send_message(
+      &state,
+      &msg.room_id,
+      &msg.player_id,
+      &ServerMessage::Error("None of the dice has this value".into()),
+    );
+    return;
+  }
+  let player_move = match find_move(&game, MoveType::Move(position), dice_value) {
+    Some(player_move) => player_move,
+    None => {
+      send_message(
+        &state,
+        &msg.room_id,
+        &msg.player_id,
+        &ServerMessage::Error("Error executing move".into()),
+      );
+      return;
+    }
+  };
+  let before = game.clone();
+  let result = play_round(&mut game, player_move);
+  if !matches!(result, MoveResult::Error(_)) {
+    log_human_move(&state, &msg, before, player_move).await;
+  }
   match result {
     MoveResult::Success(_) => {
-      let mut game_state = database::update_game_state(&state.db, &msg.room_id, &game)
+      let game_state = state
+        .db
+        .update_game_state(&msg.room_id, &game)
         .await
         .unwrap();
-      let update_message =
-        serde_json::to_string(&ServerMessage::GameUpdate(game_state.clone())).unwrap();
-      send_message_to_room(
-        update_message.as_str(),
-        state.sessions.clone(),
-        state.rooms.clone(),
-        &msg.room_id,
-      );
+      send_game_update(state.clone(), &msg.room_id, &game_state);
+
+      // the player keeps moving while there are dice left to play
+      if game_state.round_phase == RoundPhase::Moving {
+        send_available_positions(state.clone(), &msg.room_id, &game_state);
+      }
 
       // handle if next player is a bot
-      move_bot(state.clone(), &msg, &mut game_state).await;
+      spawn_bots(state.clone(), &msg, &game_state);
     }
     MoveResult::Winner(color) => {
       game.finish_game(color);
-      let game_state = database::update_game_state(&state.db, &msg.room_id, &game)
+      let game_state = state
+        .db
+        .update_game_state(&msg.room_id, &game)
         .await
         .unwrap();
-      let update_message = serde_json::to_string(&ServerMessage::GameUpdate(game_state)).unwrap();
-      send_message_to_room(
-        update_message.as_str(),
-        state.sessions.clone(),
-        state.rooms.clone(),
-        &msg.room_id,
-      );
+      send_game_update(state.clone(), &msg.room_id, &game_state);
     }
     _ => {
-      let message =
-        serde_json::to_string(&ServerMessage::Error("Error executing move".into())).unwrap();
-      send_message(message.as_str(), state.sessions, &msg.player_id);
+      send_message(
+        &state,
+        &msg.room_id,
+        &msg.player_id,
+        &ServerMessage::Error("Error executing move".into()),
+      );
     }
   }
 }
@@ -1,21 +1,22 @@
 use super::super::actor::GameServerState;
-use super::move_bot::move_bot;
-use crate::components::game_server::services::utils::{send_roll_message, skip_player};
+use super::move_bot::spawn_bots;
+use crate::components::game_server::services::utils::{
+  send_available_positions, send_roll_message, skip_player,
+};
 use crate::{
-  components::{game::database, game_server::utils::send_message},
+  components::{game::database::log_event, game_server::utils::send_message},
   models::actor_messages::ClientActorMessage,
-  utils::{
-    dice::get_dice_value,
-    enums::{RoundPhase, ServerMessage},
-    player::get_available_positions,
-  },
+};
+use ludo_core::{
+  models::{game_event::GameEvent, messages::ServerMessage},
+  utils::{enums::RoundPhase, player::next_available_positions},
 };
 
 // DEPRECATED
 // ----------
 // pub async fn roll_dice(state: GameServerState, msg: ClientActorMessage) {
 //   let roll = get_dice_value();
-//   let db_game = database::find_game(&state.db, &msg.room_id).await;
+//   let db_game = state.db.find_game(&msg.room_id).await;
 //   let game = match db_game {
 //     Ok(Some(game)) => game,
 //     _ => {
@@ -45,7 +46,7 @@ use crate::{
 //     return;
 //   };
 
-//   let res = database::add_dice_roll(&state.db, &msg.room_id, roll).await;
+//   let res = state.db.add_dice_roll(&msg.room_id, roll).await;
 
 //   if res.is_err() {
 //     let message =
@@ -71,7 +72,7 @@ use crate::{
 //   {
 //     game.update_current_player();
 //     game.dice_throws.clear();
-//     let mut game_state = database::update_game_state(&state.db, &msg.room_id, &game)
+//     let mut game_state = state.db.update_game_state(&msg.room_id, &game)
 //       .await
 //       .unwrap(); //TODO handle errors
 
@@ -102,7 +103,7 @@ use crate::{
 //     ))
 //     .unwrap(); //TODO handle no available moves
 //     game.round_phase = RoundPhase::Moving;
-//     let _ = database::update_game_state(&state.db, &msg.room_id, &game).await;
+//     let _ = state.db.update_game_state(&msg.room_id, &game).await;
 //     send_message_to_room(
 //       roll_results_message.as_str(),
 //       state.sessions.clone(),
@@ -113,99 +114,93 @@ use crate::{
 // }
 
 pub async fn roll_dice(state: GameServerState, msg: ClientActorMessage) {
-  let roll = get_dice_value();
-  let db_game = database::find_game(&state.db, &msg.room_id).await;
-  let game = match db_game {
+  let db_game = state.db.find_game(&msg.room_id).await;
+  let mut game = match db_game {
     Ok(Some(game)) => game,
     _ => {
-      let message =
-        serde_json::to_string(&ServerMessage::Error("Cannot find game".into())).unwrap();
-      send_message(message.as_str(), state.sessions, &msg.player_id);
+      send_message(
+        &state,
+        &msg.room_id,
+        &msg.player_id,
+        &ServerMessage::Error("Cannot find game".into()),
+      );
       return;
     }
   };
   if game.round_phase != RoundPhase::Rolling {
-    let message =
-      serde_json::to_string(&ServerMessage::Error("Rolling is not allowed now".into())).unwrap();
-    send_message(message.as_str(), state.sessions, &msg.player_id);
+    send_message(
+      &state,
+      &msg.room_id,
+      &msg.player_id,
+      &ServerMessage::Error("Rolling is not allowed now".into()),
+    );
     return;
   }
   let current_player_id = game.get_current_player_id();
   if current_player_id != msg.player_id {
-    let message =
-      serde_json::to_string(&ServerMessage::Error("It is not your turn".into())).unwrap();
-    send_message(message.as_str(), state.sessions, &msg.player_id);
+    send_message(
+      &state,
+      &msg.room_id,
+      &msg.player_id,
+      &ServerMessage::Error("It is not your turn".into()),
+    );
     return;
   };
 
-  let res = database::add_dice_roll(&state.db, &msg.room_id, roll).await;
+  let roll = state.dice.roll(&mut game);
+  let res = state.db.add_dice_roll(&msg.room_id, roll).await;
 
   if res.is_err() {
-    let message =
-      serde_json::to_string(&ServerMessage::Error("Error while rolling dice".into())).unwrap();
-    send_message(message.as_str(), state.sessions, &msg.player_id);
+    send_message(
+      &state,
+      &msg.room_id,
+      &msg.player_id,
+      &ServerMessage::Error("Error while rolling dice".into()),
+    );
     return;
   };
   let mut game = res.unwrap();
-  let can_roll_again = roll == 6 && game.dice_throws.len() < 3;
-  send_roll_message(state.clone(), &msg, roll, can_roll_again).await;
+  log_event(&state.db, &msg.room_id, GameEvent::Rolled(roll)).await;
+  let can_roll_again = game.rules.can_roll_again(&game.dice_throws);
+  send_roll_message(state.clone(), &msg, &game, roll, can_roll_again).await;
 
   // just informed players about roll
   if can_roll_again {
     return;
   }
 
-  let rolls_sum: usize = game.dice_throws.clone().iter().sum();
-  if rolls_sum == 18 {
+  if game.rules.is_forfeit(&game.dice_throws) {
     let _ = skip_player(state.clone(), &msg, &mut game).await;
-    move_bot(state.clone(), &msg, &mut game).await;
+    spawn_bots(state.clone(), &msg, &game);
     return;
   }
+  game.pending_moves = game.rules.pending_moves(&game.dice_throws);
 
   // shouldn't be necessary, since there should be no available positions anyway,
   //   but is faster since it doesn't need to compute the available positions
-  // can be OR'd with 'rolls_sum == 18' condition
-  if rolls_sum < 6
-    && game.get_current_player().pawns_at_start + game.get_current_player().pawns_at_finish == 4
+  // can be OR'd with the forfeit condition
+  if !game
+    .pending_moves
+    .iter()
+    .any(|dice_value| game.rules.can_enter(*dice_value))
+    && game.get_current_player().pawns_at_start + game.get_current_player().pawns_at_finish
+      == game.rules.pieces_count
   {
     let _ = skip_player(state.clone(), &msg, &mut game).await;
-    move_bot(state.clone(), &msg, &mut game).await;
+    spawn_bots(state.clone(), &msg, &game);
     return;
   }
 
-  let available_positions = get_available_positions(&game, rolls_sum);
-  if no_available_positions(&available_positions) {
+  // the player is offered the moves for every die, the turn goes on until all of them are played or forfeited
+  if next_available_positions(&mut game).is_none() {
     let _ = skip_player(state.clone(), &msg, &mut game).await;
-    move_bot(state.clone(), &msg, &mut game).await;
+    spawn_bots(state.clone(), &msg, &game);
     // else branch can be removed with early return, but maybe less readable ?
   } else {
     // send available positions to player (he should choose one of the positions / promote) and update round_phase
-    let roll_results_message = serde_json::to_string(&ServerMessage::AvailablePositions(
-      available_positions.0,
-      available_positions.1,
-      available_positions.2,
-    ))
-    .unwrap();
-
     game.round_phase = RoundPhase::Moving;
-    let _ = database::update_game_state(&state.db, &msg.room_id, &game).await;
+    let _ = state.db.update_game_state(&msg.room_id, &game).await;
 
-    // TODO: should send only to one player, or?
-    send_message(
-      roll_results_message.as_str(),
-      state.sessions.clone(),
-      &msg.player_id,
-    );
-    // send_message_to_room(
-    //   roll_results_message.as_str(),
-    //   state.sessions.clone(),
-    //   state.rooms.clone(),
-    //   &msg.room_id,
-    // );
+    send_available_positions(state.clone(), &msg.room_id, &game);
   }
 }
-
-fn no_available_positions(available_positions: &(Vec<usize>, Vec<usize>, bool)) -> bool {
-  let (positions_in_fields, positions_in_home, can_promote) = available_positions;
-  positions_in_fields.is_empty() && positions_in_home.is_empty() && !(*can_promote)
-}
@@ -0,0 +1,64 @@
+use crate::components::game::database::log_event;
+use crate::components::game_server::actor::GameServerState;
+use crate::components::game_server::services::move_bot::spawn_bots;
+use crate::components::game_server::services::utils::send_game_update;
+use crate::components::game_server::utils::send_message_to_room;
+use crate::models::{actor_messages::ClientActorMessage, room_lock::lock_room};
+use ludo_core::models::{color::Color, game::Game, game_event::GameEvent, messages::ServerMessage};
+
+// the player didn't come back in time, a bot plays for them from now on
+pub async fn hand_seat_to_bot(state: GameServerState, room_id: String, player_id: String) {
+  let guard = lock_room(&state.room_locks, &room_id).await;
+  let mut game = match state.db.find_game(&room_id).await {
+    Ok(Some(game)) => game,
+    _ => return,
+  };
+  if !game.started || game.winner.is_some() {
+    return;
+  }
+  let color = match game.get_player_by_id(&player_id) {
+    Some(player) if !player.is_bot => player.color,
+    _ => return,
+  };
+
+  game.get_player_mut(color).set_away(true);
+  let game = match state.db.update_game_state(&room_id, &game).await {
+    Ok(game) => game,
+    Err(_) => return,
+  };
+  log_event(&state.db, &room_id, GameEvent::Left(color)).await;
+  drop(guard);
+  println!("a bot took over the seat of {} in room {}", color, room_id);
+
+  send_message_to_room(&state, &room_id, &ServerMessage::PlayerLeft(color));
+  send_game_update(state.clone(), &room_id, &game);
+
+  // the turn was waiting for the player
+  if game.current_player == color {
+    let msg = ClientActorMessage {
+      content: String::new(),
+      room_id,
+      player_id,
+    };
+    spawn_bots(state, &msg, &game);
+  }
+}
+
+// the player reconnected while a bot played for them, returns the game with the seat given back
+pub async fn return_seat(
+  state: &GameServerState,
+  room_id: &str,
+  mut game: Game,
+  color: Color,
+) -> Game {
+  game.get_player_mut(color).set_away(false);
+  let game = match state.db.update_game_state(room_id, &game).await {
+    Ok(game) => game,
+    Err(_) => return game,
+  };
+  log_event(&state.db, room_id, GameEvent::Returned(color)).await;
+
+  send_message_to_room(state, room_id, &ServerMessage::PlayerReturned(color));
+  send_game_update(state.clone(), room_id, &game);
+  game
+}
@@ -0,0 +1,17 @@
+use crate::components::game_server::{
+  actor::GameServerState, services::utils::sync_turn_timer, utils::send_message,
+};
+use crate::models::actor_messages::ClientActorMessage;
+use ludo_core::models::messages::ServerMessage;
+
+// sends the whole game to a client that missed a message of the room
+pub async fn send_snapshot(state: GameServerState, msg: ClientActorMessage) {
+  let message = match state.db.find_game(&msg.room_id).await {
+    Ok(Some(game)) => {
+      let time_left = sync_turn_timer(&state, &msg.room_id, &game);
+      ServerMessage::GameUpdate(game, time_left)
+    }
+    _ => ServerMessage::Error("Cannot find game".into()),
+  };
+  send_message(&state, &msg.room_id, &msg.player_id, &message);
+}
@@ -1,40 +1,62 @@
 use super::super::actor::GameServerState;
+use super::utils::sync_turn_timer;
 use crate::{
   components::{
-    game::database,
+    game::database::log_event,
     game_server::utils::{send_message, send_message_to_room},
   },
   models::actor_messages::ClientActorMessage,
-  utils::{enums::ServerMessage, game::fill_with_bots},
+  utils::game::fill_with_bots,
 };
+use ludo_core::models::{game_event::GameEvent, messages::ServerMessage};
 
+fn send_error(state: &GameServerState, msg: &ClientActorMessage, error: &str) {
+  send_message(
+    state,
+    &msg.room_id,
+    &msg.player_id,
+    &ServerMessage::Error(error.into()),
+  );
+}
+
+// only the host starts the game, once every human in the lobby is ready
 pub async fn start_game(state: GameServerState, msg: ClientActorMessage) {
+  let lobby = match state.db.find_game(&msg.room_id).await {
+    Ok(Some(game)) => game,
+    _ => return send_error(&state, &msg, "Cannot find game"),
+  };
+  if lobby.started {
+    return send_error(&state, &msg, "The game has already started");
+  }
+  if !lobby.is_host(&msg.player_id) {
+    return send_error(&state, &msg, "Only the host can start the game");
+  }
+  if !lobby.everyone_ready() {
+    return send_error(&state, &msg, "Not every player is ready");
+  }
+
   // TODO: optimize
-  let start_res = database::start_game(&state.db, &msg.room_id).await;
+  let start_res = state.db.start_game(&msg.room_id).await;
   let mut game = match start_res {
     Ok(game) => game,
-    Err(_) => {
-      let message =
-        serde_json::to_string(&ServerMessage::Error("Cannot start the game".into())).unwrap();
-      send_message(message.as_str(), state.sessions, &msg.player_id);
-      return;
-    }
+    Err(_) => return send_error(&state, &msg, "Cannot start the game"),
   };
 
-  game.players = fill_with_bots(game.players);
-  let update_res = database::update_game_state(&state.db, &msg.room_id, &game).await;
+  game.players = fill_with_bots(game.players, &game.rules);
+  let update_res = state.db.update_game_state(&msg.room_id, &game).await;
 
   let game = match update_res {
     Ok(game) => game,
-    Err(_) => {
-      let message =
-        serde_json::to_string(&ServerMessage::Error("Cannot update the game".into())).unwrap();
-      send_message(message.as_str(), state.sessions, &msg.player_id);
-      return;
-    }
+    Err(_) => return send_error(&state, &msg, "Cannot update the game"),
   };
 
-  let message = serde_json::to_string(&ServerMessage::GameStarted(game)).unwrap();
-
-  send_message_to_room(message.as_str(), state.sessions, state.rooms, &msg.room_id);
+  log_event(
+    &state.db,
+    &msg.room_id,
+    GameEvent::Started(game.players.clone()),
+  )
+  .await;
+  // the client starts the countdown of the first turn from the rules
+  sync_turn_timer(&state, &msg.room_id, &game);
+  send_message_to_room(&state, &msg.room_id, &ServerMessage::GameStarted(game));
 }
@@ -0,0 +1,35 @@
+use crate::components::game_server::actor::GameServerState;
+use crate::components::game_server::services::move_bot::{play_turn, spawn_bots};
+use crate::models::room_lock::lock_room;
+use crate::models::{actor_messages::ClientActorMessage, turn_timer::TurnDeadline};
+
+// the player let their turn run out, it is played for them the way a bot would play it
+pub async fn turn_timeout(state: GameServerState, room_id: String, deadline: TurnDeadline) {
+  // the player may still be moving, the deadline is checked once the game can't change anymore
+  let guard = lock_room(&state.room_locks, &room_id).await;
+  if !state.timers.lock().unwrap().is_current(&room_id, &deadline) {
+    return;
+  }
+  let game = match state.db.find_game(&room_id).await {
+    Ok(Some(game)) => game,
+    _ => return,
+  };
+  if game.winner.is_some() || game.current_player != deadline.color {
+    return;
+  }
+
+  println!(
+    "turn of {} in room {} ran out",
+    game.current_player, room_id
+  );
+  let msg = ClientActorMessage {
+    content: String::new(),
+    player_id: game.get_current_player_id(),
+    room_id,
+  };
+  let game = play_turn(state.clone(), &msg, game).await;
+  drop(guard);
+  if game.winner.is_none() {
+    spawn_bots(state, &msg, &game);
+  }
+}
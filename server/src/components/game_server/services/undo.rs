@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use crate::components::game_server::services::utils::{send_available_positions, send_game_update};
+use crate::{
+  components::{
+    game::database::log_event,
+    game_server::{
+      actor::GameServerState,
+      utils::{send_message, send_message_to_room},
+    },
+  },
+  models::{
+    actor_messages::{ClientActorMessage, Role},
+    undo::UndoAnswer,
+  },
+};
+use ludo_core::{
+  models::{game_event::GameEvent, messages::ServerMessage},
+  utils::enums::RoundPhase,
+};
+
+fn send_error(state: GameServerState, msg: &ClientActorMessage, error: &str) {
+  send_message(
+    &state,
+    &msg.room_id,
+    &msg.player_id,
+    &ServerMessage::Error(error.into()),
+  );
+}
+
+// asks the other humans in the room whether the last move of the player can be taken back
+pub async fn request_undo(state: GameServerState, msg: ClientActorMessage) {
+  let game = match state.db.find_game(&msg.room_id).await {
+    Ok(Some(game)) => game,
+    _ => return send_error(state, &msg, "Cannot find game"),
+  };
+  if game.winner.is_some() {
+    return send_error(state, &msg, "The game is over");
+  }
+
+  let player_id = {
+    let undo = state.undo.lock().unwrap();
+    if undo.has_request(&msg.room_id) {
+      return send_error(state.clone(), &msg, "A takeback was already requested");
+    }
+    undo
+      .last_snapshot(&msg.room_id)
+      .map(|snapshot| snapshot.player_id.clone())
+  };
+  match player_id {
+    None => return send_error(state, &msg, "There is no move to take back"),
+    Some(player_id) if player_id != msg.player_id => {
+      return send_error(
+        state,
+        &msg,
+        "Only the player who made the last move can take it back",
+      )
+    }
+    _ => {}
+  }
+
+  // bots always agree, only the connected humans are asked
+  let waiting_for: HashSet<String> = state
+    .rooms
+    .get(&msg.room_id)
+    .map(|sessions| {
+      sessions
+        .iter()
+        .filter(|(id, role)| **role == Role::Player && **id != msg.player_id)
+        .map(|(id, _)| id.clone())
+        .collect()
+    })
+    .unwrap_or_default();
+  if waiting_for.is_empty() {
+    return restore(state, &msg).await;
+  }
+
+  let color = match game.get_player_by_id(&msg.player_id) {
+    Some(player) => player.color,
+    None => return send_error(state, &msg, "You are not playing in this game"),
+  };
+  let message = ServerMessage::UndoRequested(color);
+  for player_id in &waiting_for {
+    send_message(&state, &msg.room_id, player_id, &message);
+  }
+  state
+    .undo
+    .lock()
+    .unwrap()
+    .request(&msg.room_id, waiting_for);
+}
+
+pub async fn answer_undo(state: GameServerState, msg: ClientActorMessage, accept: bool) {
+  let answer = state
+    .undo
+    .lock()
+    .unwrap()
+    .answer(&msg.room_id, &msg.player_id, accept);
+  match answer {
+    UndoAnswer::Accepted => restore(state, &msg).await,
+    UndoAnswer::Rejected => {
+      send_message_to_room(&state, &msg.room_id, &ServerMessage::UndoResult(false));
+    }
+    UndoAnswer::NotAsked => send_error(state, &msg, "There is nothing to answer"),
+    UndoAnswer::Waiting => {}
+  }
+}
+
+// puts the game back to how it was before the last move of a human
+async fn restore(state: GameServerState, msg: &ClientActorMessage) {
+  let snapshot = state.undo.lock().unwrap().pop_snapshot(&msg.room_id);
+  let snapshot = match snapshot {
+    Some(snapshot) => snapshot,
+    None => return send_error(state, msg, "There is no move to take back"),
+  };
+  let game = match state
+    .db
+    .update_game_state(&msg.room_id, &snapshot.game)
+    .await
+  {
+    Ok(game) => game,
+    Err(_) => return send_error(state, msg, "Cannot find game"),
+  };
+  log_event(
+    &state.db,
+    &msg.room_id,
+    GameEvent::Undone {
+      events: snapshot.events,
+    },
+  )
+  .await;
+
+  send_message_to_room(&state, &msg.room_id, &ServerMessage::UndoResult(true));
+  send_game_update(state.clone(), &msg.room_id, &game);
+  if game.round_phase == RoundPhase::Moving {
+    send_available_positions(state, &msg.room_id, &game);
+  }
+}
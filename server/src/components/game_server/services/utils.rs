@@ -1,9 +1,14 @@
-use crate::components::game::database;
+use crate::components::game::database::log_event;
 use crate::components::game_server::actor::GameServerState;
-use crate::components::game_server::utils::send_message_to_room;
+use crate::components::game_server::utils::{
+  send_game_to_room, send_message, send_message_to_room,
+};
 use crate::models::actor_messages::ClientActorMessage;
-use crate::models::game::Game;
-use crate::utils::enums::ServerMessage;
+use crate::models::actor_messages::TurnStarted;
+use crate::models::undo::Snapshot;
+use ludo_core::models::{game::Game, game_event::GameEvent, messages::ServerMessage};
+use ludo_core::utils::{enums::Move, player::get_die_moves};
+use std::time::Instant;
 
 // update game, sends SkipPlayer message and GameUpdate message to room,
 pub async fn skip_player(
@@ -11,16 +16,10 @@ pub async fn skip_player(
   msg: &ClientActorMessage,
   game: &mut Game,
 ) -> Game {
-  game.update_current_player();
-  game.dice_throws.clear();
+  game.skip_player();
+  log_event(&state.db, &msg.room_id, GameEvent::Skipped).await;
 
-  let skip_message = serde_json::to_string(&ServerMessage::SkipPlayer).unwrap();
-  send_message_to_room(
-    skip_message.as_str(),
-    state.sessions.clone(),
-    state.rooms.clone(),
-    &msg.room_id,
-  );
+  send_message_to_room(&state, &msg.room_id, &ServerMessage::SkipPlayer);
 
   send_game_update_message(state.clone(), msg, game).await
 }
@@ -28,16 +27,15 @@ pub async fn skip_player(
 pub async fn send_roll_message(
   state: GameServerState,
   msg: &ClientActorMessage,
+  game: &Game,
   roll: usize,
   can_roll_again: bool,
 ) {
-  let roll_message =
-    serde_json::to_string(&ServerMessage::DiceValue(roll, can_roll_again)).unwrap();
+  let time_left = sync_turn_timer(&state, &msg.room_id, game);
   send_message_to_room(
-    roll_message.as_str(),
-    state.sessions.clone(),
-    state.rooms,
+    &state,
     &msg.room_id,
+    &ServerMessage::DiceValue(roll, can_roll_again, time_left),
   );
 }
 
@@ -46,16 +44,67 @@ pub async fn send_game_update_message(
   msg: &ClientActorMessage,
   game: &Game,
 ) -> Game {
-  let game = database::update_game_state(&state.db, &msg.room_id, game)
+  let game = state
+    .db
+    .update_game_state(&msg.room_id, game)
     .await
     .unwrap(); //TODO handle errors
-  let update_message = serde_json::to_string(&ServerMessage::GameUpdate(game.clone())).unwrap();
+  send_game_update(state, &msg.room_id, &game);
+  game
+}
 
-  send_message_to_room(
-    update_message.as_str(),
-    state.sessions.clone(),
-    state.rooms.clone(),
-    &msg.room_id,
+// sends what changed in the game to the room together with the time the current player has left
+pub fn send_game_update(state: GameServerState, room_id: &str, game: &Game) {
+  let time_left = sync_turn_timer(&state, room_id, game);
+  send_game_to_room(&state, room_id, game, time_left);
+}
+
+// sends what the current player can do with each of the pending dice, only to that player
+pub fn send_available_positions(state: GameServerState, room_id: &str, game: &Game) {
+  send_message(
+    &state,
+    room_id,
+    &game.get_current_player_id(),
+    &ServerMessage::AvailablePositions(get_die_moves(game)),
   );
-  game
+}
+
+// logs the move of a human and keeps the game from before it, so the move can be taken back
+pub async fn log_human_move(
+  state: &GameServerState,
+  msg: &ClientActorMessage,
+  before: Game,
+  player_move: Move,
+) {
+  let logged = log_event(&state.db, &msg.room_id, GameEvent::Moved(player_move)).await;
+  // without the event the log couldn't follow the takeback
+  if let Some(logged) = logged {
+    let snapshot = Snapshot {
+      game: before,
+      player_id: msg.player_id.clone(),
+      events: logged.sequence,
+    };
+    state
+      .undo
+      .lock()
+      .unwrap()
+      .push_snapshot(&msg.room_id, snapshot);
+  }
+}
+
+// starts the timer when the turn went to a human, returns the milliseconds the current player has left
+pub fn sync_turn_timer(state: &GameServerState, room_id: &str, game: &Game) -> Option<u64> {
+  let now = Instant::now();
+  let mut timers = state.timers.lock().unwrap();
+  if let (Some(deadline), Some(turn_started)) =
+    (timers.update(room_id, game, now), &state.turn_started)
+  {
+    turn_started.do_send(TurnStarted {
+      room_id: room_id.to_string(),
+      deadline,
+    });
+  }
+  timers
+    .time_left(room_id, now)
+    .map(|time_left| time_left.as_millis() as u64)
 }
@@ -1,25 +1,64 @@
 use actix::Recipient;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
+use ludo_core::models::{
+  game::Game,
+  messages::{SequencedMessage, ServerMessage},
+};
+
+use super::actor::GameServerState;
 use crate::models::actor_messages::WsMessage;
 
-pub fn send_message(message: &str, sessions: HashMap<String, Recipient<WsMessage>>, id_to: &str) {
+fn send_text(message: &str, sessions: &HashMap<String, Recipient<WsMessage>>, id_to: &str) {
   if let Some(session) = sessions.get(id_to) {
-    session.do_send(WsMessage(message.to_owned())).ok();
+    session.do_send(WsMessage::Text(message.to_owned()));
   } else {
     println!("attempting to send message but couldn't find session with given id.");
   }
 }
 
-pub fn send_message_to_room(
-  message: &str,
-  sessions: HashMap<String, Recipient<WsMessage>>,
-  rooms: HashMap<String, HashSet<String>>,
-  room_id: &str,
-) {
-  if let Some(sessions_ids) = rooms.get(room_id) {
-    for session_id in sessions_ids {
-      send_message(message, sessions.clone(), session_id);
+fn to_json(sequence: u64, message: &ServerMessage) -> String {
+  serde_json::to_string(&SequencedMessage {
+    sequence,
+    message: message.clone(),
+  })
+  .unwrap()
+}
+
+// sends the message to a single player, with the number of the last message of the room
+pub fn send_message(state: &GameServerState, room_id: &str, id_to: &str, message: &ServerMessage) {
+  let sequence = state.sequences.lock().unwrap().current(room_id);
+  send_text(to_json(sequence, message).as_str(), &state.sessions, id_to);
+}
+
+fn send_numbered(state: &GameServerState, room_id: &str, sequence: u64, message: &ServerMessage) {
+  let json = to_json(sequence, message);
+  if let Some(sessions_ids) = state.rooms.get(room_id) {
+    for session_id in sessions_ids.keys() {
+      send_text(json.as_str(), &state.sessions, session_id);
     }
   }
 }
+
+// sends the message to everyone in the room with the next number of the room
+pub fn send_message_to_room(state: &GameServerState, room_id: &str, message: &ServerMessage) {
+  // the lock is held until the message is sent, so the room gets the messages in the order of their numbers
+  let mut sequences = state.sequences.lock().unwrap();
+  // the room knows the whole game now, the next update is a diff against it
+  if let ServerMessage::GameStarted(game) | ServerMessage::GameUpdate(game, _) = message {
+    sequences.set_game(room_id, game);
+  }
+  send_numbered(state, room_id, sequences.next(room_id), message);
+}
+
+// sends the room what changed in the game since the last update it got
+pub fn send_game_to_room(
+  state: &GameServerState,
+  room_id: &str,
+  game: &Game,
+  time_left: Option<u64>,
+) {
+  let mut sequences = state.sequences.lock().unwrap();
+  let message = sequences.update(room_id, game, time_left);
+  send_numbered(state, room_id, sequences.next(room_id), &message);
+}
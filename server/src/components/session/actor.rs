@@ -3,69 +3,102 @@ use actix::{
   Running, StreamHandler, WrapFuture,
 };
 use actix_web_actors::ws;
-use std::time::Instant;
+use log::debug;
+use ludo_core::models::messages::ClientMessage;
+use std::time::{Duration, Instant};
 
-use crate::components::game_server::actor::GameServer;
-use crate::models::actor_messages::{ClientActorMessage, Connect, Disconnect, WsMessage};
+use crate::components::{game::database::Database, game_server::actor::GameServer};
+use crate::models::actor_messages::{ClientActorMessage, Connect, Disconnect, Role, WsMessage};
+use crate::utils::access::{authorize, TokenSigner};
 
-// const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
-// const TIMEOUT: Duration = Duration::from_secs(15);
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+// how often the client is pinged and how long it may stay silent before the session is dropped
+#[derive(Clone, Copy, Debug)]
+pub struct Heartbeat {
+  pub interval: Duration,
+  pub timeout: Duration,
+}
+
+impl Default for Heartbeat {
+  fn default() -> Self {
+    Heartbeat {
+      interval: HEARTBEAT_INTERVAL,
+      timeout: TIMEOUT,
+    }
+  }
+}
 
 // Game session actor (for each connected client)
 // Sends messages to the GameServer actor who coordinates all connected sessions
 pub struct GameSession {
-  id: String,
+  // the player, None until the client sent a valid token
+  id: Option<String>,
+  role: Role,
   room: String,
   game_server: Addr<GameServer>,
   heartbeat: Instant,
+  heartbeat_config: Heartbeat,
+  db: Database,
+  tokens: TokenSigner,
 }
 
 impl GameSession {
-  pub fn new(id: String, room: String, game_server: Addr<GameServer>) -> Self {
-    println!("created game session");
+  pub fn new(
+    room: String,
+    game_server: Addr<GameServer>,
+    heartbeat_config: Heartbeat,
+    db: Database,
+    tokens: TokenSigner,
+  ) -> Self {
+    debug!("created game session in room {}", room);
     GameSession {
-      id,
+      id: None,
+      role: Role::Player,
       room,
       heartbeat: Instant::now(),
+      heartbeat_config,
       game_server,
+      db,
+      tokens,
     }
   }
 
-  // start the heartbeat (ping the client and expect a pong message sent back)
-  // if we get a pong message back, reset the last heartbeat -> can be seen later in this file
-  // if the duration since the last pong message is greater than the timeout, we disconnect the session due to inactivity
-  // fn heartbeat(&self, ctx: &mut ws::WebsocketContext<Self>) {
-  //   ctx.run_interval(HEARTBEAT_INTERVAL, |actor, ctx| {
-  //     if Instant::now().duration_since(actor.heartbeat) > TIMEOUT {
-  //       println!("Disconnecting due to timeout");
-  //       actor.game_server.do_send(Disconnect {
-  //         player_id: actor.id.clone(),
-  //         room_id: actor.room.clone(),
-  //       });
-  //       ctx.stop();
-  //       return;
-  //     };
-  //     ctx.ping(b"PING");
-  //   });
-  // }
-}
-
-// implementing lifecycle methods for a session
-impl Actor for GameSession {
-  type Context = ws::WebsocketContext<Self>;
+  // the token came with the request that opened the websocket and was already checked
+  pub fn with_player(mut self, id: String) -> Self {
+    self.id = Some(id);
+    self
+  }
 
-  fn started(&mut self, ctx: &mut Self::Context) {
-    // self.heartbeat(ctx);
+  // spectators need no token, they can only watch
+  pub fn with_spectator(mut self, id: String) -> Self {
+    self.id = Some(id);
+    self.role = Role::Spectator;
+    self
+  }
 
-    println!("session started");
+  fn close(ctx: &mut ws::WebsocketContext<Self>, reason: &str) {
+    ctx.close(Some(ws::CloseReason {
+      code: ws::CloseCode::Policy,
+      description: Some(reason.into()),
+    }));
+    ctx.stop();
+  }
 
+  fn connect(&self, ctx: &mut ws::WebsocketContext<Self>) {
+    let player_id = match &self.id {
+      Some(id) => id.clone(),
+      None => return,
+    };
     let address = ctx.address();
     self
       .game_server
       .send(Connect {
         address: address.recipient(),
-        player_id: self.id.clone(),
+        player_id,
         room_id: self.room.clone(),
+        role: self.role,
       })
       .into_actor(self)
       .then(|res, _, ctx| {
@@ -77,12 +110,71 @@ impl Actor for GameSession {
       .wait(ctx);
   }
 
-  fn stopping(&mut self, _: &mut Self::Context) -> Running {
-    println!("stoppping");
-    self.game_server.do_send(Disconnect {
-      room_id: self.room.clone(),
-      player_id: self.id.clone(),
+  // the first message of a session without a player has to be Authenticate with their token,
+  // nothing else is read until the token is checked
+  fn authenticate(&self, text: &str, ctx: &mut ws::WebsocketContext<Self>) {
+    let token = match serde_json::from_str::<ClientMessage>(text) {
+      Ok(ClientMessage::Authenticate(token)) => token,
+      _ => return GameSession::close(ctx, "Authenticate first"),
+    };
+    let (db, tokens, room) = (self.db.clone(), self.tokens.clone(), self.room.clone());
+    async move { authorize(&db, &tokens, &room, &token).await }
+      .into_actor(self)
+      .then(|res, actor, ctx| {
+        match res {
+          Ok(id) => {
+            actor.id = Some(id);
+            actor.connect(ctx);
+          }
+          Err(reason) => GameSession::close(ctx, reason),
+        }
+        fut::ready(())
+      })
+      .wait(ctx);
+  }
+
+  // start the heartbeat (ping the client and expect a pong message sent back)
+  // if we get a pong message back, reset the last heartbeat -> can be seen later in this file
+  // if the duration since the last pong message is greater than the timeout, we disconnect the session due to inactivity
+  fn heartbeat(&self, ctx: &mut ws::WebsocketContext<Self>) {
+    let Heartbeat { interval, timeout } = self.heartbeat_config;
+    ctx.run_interval(interval, move |actor, ctx| {
+      if Instant::now().duration_since(actor.heartbeat) > timeout {
+        debug!("disconnecting {:?} due to timeout", actor.id);
+        // stopping sends the Disconnect to the server
+        ctx.stop();
+        return;
+      };
+      ctx.ping(b"PING");
+    });
+  }
+}
+
+// implementing lifecycle methods for a session
+impl Actor for GameSession {
+  type Context = ws::WebsocketContext<Self>;
+
+  fn started(&mut self, ctx: &mut Self::Context) {
+    self.heartbeat(ctx);
+
+    // a client that doesn't authenticate in time is dropped like a silent one
+    ctx.run_later(self.heartbeat_config.timeout, |actor, ctx| {
+      if actor.id.is_none() {
+        GameSession::close(ctx, "Authenticate first");
+      }
     });
+    self.connect(ctx);
+  }
+
+  fn stopping(&mut self, ctx: &mut Self::Context) -> Running {
+    debug!("stopping session of {:?}", self.id);
+    if let Some(id) = &self.id {
+      self.game_server.do_send(Disconnect {
+        address: ctx.address().recipient(),
+        room_id: self.room.clone(),
+        player_id: id.clone(),
+      });
+    }
     Running::Stop
   }
 }
@@ -110,12 +202,16 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for GameSession {
         ctx.stop();
       }
       Ok(ws::Message::Nop) => {}
-      Ok(ws::Message::Text(s)) => self.game_server.do_send(ClientActorMessage {
-        player_id: self.id.clone(),
-        content: s.to_string(),
-        room_id: self.room.clone(),
-      }),
-      Err(e) => panic!("{}", e),
+      Ok(ws::Message::Text(s)) => match &self.id {
+        Some(id) => self.game_server.do_send(ClientActorMessage {
+          player_id: id.clone(),
+          content: s.to_string(),
+          room_id: self.room.clone(),
+        }),
+        None => self.authenticate(&s, ctx),
+      },
+      // a broken frame only ends the session of the client that sent it
+      Err(e) => GameSession::close(ctx, &e.to_string()),
     }
   }
 }
@@ -125,7 +221,10 @@ impl Handler<WsMessage> for GameSession {
   type Result = ();
 
   fn handle(&mut self, msg: WsMessage, ctx: &mut Self::Context) {
-    // sending text to the client
-    ctx.text(msg.0);
+    match msg {
+      // sending text to the client
+      WsMessage::Text(text) => ctx.text(text),
+      WsMessage::Close(reason) => GameSession::close(ctx, &reason),
+    }
   }
 }
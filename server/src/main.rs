@@ -1,40 +1,66 @@
 use actix::Actor;
 use actix_cors::Cors;
 use actix_web::{middleware, web, App, HttpServer};
-use components::game_server::actor::GameServer;
+use components::{game_server::actor::GameServer, session::actor::Heartbeat};
 use dotenv::dotenv;
 use env_logger::Env;
-use mongodb::{options::ClientOptions, Client};
-use std::env;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::{env, sync::Arc, time::Duration};
 
 mod components;
 mod models;
-mod types;
 mod utils;
 
 use models::app_data::AppData;
+use utils::{access::TokenSigner, dice::SeededDice};
+
+fn env_seconds(key: &str) -> Option<u64> {
+  env::var(key).ok().and_then(|seconds| seconds.parse().ok())
+}
 
 #[actix_web::main]
 async fn main() -> anyhow::Result<()> {
   env_logger::init_from_env(Env::default().default_filter_or("info"));
   dotenv().ok();
 
-  let database_url = env::var("DATABASE_URL").expect("DATABASE_URL env variable is not set");
   let port = env::var("PORT").unwrap_or("8080".to_string());
 
-  let mut client_options = ClientOptions::parse(database_url).await?;
-  client_options.app_name = Some("Ludo".to_string());
+  let db = components::game::database::connect(env::var("DATABASE_URL").ok()).await?;
+
+  let mut game_server = GameServer::new(db.clone(), Arc::new(SeededDice));
+  // seconds a disconnected player has to come back before a bot takes over their seat
+  if let Some(seconds) = env_seconds("RECONNECT_GRACE_SECONDS") {
+    game_server = game_server.with_grace_period(Duration::from_secs(seconds));
+  }
+  let room_locks = game_server.room_locks();
+  let game_server_addr = game_server.start();
 
-  let client = Client::with_options(client_options)?;
-  let db = Arc::new(Mutex::new(client.database("main")));
+  // seconds between the pings of the websockets and without an answer before a session is dropped
+  let mut heartbeat = Heartbeat::default();
+  if let Some(seconds) = env_seconds("HEARTBEAT_INTERVAL_SECONDS") {
+    heartbeat.interval = Duration::from_secs(seconds);
+  }
+  if let Some(seconds) = env_seconds("HEARTBEAT_TIMEOUT_SECONDS") {
+    heartbeat.timeout = Duration::from_secs(seconds);
+  }
 
-  let game_server_addr = GameServer::new(db.clone()).start();
+  // the secret the session tokens of the players are signed with
+  let mut tokens = match env::var("SESSION_SECRET") {
+    Ok(secret) if !secret.is_empty() => TokenSigner::new(secret.as_bytes()),
+    _ => {
+      println!("SESSION_SECRET is not set, players have to join again after a restart");
+      TokenSigner::random()
+    }
+  };
+  if let Some(seconds) = env_seconds("SESSION_TOKEN_LIFETIME_SECONDS") {
+    tokens = tokens.with_lifetime(seconds as i64);
+  }
 
   let app_data = web::Data::new(AppData {
     game_server_addr,
     db: db.clone(),
+    heartbeat,
+    tokens,
+    room_locks,
   });
 
   HttpServer::new(move || {
@@ -49,7 +75,7 @@ async fn main() -> anyhow::Result<()> {
       .wrap(middleware::Logger::default())
       .configure(components::game::routes::attach_routes)
   })
-  .bind(format!("0.0.0.0:{}",port))?
+  .bind(format!("0.0.0.0:{}", port))?
   .run()
   .await?;
 
@@ -1,22 +1,39 @@
 use actix::{Message, Recipient};
 
+use super::turn_timer::TurnDeadline;
+
 // `rtype` is a return type of the message
 
 #[derive(Message)]
 #[rtype(result = "()")]
-pub struct WsMessage(pub String);
+pub enum WsMessage {
+  Text(String),
+  // the server ends the session, with the reason sent in the close frame
+  Close(String),
+}
+
+// what a session may do in its room, spectators only get the messages of the room
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+  Player,
+  Spectator,
+}
 
 #[derive(Message)]
 #[rtype(result = "()")]
 pub struct Connect {
   pub address: Recipient<WsMessage>,
+  // the id of the spectator for spectators
   pub player_id: String,
   pub room_id: String,
+  pub role: Role,
 }
 
 #[derive(Message)]
 #[rtype(result = "()")]
 pub struct Disconnect {
+  // the session that was closed, the player may already have connected again with a new one
+  pub address: Recipient<WsMessage>,
   pub player_id: String,
   pub room_id: String,
 }
@@ -28,3 +45,19 @@ pub struct ClientActorMessage {
   pub room_id: String,
   pub player_id: String,
 }
+
+// the host sent the player out of the lobby, their session leaves the room and is closed
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct Kick {
+  pub room_id: String,
+  pub player_id: String,
+}
+
+// the turn in the room went to a human, the GameServer plays it for them once the deadline passes
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct TurnStarted {
+  pub room_id: String,
+  pub deadline: TurnDeadline,
+}
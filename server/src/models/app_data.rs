@@ -1,12 +1,15 @@
-use std::sync::Arc;
-use tokio::sync::Mutex;
-
 use actix::Addr;
-use mongodb::Database;
 
-use crate::components::game_server::actor::GameServer;
+use crate::components::{
+  game::database::Database, game_server::actor::GameServer, session::actor::Heartbeat,
+};
+use crate::models::room_lock::RoomLocks;
+use crate::utils::access::TokenSigner;
 
 pub struct AppData {
   pub game_server_addr: Addr<GameServer>,
-  pub db: Arc<Mutex<Database>>,
+  pub db: Database,
+  pub heartbeat: Heartbeat,
+  pub tokens: TokenSigner,
+  pub room_locks: RoomLocks,
 }
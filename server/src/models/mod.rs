@@ -1,6 +1,6 @@
 pub mod actor_messages;
 pub mod app_data;
-pub mod color;
-pub mod game;
-pub mod player;
-pub mod position;
+pub mod room_lock;
+pub mod sequence;
+pub mod turn_timer;
+pub mod undo;
@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+// A lock for every room, whoever changes the game of a room holds it from reading the game
+// until the changed game is stored, so the messages, timers and bots of a room take turns
+#[derive(Default)]
+pub struct RoomLockState {
+  locks: HashMap<String, Arc<AsyncMutex<()>>>,
+}
+
+pub type RoomLocks = Arc<Mutex<RoomLockState>>;
+
+impl RoomLockState {
+  pub fn get(&mut self, room_id: &str) -> Arc<AsyncMutex<()>> {
+    self.locks.entry(room_id.to_string()).or_default().clone()
+  }
+}
+
+// waits until nobody else changes the game of the room, it is free again once the guard is dropped
+pub async fn lock_room(locks: &RoomLocks, room_id: &str) -> OwnedMutexGuard<()> {
+  let lock = locks.lock().unwrap().get(room_id);
+  lock.lock_owned().await
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[actix_web::test]
+  async fn rooms_are_locked_apart() {
+    let locks = RoomLocks::default();
+    let guard = lock_room(&locks, "a").await;
+    assert!(locks.lock().unwrap().get("a").try_lock().is_err());
+    assert!(locks.lock().unwrap().get("b").try_lock().is_ok());
+
+    drop(guard);
+    assert!(locks.lock().unwrap().get("a").try_lock().is_ok());
+  }
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use ludo_core::models::{game::Game, game_diff::GameDiff, messages::ServerMessage};
+
+// Numbers of the messages sent to every room, together with the last game the room was sent
+#[derive(Default)]
+pub struct SequenceState {
+  sequences: HashMap<String, u64>,
+  games: HashMap<String, Game>,
+}
+
+pub type Sequences = Arc<Mutex<SequenceState>>;
+
+impl SequenceState {
+  // number of the next message to the whole room
+  pub fn next(&mut self, room_id: &str) -> u64 {
+    let sequence = self.sequences.entry(room_id.to_string()).or_insert(0);
+    *sequence += 1;
+    *sequence
+  }
+
+  // number of the last message sent to the whole room, 0 before the first one
+  pub fn current(&self, room_id: &str) -> u64 {
+    self.sequences.get(room_id).copied().unwrap_or(0)
+  }
+
+  // the game becomes the one the room knows, the next update only sends what changed since
+  pub fn set_game(&mut self, room_id: &str, game: &Game) {
+    self.games.insert(room_id.to_string(), game.clone());
+  }
+
+  // what changed since the last game the room was sent, the whole game if there is no diff for it
+  pub fn update(&mut self, room_id: &str, game: &Game, time_left: Option<u64>) -> ServerMessage {
+    let diff = self
+      .games
+      .get(room_id)
+      .and_then(|known| GameDiff::between(known, game));
+    self.set_game(room_id, game);
+    match diff {
+      Some(diff) => ServerMessage::GameDiff(diff, time_left),
+      None => ServerMessage::GameUpdate(game.clone(), time_left),
+    }
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ludo_core::models::color::Color;
+
+  #[test]
+  fn rooms_are_numbered_apart() {
+    let mut state = SequenceState::default();
+    assert_eq!(state.current("a"), 0);
+    assert_eq!(state.next("a"), 1);
+    assert_eq!(state.next("a"), 2);
+    assert_eq!(state.next("b"), 1);
+    assert_eq!(state.current("a"), 2);
+  }
+
+  #[test]
+  fn only_the_first_update_sends_the_whole_game() {
+    let mut state = SequenceState::default();
+    let mut game = Game::new();
+    assert!(matches!(
+      state.update("a", &game, None),
+      ServerMessage::GameUpdate(..)
+    ));
+
+    game.current_player = Color::Yellow;
+    match state.update("a", &game, Some(500)) {
+      ServerMessage::GameDiff(diff, Some(500)) => {
+        assert_eq!(diff.current_player, Some(Color::Yellow));
+        assert!(diff.players.is_empty());
+      }
+      message => panic!("unexpected message {:?}", message),
+    }
+  }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ludo_core::models::{color::Color, game::Game};
+
+// When the turn of a player in a room runs out
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TurnDeadline {
+  pub color: Color,
+  pub ends_at: Instant,
+}
+
+// The deadline of the turn in every room where a human is on turn
+#[derive(Default)]
+pub struct TurnTimerState {
+  deadlines: HashMap<String, TurnDeadline>,
+}
+
+pub type TurnTimers = Arc<Mutex<TurnTimerState>>;
+
+impl TurnTimerState {
+  // gives the player on turn a new deadline once the turn went to them,
+  // returns it when the timer has to be started
+  pub fn update(&mut self, room_id: &str, game: &Game, now: Instant) -> Option<TurnDeadline> {
+    let timed = game.started
+      && game.winner.is_none()
+      && game.rules.turn_time > 0
+      && !game.is_current_player_ai();
+    // a turn too long to be measured has no deadline
+    let ends_at = now.checked_add(Duration::from_secs(game.rules.turn_time));
+    let ends_at = match ends_at {
+      Some(ends_at) if timed => ends_at,
+      _ => {
+        self.deadlines.remove(room_id);
+        return None;
+      }
+    };
+    match self.deadlines.get(room_id) {
+      Some(deadline) if deadline.color == game.current_player => None,
+      _ => {
+        let deadline = TurnDeadline {
+          color: game.current_player,
+          ends_at,
+        };
+        self.deadlines.insert(room_id.to_string(), deadline);
+        Some(deadline)
+      }
+    }
+  }
+
+  pub fn time_left(&self, room_id: &str, now: Instant) -> Option<Duration> {
+    self
+      .deadlines
+      .get(room_id)
+      .map(|deadline| deadline.ends_at.saturating_duration_since(now))
+  }
+
+  // a timer that fires after the turn has already ended is ignored
+  pub fn is_current(&self, room_id: &str, deadline: &TurnDeadline) -> bool {
+    self.deadlines.get(room_id) == Some(deadline)
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ludo_core::models::player::Player;
+
+  fn started_game() -> Game {
+    let mut game = Game::new();
+    game.players = Color::ordered()
+      .into_iter()
+      .map(|color| {
+        let is_bot = color == Color::Yellow;
+        Player::new(
+          color.to_string(),
+          color.to_string(),
+          color,
+          is_bot,
+          &game.rules,
+        )
+      })
+      .collect();
+    game.started = true;
+    game
+  }
+
+  #[test]
+  fn deadline_follows_the_turn() {
+    let mut timers = TurnTimerState::default();
+    let now = Instant::now();
+    let mut game = started_game();
+
+    let deadline = timers.update("room", &game, now).unwrap();
+    assert_eq!(deadline.color, Color::Green);
+    assert_eq!(deadline.ends_at, now + Duration::from_secs(60));
+    // the same turn keeps its deadline
+    let later = now + Duration::from_secs(20);
+    assert_eq!(timers.update("room", &game, later), None);
+    assert_eq!(
+      timers.time_left("room", later),
+      Some(Duration::from_secs(40))
+    );
+    assert!(timers.is_current("room", &deadline));
+
+    // bots play without a timer
+    game.current_player = Color::Yellow;
+    assert_eq!(timers.update("room", &game, later), None);
+    assert_eq!(timers.time_left("room", later), None);
+    assert!(!timers.is_current("room", &deadline));
+
+    game.current_player = Color::Blue;
+    assert_eq!(
+      timers.update("room", &game, later).unwrap().color,
+      Color::Blue
+    );
+
+    game.rules.turn_time = 0;
+    game.current_player = Color::Red;
+    assert_eq!(timers.update("room", &game, later), None);
+    assert_eq!(timers.time_left("room", later), None);
+
+    // a game stored before turn_time was limited doesn't overflow the clock
+    game.rules.turn_time = u64::MAX;
+    game.current_player = Color::Blue;
+    assert_eq!(timers.update("room", &game, later), None);
+    assert_eq!(timers.time_left("room", later), None);
+  }
+}
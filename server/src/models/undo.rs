@@ -0,0 +1,141 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use ludo_core::models::game::Game;
+
+// how many moves of a room can be taken back one after another
+const HISTORY_SIZE: usize = 10;
+
+// The game before a move of a human, so the move can be taken back
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+  pub game: Game,
+  // the player who made the move
+  pub player_id: String,
+  // length of the event log before the move
+  pub events: u64,
+}
+
+// A takeback that waits for the other humans of the room
+struct UndoRequest {
+  waiting_for: HashSet<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UndoAnswer {
+  // everyone agreed, the move can be taken back
+  Accepted,
+  Rejected,
+  // there are still players who haven't answered
+  Waiting,
+  // the player wasn't asked
+  NotAsked,
+}
+
+// Snapshots and pending takebacks of every room
+#[derive(Default)]
+pub struct UndoState {
+  history: HashMap<String, Vec<Snapshot>>,
+  requests: HashMap<String, UndoRequest>,
+}
+
+pub type Undo = Arc<Mutex<UndoState>>;
+
+impl UndoState {
+  // a new move makes a pending takeback of the room outdated
+  pub fn push_snapshot(&mut self, room_id: &str, snapshot: Snapshot) {
+    let history = self.history.entry(room_id.to_string()).or_default();
+    history.push(snapshot);
+    if history.len() > HISTORY_SIZE {
+      history.remove(0);
+    }
+    self.requests.remove(room_id);
+  }
+
+  pub fn last_snapshot(&self, room_id: &str) -> Option<&Snapshot> {
+    self.history.get(room_id).and_then(|history| history.last())
+  }
+
+  pub fn pop_snapshot(&mut self, room_id: &str) -> Option<Snapshot> {
+    self
+      .history
+      .get_mut(room_id)
+      .and_then(|history| history.pop())
+  }
+
+  pub fn has_request(&self, room_id: &str) -> bool {
+    self.requests.contains_key(room_id)
+  }
+
+  pub fn request(&mut self, room_id: &str, waiting_for: HashSet<String>) {
+    self
+      .requests
+      .insert(room_id.to_string(), UndoRequest { waiting_for });
+  }
+
+  pub fn answer(&mut self, room_id: &str, player_id: &str, accept: bool) -> UndoAnswer {
+    let request = match self.requests.get_mut(room_id) {
+      Some(request) if request.waiting_for.contains(player_id) => request,
+      _ => return UndoAnswer::NotAsked,
+    };
+    request.waiting_for.remove(player_id);
+    let answer = match (accept, request.waiting_for.is_empty()) {
+      (false, _) => UndoAnswer::Rejected,
+      (true, true) => UndoAnswer::Accepted,
+      (true, false) => return UndoAnswer::Waiting,
+    };
+    self.requests.remove(room_id);
+    answer
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn snapshot(events: u64) -> Snapshot {
+    Snapshot {
+      game: Game::new(),
+      player_id: String::from("a"),
+      events,
+    }
+  }
+
+  #[test]
+  fn history_is_limited() {
+    let mut undo = UndoState::default();
+    for events in 0..HISTORY_SIZE as u64 + 2 {
+      undo.push_snapshot("room", snapshot(events));
+    }
+    assert_eq!(undo.history["room"].len(), HISTORY_SIZE);
+    assert_eq!(undo.history["room"][0].events, 2);
+    assert_eq!(
+      undo.pop_snapshot("room").unwrap().events,
+      HISTORY_SIZE as u64 + 1
+    );
+    assert!(undo.pop_snapshot("other").is_none());
+  }
+
+  #[test]
+  fn everyone_has_to_agree() {
+    let mut undo = UndoState::default();
+    let players = ["b", "c"].iter().map(|id| id.to_string()).collect();
+    undo.request("room", players);
+    assert_eq!(undo.answer("room", "a", true), UndoAnswer::NotAsked);
+    assert_eq!(undo.answer("room", "b", true), UndoAnswer::Waiting);
+    assert_eq!(undo.answer("room", "b", true), UndoAnswer::NotAsked);
+    assert_eq!(undo.answer("room", "c", true), UndoAnswer::Accepted);
+    assert!(!undo.has_request("room"));
+
+    undo.request("room", vec!["b".to_string()].into_iter().collect());
+    assert_eq!(undo.answer("room", "b", false), UndoAnswer::Rejected);
+    assert!(!undo.has_request("room"));
+
+    // a move made in the meantime cancels the request
+    undo.request("room", vec!["b".to_string()].into_iter().collect());
+    undo.push_snapshot("room", snapshot(3));
+    assert_eq!(undo.answer("room", "b", true), UndoAnswer::NotAsked);
+  }
+}
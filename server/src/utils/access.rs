@@ -0,0 +1,282 @@
+use hmac::{Hmac, Mac};
+use rand::{distributions::Slice, Rng};
+use sha2::Sha256;
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+
+use crate::components::game::database::Database;
+
+pub const JOIN_CODE_LENGTH: usize = 6;
+// letters and digits that can't be mistaken for one another when read out loud
+const JOIN_CODE_ALPHABET: &[char] = &[
+  'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'J', 'K', 'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'U', 'V',
+  'W', 'X', 'Y', 'Z', '2', '3', '4', '5', '6', '7', '8', '9',
+];
+const SALT_LENGTH: usize = 16;
+const PASSWORD_ROUNDS: u32 = 10_000;
+// seconds a session token can be used to connect after the player joined
+pub const TOKEN_LIFETIME: i64 = 24 * 60 * 60;
+
+pub fn new_join_code() -> String {
+  rand::thread_rng()
+    .sample_iter(Slice::new(JOIN_CODE_ALPHABET).unwrap())
+    .take(JOIN_CODE_LENGTH)
+    .collect()
+}
+
+// codes are typed in by people, so the case and the spaces around them don't matter
+pub fn normalize_join_code(code: &str) -> String {
+  code.trim().to_uppercase()
+}
+
+fn derive(password: &str, salt: &[u8]) -> [u8; 32] {
+  let mut hash = [0; 32];
+  pbkdf2::pbkdf2::<Hmac<Sha256>>(password.as_bytes(), salt, PASSWORD_ROUNDS, &mut hash);
+  hash
+}
+
+// the salt and the hash in hex, separated by '$'
+pub fn hash_password(password: &str) -> String {
+  let salt: [u8; SALT_LENGTH] = rand::thread_rng().gen();
+  format!(
+    "{}${}",
+    hex::encode(salt),
+    hex::encode(derive(password, &salt))
+  )
+}
+
+pub fn verify_password(password: &str, stored: &str) -> bool {
+  let (salt, hash) = match stored.split_once('$') {
+    Some(parts) => parts,
+    None => return false,
+  };
+  match (hex::decode(salt), hex::decode(hash)) {
+    // compared in constant time, so the time taken doesn't tell how much of the hash matched
+    (Ok(salt), Ok(hash)) => derive(password, &salt).ct_eq(&hash[..]).into(),
+    _ => false,
+  }
+}
+
+// Signs the session tokens players connect to the websocket of a game with. A token is
+// '{player_id}.{expires}.{signature}', the signature covers the room as well, so a token only
+// opens the game it was issued for. The player id alone is public, it's in every game update.
+#[derive(Clone)]
+pub struct TokenSigner {
+  secret: Arc<[u8]>,
+  lifetime: i64,
+}
+
+impl TokenSigner {
+  pub fn new(secret: &[u8]) -> Self {
+    TokenSigner {
+      secret: secret.into(),
+      lifetime: TOKEN_LIFETIME,
+    }
+  }
+
+  // tokens signed with a random secret stop working once the server restarts
+  pub fn random() -> Self {
+    let secret: [u8; 32] = rand::thread_rng().gen();
+    TokenSigner::new(&secret)
+  }
+
+  pub fn with_lifetime(mut self, seconds: i64) -> Self {
+    self.lifetime = seconds;
+    self
+  }
+
+  fn mac(&self, room: &str, player_id: &str, expires: i64) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).expect("any key length works");
+    mac.update(format!("{}.{}.{}", room, player_id, expires).as_bytes());
+    mac
+  }
+
+  // 'now' and the expiry are seconds since the unix epoch
+  pub fn issue(&self, room: &str, player_id: &str, now: i64) -> String {
+    let expires = now + self.lifetime;
+    let signature = self.mac(room, player_id, expires).finalize().into_bytes();
+    format!("{}.{}.{}", player_id, expires, hex::encode(signature))
+  }
+
+  // the id of the player the token was issued to, None when it's forged, expired or for another room
+  pub fn verify(&self, token: &str, room: &str, now: i64) -> Option<String> {
+    let mut parts = token.rsplitn(3, '.');
+    let signature = hex::decode(parts.next()?).ok()?;
+    let expires = parts.next()?.parse::<i64>().ok()?;
+    let player_id = parts.next()?;
+    self
+      .mac(room, player_id, expires)
+      .verify_slice(&signature)
+      .ok()?;
+    match expires > now {
+      true => Some(player_id.to_string()),
+      false => None,
+    }
+  }
+}
+
+// the player the token belongs to, as long as they still sit in the game
+pub async fn authorize(
+  db: &Database,
+  tokens: &TokenSigner,
+  room: &str,
+  token: &str,
+) -> Result<String, &'static str> {
+  let now = chrono::Utc::now().timestamp();
+  let player_id = tokens
+    .verify(token, room, now)
+    .ok_or("Invalid or expired token")?;
+  let game = match db.find_game(room).await {
+    Ok(Some(game)) => game,
+    _ => return Err("Game does not exist"),
+  };
+  match game.get_player_by_id(&player_id) {
+    Some(_) => Ok(player_id),
+    None => Err("You haven't joined this game"),
+  }
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use actix_web::http::StatusCode;
+  use actix_web::test::{call_and_read_body, call_and_read_body_json, call_service, TestRequest};
+  use futures::StreamExt;
+  use std::time::Duration;
+  use tokio::time::timeout;
+  use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+  use ludo_core::models::{
+    game_event::{GameEvent, LoggedEvent},
+    lobby::LobbyPage,
+    messages::{ClientMessage, ServerMessage},
+  };
+
+  use crate::utils::testing::*;
+
+  #[test]
+  fn join_codes() {
+    let code = new_join_code();
+    assert_eq!(code.len(), JOIN_CODE_LENGTH);
+    assert!(code.chars().all(|c| JOIN_CODE_ALPHABET.contains(&c)));
+    assert_eq!(normalize_join_code(" ab3k9z "), "AB3K9Z");
+  }
+
+  #[test]
+  fn passwords() {
+    let stored = hash_password("secret");
+    assert_ne!(stored, hash_password("secret"));
+    assert!(verify_password("secret", &stored));
+    assert!(!verify_password("Secret", &stored));
+    assert!(!verify_password("secret", "not a hash"));
+  }
+
+  #[test]
+  fn tokens() {
+    let signer = TokenSigner::new(b"secret");
+    let token = signer.issue("room", "player", 1_000);
+    assert_eq!(signer.verify(&token, "room", 1_000), Some("player".into()));
+    assert_eq!(signer.verify(&token, "other room", 1_000), None);
+    assert_eq!(signer.verify(&token, "room", 1_000 + TOKEN_LIFETIME), None);
+    assert_eq!(
+      TokenSigner::new(b"other").verify(&token, "room", 1_000),
+      None
+    );
+
+    // the player id can't be swapped without the signature breaking
+    let forged = token.replacen("player", "someone", 1);
+    assert_eq!(signer.verify(&forged, "room", 1_000), None);
+    assert_eq!(signer.verify("player", "room", 1_000), None);
+  }
+
+  #[actix_web::test]
+  async fn private_game_with_password() {
+    let app = app(app_data()).await;
+    let request = TestRequest::post()
+      .uri("/games")
+      .set_json(serde_json::json!({ "password": "secret" }))
+      .to_request();
+    let response = call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = serde_json::json!({ "private": true, "password": "secret" });
+    let room = create_game(&app, body).await;
+    let request = TestRequest::get()
+      .uri(&format!("/games/{}/events", room))
+      .to_request();
+    let events: Vec<LoggedEvent> = call_and_read_body_json(&app, request).await;
+    let code = match &events[0].event {
+      GameEvent::Created { join_code, .. } => join_code.clone().unwrap(),
+      event => panic!("the game starts with {:?}", event),
+    };
+
+    let request = TestRequest::get()
+      .uri(&format!("/games/codes/{}", code.to_lowercase()))
+      .to_request();
+    let found = String::from_utf8(call_and_read_body(&app, request).await.to_vec()).unwrap();
+    assert_eq!(found, room);
+    let request = TestRequest::get().uri("/games/codes/NOPE22").to_request();
+    let response = call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let join = |password: Option<&str>| {
+      TestRequest::put()
+        .uri(&format!("/games/{}", room))
+        .set_json(serde_json::json!({ "name": "player", "password": password }))
+        .to_request()
+    };
+    let response = call_service(&app, join(None)).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = call_service(&app, join(Some("Secret"))).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = call_service(&app, join(Some("secret"))).await;
+    assert!(response.status().is_success());
+
+    let request = TestRequest::get().uri("/games").to_request();
+    let page: LobbyPage = call_and_read_body_json(&app, request).await;
+    assert!(page.lobbies.is_empty());
+  }
+
+  #[actix_web::test]
+  async fn websocket_needs_a_token() {
+    let app_data = app_data();
+    let address = spawn_server(app_data.clone());
+    let app = app(app_data.clone()).await;
+    let room = create_game(&app, serde_json::json!({})).await;
+    let joined = join_game(&app, &room, "player").await;
+    let url = format!("ws://{}/games/websocket/{}", address, room);
+
+    // browsers can't set headers, they send the token first
+    let (mut socket, _) = connect_async(url.clone()).await.unwrap();
+    let authenticate = ClientMessage::Authenticate(joined.token.clone());
+    send_message(&mut socket, authenticate).await;
+    match wait_for(&mut socket, |message| {
+      matches!(message, ServerMessage::ConnectResponse(..))
+    })
+    .await
+    {
+      ServerMessage::ConnectResponse(game, color) => {
+        assert_eq!(game.get_player(color).id, joined.player_id)
+      }
+      _ => unreachable!(),
+    }
+
+    // anything else first closes the websocket
+    let (mut socket, _) = connect_async(url).await.unwrap();
+    send_message(&mut socket, ClientMessage::ThrowDice).await;
+    let frame = timeout(Duration::from_secs(5), socket.next())
+      .await
+      .unwrap();
+    assert!(matches!(frame, Some(Ok(Message::Close(_))) | None));
+
+    // the player id alone, a token of another room and a token of another secret are refused
+    let other_room = token(&app_data, "other", &joined.player_id);
+    let forged = TokenSigner::new(b"guess").issue(&room, &joined.player_id, 0);
+    for token in [joined.player_id.as_str(), &other_room, &forged] {
+      let request = websocket_request(&address, &room, token);
+      assert!(connect_async(request).await.is_err());
+    }
+  }
+}
@@ -1,6 +1,109 @@
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::sync::Arc;
 
-pub fn get_dice_value() -> usize {
-  let mut rng = rand::thread_rng();
-  rng.gen_range(1..7)
+use ludo_core::models::game::Game;
+
+// Where the game server takes the dice rolls from
+pub trait DiceSource: Send + Sync {
+  // the next roll of 'game', counted in game.dice_rolls
+  fn roll(&self, game: &mut Game) -> usize;
+}
+
+pub type Dice = Arc<dyn DiceSource>;
+
+// every roll follows from the seed of the game and the number of rolls before it,
+//   so a game with the same seed gets the same dice
+pub struct SeededDice;
+
+impl DiceSource for SeededDice {
+  fn roll(&self, game: &mut Game) -> usize {
+    let mut rng = ChaCha8Rng::seed_from_u64(game.dice_seed);
+    rng.set_stream(game.dice_rolls);
+    game.dice_rolls += 1;
+    rng.gen_range(1..7)
+  }
+}
+
+// rolls the given values in order and starts over once they run out, used in tests
+#[cfg(test)]
+pub struct ScriptedDice {
+  rolls: Vec<usize>,
+  next: std::sync::Mutex<usize>,
+}
+
+#[cfg(test)]
+impl ScriptedDice {
+  pub fn new(rolls: Vec<usize>) -> Self {
+    ScriptedDice {
+      rolls,
+      next: std::sync::Mutex::new(0),
+    }
+  }
+}
+
+#[cfg(test)]
+impl DiceSource for ScriptedDice {
+  fn roll(&self, game: &mut Game) -> usize {
+    let mut next = self.next.lock().unwrap();
+    let roll = self.rolls[*next % self.rolls.len()];
+    *next += 1;
+    game.dice_rolls += 1;
+    roll
+  }
+}
+
+// seeds stay below 2^53, so they survive bson and javascript numbers
+pub const MAX_SEED: u64 = (1 << 53) - 1;
+
+pub fn new_seed() -> u64 {
+  rand::thread_rng().gen_range(0..=MAX_SEED)
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn roll_many(dice: &dyn DiceSource, game: &mut Game, count: usize) -> Vec<usize> {
+    (0..count).map(|_| dice.roll(game)).collect()
+  }
+
+  #[test]
+  fn same_seed_same_rolls() {
+    let mut game = Game::new();
+    game.dice_seed = 1234;
+    let rolls = roll_many(&SeededDice, &mut game, 50);
+    assert!(rolls.iter().all(|roll| (1..=6).contains(roll)));
+    assert_eq!(game.dice_rolls, 50);
+
+    let mut replayed = Game::new();
+    replayed.dice_seed = 1234;
+    assert_eq!(roll_many(&SeededDice, &mut replayed, 50), rolls);
+
+    let mut other = Game::new();
+    other.dice_seed = 4321;
+    assert_ne!(roll_many(&SeededDice, &mut other, 50), rolls);
+  }
+
+  // a stored game continues with the rolls it would have had without a restart
+  #[test]
+  fn continues_from_stored_rolls() {
+    let mut game = Game::new();
+    game.dice_seed = 99;
+    let rolls = roll_many(&SeededDice, &mut game, 10);
+
+    let mut stored = Game::new();
+    stored.dice_seed = 99;
+    stored.dice_rolls = 4;
+    assert_eq!(roll_many(&SeededDice, &mut stored, 6), rolls[4..]);
+  }
+
+  #[test]
+  fn scripted_rolls() {
+    let dice = ScriptedDice::new(vec![6, 6, 2]);
+    let mut game = Game::new();
+    assert_eq!(roll_many(&dice, &mut game, 4), vec![6, 6, 2, 6]);
+  }
 }
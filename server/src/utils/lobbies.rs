@@ -0,0 +1,255 @@
+use serde::Deserialize;
+
+use crate::components::game::database::OpenGame;
+use ludo_core::models::{
+  color::Color,
+  lobby::{LobbyPage, LobbySummary},
+  rule_set::RuleSet,
+};
+
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+pub const MAX_PAGE_SIZE: usize = 50;
+
+// Query of the lobby listing, every filter is optional
+#[derive(Deserialize, Default)]
+pub struct LobbyQuery {
+  // the next_cursor of the previous page
+  pub cursor: Option<String>,
+  pub limit: Option<usize>,
+  // only games with at least this many free seats
+  pub free_seats: Option<usize>,
+  // only games whose host has the text in their name, ignoring case
+  pub host: Option<String>,
+  // only games played with the standard rules, or only the ones with house rules
+  pub standard_rules: Option<bool>,
+}
+
+// the cursor points at the last game of a page by its creation time and id
+fn encode_cursor(created_at: i64, id: &str) -> String {
+  format!("{}_{}", created_at, id)
+}
+
+fn decode_cursor(cursor: &str) -> Option<(i64, String)> {
+  let (created_at, id) = cursor.split_once('_')?;
+  Some((created_at.parse().ok()?, id.to_string()))
+}
+
+// games nobody joined yet have no host to play with and private games are only found by
+// their code, neither are listed
+fn summarize(open: OpenGame, now: i64) -> Option<LobbySummary> {
+  if open.game.join_code.is_some() {
+    return None;
+  }
+  let host = open.game.get_host()?.name.clone();
+  let players = open.game.players.len();
+  Some(LobbySummary {
+    id: open.id,
+    host,
+    players,
+    free_seats: Color::ordered().len().saturating_sub(players),
+    rules: open.game.rules,
+    age: ((now - open.created_at).max(0) / 1000) as u64,
+  })
+}
+
+impl LobbyQuery {
+  fn matches(&self, lobby: &LobbySummary) -> bool {
+    let host = self.host.as_ref().map(|host| host.to_lowercase());
+    lobby.free_seats >= self.free_seats.unwrap_or(1).max(1)
+      && host.is_none_or(|host| lobby.host.to_lowercase().contains(&host))
+      && self
+        .standard_rules
+        .is_none_or(|standard| (lobby.rules == RuleSet::default()) == standard)
+  }
+}
+
+// the games with a free seat that match the query, the newest first, starting after the cursor
+pub fn lobby_page(games: Vec<OpenGame>, query: &LobbyQuery, now: i64) -> Result<LobbyPage, String> {
+  let after = match &query.cursor {
+    Some(cursor) => Some(decode_cursor(cursor).ok_or("Invalid cursor")?),
+    None => None,
+  };
+  let limit = query
+    .limit
+    .unwrap_or(DEFAULT_PAGE_SIZE)
+    .clamp(1, MAX_PAGE_SIZE);
+
+  let mut games = games;
+  games.sort_by(|a, b| (b.created_at, &b.id).cmp(&(a.created_at, &a.id)));
+  let mut page = games
+    .into_iter()
+    .filter(|game| match &after {
+      Some((created_at, id)) => (game.created_at, &game.id) < (*created_at, id),
+      None => true,
+    })
+    .filter_map(|game| {
+      let created_at = game.created_at;
+      summarize(game, now).map(|lobby| (created_at, lobby))
+    })
+    .filter(|(_, lobby)| query.matches(lobby))
+    .take(limit + 1)
+    .collect::<Vec<_>>();
+
+  // one game more than asked for means there is another page
+  let next_cursor = match page.len() > limit {
+    true => {
+      page.truncate(limit);
+      page
+        .last()
+        .map(|(created_at, lobby)| encode_cursor(*created_at, &lobby.id))
+    }
+    false => None,
+  };
+  Ok(LobbyPage {
+    lobbies: page.into_iter().map(|(_, lobby)| lobby).collect(),
+    next_cursor,
+  })
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use actix_web::http::StatusCode;
+  use actix_web::test::{call_and_read_body_json, call_service, TestRequest};
+  use ludo_core::models::{game::Game, player::Player};
+
+  use crate::utils::testing::{app, app_data, create_game, join_game};
+
+  fn open_game(id: &str, created_at: i64, names: &[&str]) -> OpenGame {
+    let mut game = Game::new();
+    game.players = names
+      .iter()
+      .zip(Color::ordered())
+      .map(|(name, color)| {
+        Player::new(
+          name.to_string(),
+          name.to_string(),
+          color,
+          false,
+          &game.rules,
+        )
+      })
+      .collect();
+    OpenGame {
+      id: id.to_string(),
+      created_at,
+      game,
+    }
+  }
+
+  fn ids(page: &LobbyPage) -> Vec<&str> {
+    page.lobbies.iter().map(|lobby| lobby.id.as_str()).collect()
+  }
+
+  #[test]
+  fn pages_follow_the_cursor() {
+    let mut private = open_game("f", 4_500, &["fay"]);
+    private.game.join_code = Some("ABC234".into());
+    let games = vec![
+      open_game("a", 1_000, &["ann"]),
+      open_game("b", 3_000, &["bob"]),
+      open_game("c", 2_000, &["cid"]),
+      open_game("d", 2_000, &["dan"]),
+      // nobody to play with yet
+      open_game("e", 4_000, &[]),
+      private,
+    ];
+    let mut query = LobbyQuery {
+      limit: Some(2),
+      ..LobbyQuery::default()
+    };
+
+    let first = lobby_page(games.clone(), &query, 5_000).unwrap();
+    assert_eq!(ids(&first), vec!["b", "d"]);
+    assert_eq!(first.lobbies[0].age, 2);
+    assert_eq!(first.lobbies[0].host, "bob");
+    assert_eq!(first.lobbies[0].free_seats, 3);
+
+    query.cursor = first.next_cursor;
+    let second = lobby_page(games.clone(), &query, 5_000).unwrap();
+    assert_eq!(ids(&second), vec!["c", "a"]);
+    assert_eq!(second.next_cursor, None);
+
+    query.cursor = Some("not a cursor".into());
+    assert!(lobby_page(games, &query, 5_000).is_err());
+  }
+
+  #[test]
+  fn filters() {
+    let mut custom = open_game("custom", 1_000, &["Carol"]);
+    custom.game.rules.turn_time = 0;
+    let games = vec![
+      custom,
+      open_game("full", 2_000, &["a", "b", "c", "d"]),
+      open_game("pair", 3_000, &["Dave", "eve"]),
+    ];
+
+    let all = lobby_page(games.clone(), &LobbyQuery::default(), 5_000).unwrap();
+    assert_eq!(ids(&all), vec!["pair", "custom"]);
+
+    let query = LobbyQuery {
+      free_seats: Some(3),
+      ..LobbyQuery::default()
+    };
+    assert_eq!(
+      ids(&lobby_page(games.clone(), &query, 5_000).unwrap()),
+      vec!["custom"]
+    );
+
+    let query = LobbyQuery {
+      host: Some("dav".into()),
+      ..LobbyQuery::default()
+    };
+    assert_eq!(
+      ids(&lobby_page(games.clone(), &query, 5_000).unwrap()),
+      vec!["pair"]
+    );
+
+    let query = LobbyQuery {
+      standard_rules: Some(false),
+      ..LobbyQuery::default()
+    };
+    assert_eq!(
+      ids(&lobby_page(games, &query, 5_000).unwrap()),
+      vec!["custom"]
+    );
+  }
+
+  #[actix_web::test]
+  async fn list_open_lobbies() {
+    let app_data = app_data();
+    let app = app(app_data.clone()).await;
+
+    let mut rooms = vec![];
+    for name in ["ann", "bob", "cid"] {
+      let room = create_game(&app, serde_json::json!({})).await;
+      join_game(&app, &room, name).await;
+      rooms.push(room);
+    }
+    // nobody joined the empty game and the started one can't be joined anymore
+    create_game(&app, serde_json::json!({})).await;
+    app_data.db.start_game(&rooms[2]).await.unwrap();
+
+    let mut hosts = vec![];
+    let mut uri = String::from("/games?limit=1");
+    loop {
+      let request = TestRequest::get().uri(&uri).to_request();
+      let page: LobbyPage = call_and_read_body_json(&app, request).await;
+      assert_eq!(page.lobbies.len(), 1);
+      assert_eq!(page.lobbies[0].free_seats, 3);
+      hosts.push(page.lobbies[0].host.clone());
+      match page.next_cursor {
+        Some(cursor) => uri = format!("/games?limit=1&cursor={}", cursor),
+        None => break,
+      }
+    }
+    hosts.sort();
+    assert_eq!(hosts, vec!["ann", "bob"]);
+
+    let request = TestRequest::get().uri("/games?cursor=nothing").to_request();
+    let response = call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  }
+}
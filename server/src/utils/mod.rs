@@ -1,5 +1,8 @@
+pub mod access;
 pub mod bot;
 pub mod dice;
-pub mod enums;
 pub mod game;
-pub mod player;
+pub mod lobbies;
+pub mod notation;
+#[cfg(test)]
+pub mod testing;
@@ -0,0 +1,490 @@
+use std::fmt;
+use uuid::Uuid;
+
+use ludo_core::models::{
+  color::Color,
+  game::Game,
+  game_event::{effective_events, GameEvent, LoggedEvent},
+  player::Player,
+  rule_set::RuleSet,
+};
+use ludo_core::utils::enums::{Move, MoveResult, RoundPhase};
+use ludo_core::utils::player::{get_die_moves, play_round};
+
+use super::dice::{new_seed, MAX_SEED};
+
+// A whole game as text, the header holds the rules, the seed and the players, then there is a line for every turn:
+//
+//   [Rules {"field_count":52,"home_size":5,...}]
+//   [Seed 1234]
+//   [Green "alice"]
+//   [Yellow "Bot Bob" bot]
+//   ...
+//
+//   1. G 6 2 : E 14-16
+//   2. Y 6 6 6 : -
+//
+// a turn lists the rolls and after the colon the moves made with them:
+//   E           new piece onto the board
+//   14-16       on the main board
+//   50-h1       from the main board into the home column
+//   h1-h3       in the home column
+//   14-F, h3-F  to the finish
+//   -           no move, the turn was skipped
+// an x after a move marks a capture, the last turn can be unfinished
+
+#[derive(Debug, PartialEq)]
+pub struct NotationError {
+  // turns are numbered from 1, None for errors in the header
+  pub turn: Option<usize>,
+  pub message: String,
+}
+
+impl fmt::Display for NotationError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self.turn {
+      Some(turn) => write!(f, "turn {}: {}", turn, self.message),
+      None => write!(f, "{}", self.message),
+    }
+  }
+}
+
+fn header_error(message: String) -> NotationError {
+  NotationError {
+    turn: None,
+    message,
+  }
+}
+
+// the first letter of every color is different
+fn color_letter(color: Color) -> String {
+  color.to_string()[..1].to_string()
+}
+
+fn find_color(matches: impl Fn(&str) -> bool) -> Option<Color> {
+  Color::ordered()
+    .into_iter()
+    .find(|color| matches(&color.to_string()))
+}
+
+pub fn format_move(player_move: &Move) -> String {
+  let (text, capture) = match *player_move {
+    Move::Enter { capture, .. } => (String::from("E"), capture),
+    Move::Advance { from, to, capture } => (format!("{}-{}", from, to), capture),
+    Move::EnterHome { from, to } => (format!("{}-h{}", from, to), false),
+    Move::AdvanceInHome { from, to } => (format!("h{}-h{}", from, to), false),
+    Move::Finish { from, from_home } => match from_home {
+      true => (format!("h{}-F", from), false),
+      false => (format!("{}-F", from), false),
+    },
+  };
+  match capture {
+    true => format!("{}x", text),
+    false => text,
+  }
+}
+
+struct Turn {
+  color: Color,
+  rolls: Vec<usize>,
+  moves: Vec<String>,
+}
+
+// None until the game was started, a game in the lobby has nothing to export
+pub fn export_game(events: &[LoggedEvent]) -> Option<String> {
+  // moves that were taken back aren't part of the game
+  let events = effective_events(events.iter().map(|logged| &logged.event));
+  let started = events
+    .iter()
+    .position(|event| matches!(event, GameEvent::Started(_)))?;
+  let mut game = Game::from_events(events[..=started].iter().copied());
+
+  let mut lines = vec![
+    format!("[Rules {}]", serde_json::to_string(&game.rules).unwrap()),
+    format!("[Seed {}]", game.dice_seed),
+  ];
+  for player in &game.players {
+    let bot = match player.is_bot {
+      true => " bot",
+      false => "",
+    };
+    let name = serde_json::to_string(&player.name).unwrap();
+    lines.push(format!("[{} {}{}]", player.color, name, bot));
+  }
+  lines.push(String::new());
+
+  let mut turns: Vec<Turn> = vec![];
+  for event in &events[started + 1..] {
+    if turns.last().map(|turn| turn.color) != Some(game.current_player) {
+      turns.push(Turn {
+        color: game.current_player,
+        rolls: vec![],
+        moves: vec![],
+      });
+    }
+    let turn = turns.last_mut().unwrap();
+    match event {
+      GameEvent::Rolled(roll) => turn.rolls.push(*roll),
+      GameEvent::Moved(player_move) => turn.moves.push(format_move(player_move)),
+      GameEvent::Skipped => turn.moves.push(String::from("-")),
+      _ => {}
+    }
+    game.apply_event(event);
+  }
+
+  for (index, turn) in turns.iter().enumerate() {
+    let rolls = turn
+      .rolls
+      .iter()
+      .map(|roll| roll.to_string())
+      .collect::<Vec<_>>();
+    let mut line = format!(
+      "{}. {} {}",
+      index + 1,
+      color_letter(turn.color),
+      rolls.join(" ")
+    );
+    if !turn.moves.is_empty() {
+      line = format!("{} : {}", line, turn.moves.join(" "));
+    }
+    lines.push(line);
+  }
+  Some(lines.join("\n") + "\n")
+}
+
+// the events of the game written in 'text', every roll and move is checked against the rules
+pub fn import_game(text: &str) -> Result<Vec<GameEvent>, NotationError> {
+  let lines = text
+    .lines()
+    .map(|line| line.trim())
+    .filter(|line| !line.is_empty())
+    .collect::<Vec<_>>();
+  let header_size = lines
+    .iter()
+    .position(|line| !line.starts_with('['))
+    .unwrap_or(lines.len());
+
+  let mut events = read_header(&lines[..header_size])?;
+  let mut game = Game::from_events(&events);
+
+  let turn_lines = &lines[header_size..];
+  for (index, line) in turn_lines.iter().enumerate() {
+    let is_last = index + 1 == turn_lines.len();
+    read_turn(&mut game, &mut events, line, index + 1, is_last).map_err(|message| {
+      NotationError {
+        turn: Some(index + 1),
+        message,
+      }
+    })?;
+  }
+  Ok(events)
+}
+
+// the game up to its start
+fn read_header(lines: &[&str]) -> Result<Vec<GameEvent>, NotationError> {
+  let mut rules = RuleSet::default();
+  let mut dice_seed = None;
+  let mut seats: Vec<(Color, String, bool)> = vec![];
+
+  for line in lines {
+    let content = line
+      .strip_prefix('[')
+      .and_then(|line| line.strip_suffix(']'))
+      .ok_or_else(|| header_error(format!("{} is not a header line", line)))?;
+    let (key, value) = content.split_once(' ').unwrap_or((content, ""));
+    match key {
+      "Rules" => {
+        rules = serde_json::from_str(value)
+          .map_err(|_| header_error(String::from("the rules can't be read")))?;
+        rules.validate().map_err(header_error)?;
+      }
+      "Seed" => match value.parse::<u64>() {
+        Ok(seed) if seed <= MAX_SEED => dice_seed = Some(seed),
+        _ => return Err(header_error(format!("{} is not a valid seed", value))),
+      },
+      key => {
+        let color = find_color(|name| name == key)
+          .ok_or_else(|| header_error(format!("unknown header {}", key)))?;
+        let (name, bot) = match value.strip_suffix(" bot") {
+          Some(name) => (name, true),
+          None => (value, false),
+        };
+        let name = serde_json::from_str::<String>(name)
+          .map_err(|_| header_error(format!("the name of {} has to be quoted", color)))?;
+        seats.push((color, name, bot));
+      }
+    }
+  }
+
+  for color in Color::ordered() {
+    if seats.iter().filter(|(seat, _, _)| *seat == color).count() != 1 {
+      return Err(header_error(format!("{} needs exactly one player", color)));
+    }
+  }
+
+  // imported players get new ids, the ones of the exported game stay with its server
+  let players = seats
+    .into_iter()
+    .map(|(color, name, bot)| {
+      let id = match bot {
+        true => String::from("0"),
+        false => Uuid::new_v4().to_string(),
+      };
+      Player::new(id, name, color, bot, &rules)
+    })
+    .collect::<Vec<_>>();
+
+  let mut events = vec![GameEvent::Created {
+    rules,
+    dice_seed: dice_seed.unwrap_or_else(new_seed),
+    join_code: None,
+  }];
+  events.extend(
+    players
+      .iter()
+      .filter(|player| !player.is_bot)
+      .map(|player| GameEvent::Joined(player.clone())),
+  );
+  events.push(GameEvent::Started(players));
+  Ok(events)
+}
+
+// plays the turn written on 'line', only the last turn can stop before the next player is on turn
+fn read_turn(
+  game: &mut Game,
+  events: &mut Vec<GameEvent>,
+  line: &str,
+  number: usize,
+  is_last: bool,
+) -> Result<(), String> {
+  let (head, moves) = line.split_once(':').unwrap_or((line, ""));
+  let mut head = head.split_whitespace();
+
+  if head.next() != Some(format!("{}.", number).as_str()) {
+    return Err(String::from("the turns have to be numbered in order"));
+  }
+  let color = head
+    .next()
+    .and_then(|letter| find_color(|name| name.starts_with(letter) && letter.len() == 1))
+    .ok_or_else(|| String::from("the color of the player is missing"))?;
+  if game.winner.is_some() {
+    return Err(String::from("the game is already over"));
+  }
+  if color != game.current_player {
+    return Err(format!("it is {}'s turn", game.current_player));
+  }
+
+  let rolls = head
+    .map(|roll| {
+      roll
+        .parse::<usize>()
+        .ok()
+        .filter(|roll| (1..=6).contains(roll))
+    })
+    .collect::<Option<Vec<_>>>()
+    .ok_or_else(|| String::from("a roll has to be a number from 1 to 6"))?;
+  if rolls.is_empty() {
+    return Err(String::from("the turn has no rolls"));
+  }
+  for roll in rolls {
+    let rolling_is_over = game.round_phase == RoundPhase::Moving
+      || !(game.dice_throws.is_empty() || game.rules.can_roll_again(&game.dice_throws));
+    if rolling_is_over {
+      return Err(format!("the roll of {} is one too many", roll));
+    }
+    let event = GameEvent::Rolled(roll);
+    game.apply_event(&event);
+    events.push(event);
+  }
+
+  for token in moves.split_whitespace() {
+    if token == "-" {
+      let forfeit =
+        game.round_phase == RoundPhase::Rolling && game.rules.is_forfeit(&game.dice_throws);
+      let stuck = game.round_phase == RoundPhase::Moving
+        && get_die_moves(game).iter().all(|die| die.is_empty());
+      if !forfeit && !stuck {
+        return Err(String::from(
+          "the turn can't be skipped, there is a move to make",
+        ));
+      }
+      game.apply_event(&GameEvent::Skipped);
+      events.push(GameEvent::Skipped);
+      continue;
+    }
+
+    if game.round_phase != RoundPhase::Moving || game.current_player != color {
+      return Err(format!(
+        "{} is played when there is nothing to move with",
+        token
+      ));
+    }
+    let player_move = game
+      .pending_moves
+      .iter()
+      .flat_map(|roll| game.legal_moves(*roll))
+      .find(|legal_move| format_move(legal_move) == token)
+      .ok_or_else(|| format!("{} is not a legal move", token))?;
+    if let MoveResult::Error(err) = play_round(game, player_move) {
+      return Err(format!("{}: {}", token, err));
+    }
+    events.push(GameEvent::Moved(player_move));
+  }
+
+  if !is_last && game.winner.is_none() && game.current_player == color {
+    return Err(String::from("the turn isn't over"));
+  }
+  Ok(())
+}
+
+// ----------------[ tests ]-----------------
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn logged(events: &[GameEvent]) -> Vec<LoggedEvent> {
+    events
+      .iter()
+      .enumerate()
+      .map(|(sequence, event)| LoggedEvent {
+        sequence: sequence as u64,
+        timestamp: 0,
+        event: event.clone(),
+      })
+      .collect()
+  }
+
+  // players take the first legal move, the rolls come from a fixed sequence
+  fn played_game(size: usize) -> Vec<GameEvent> {
+    let rules = RuleSet::default();
+    let players = Color::ordered()
+      .into_iter()
+      .map(|color| {
+        let name = format!("\"{}\" player", color);
+        Player::new(color.to_string(), name, color, color == Color::Red, &rules)
+      })
+      .collect::<Vec<_>>();
+    let mut events = vec![GameEvent::Created {
+      rules,
+      dice_seed: 11,
+      join_code: None,
+    }];
+    events.extend(
+      players
+        .iter()
+        .filter(|player| !player.is_bot)
+        .map(|player| GameEvent::Joined(player.clone())),
+    );
+    events.push(GameEvent::Started(players));
+
+    let mut game = Game::from_events(&events);
+    let mut random = 11_u64;
+    while events.len() < size && game.winner.is_none() {
+      let event = match game.round_phase {
+        RoundPhase::Rolling if game.rules.is_forfeit(&game.dice_throws) => GameEvent::Skipped,
+        RoundPhase::Rolling => {
+          random = random
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+          GameEvent::Rolled((random >> 33) as usize % 6 + 1)
+        }
+        RoundPhase::Moving => game
+          .pending_moves
+          .iter()
+          .flat_map(|roll| game.legal_moves(*roll))
+          .next()
+          .map(GameEvent::Moved)
+          .unwrap_or(GameEvent::Skipped),
+      };
+      game.apply_event(&event);
+      events.push(event);
+    }
+    events
+  }
+
+  #[test]
+  fn export_import_round_trip() {
+    let events = played_game(700);
+    let text = export_game(&logged(&events)).unwrap();
+    assert!(text.contains("[Red \"\\\"Red\\\" player\" bot]"));
+    assert!(text.contains("1. G "));
+
+    let imported = import_game(&text).unwrap();
+    assert_eq!(imported.len(), events.len());
+    assert_eq!(export_game(&logged(&imported)).unwrap(), text);
+
+    let game = Game::from_events(&events);
+    let imported_game = Game::from_events(&imported);
+    assert_eq!(imported_game.fields, game.fields);
+    assert_eq!(imported_game.current_player, game.current_player);
+    assert_eq!(imported_game.dice_rolls, game.dice_rolls);
+  }
+
+  #[test]
+  fn taken_back_moves_are_not_exported() {
+    let mut events = played_game(300);
+    let moved = events
+      .iter()
+      .rposition(|event| matches!(event, GameEvent::Moved(_)))
+      .unwrap();
+    let text = export_game(&logged(&events[..moved])).unwrap();
+    events.push(GameEvent::Undone {
+      events: moved as u64,
+    });
+    assert_eq!(export_game(&logged(&events)).unwrap(), text);
+  }
+
+  #[test]
+  fn game_in_lobby_is_not_exported() {
+    let events = played_game(0);
+    let lobby = &events[..events.len() - 1];
+    assert_eq!(export_game(&logged(lobby)), None);
+  }
+
+  #[test]
+  fn corrupted_turn_is_reported() {
+    let text = export_game(&logged(&played_game(700))).unwrap();
+    let lines = text.lines().collect::<Vec<_>>();
+    let header_size = lines.iter().position(|line| line.is_empty()).unwrap() + 1;
+
+    // the first turn with a move gets one that is never legal
+    let index = lines
+      .iter()
+      .position(|line| {
+        line
+          .split_once(" : ")
+          .is_some_and(|(_, moves)| moves != "-")
+      })
+      .unwrap();
+    let corrupted = format!("{} : 99-100", lines[index].split_once(" : ").unwrap().0);
+    let mut corrupted_lines = lines.clone();
+    corrupted_lines[index] = &corrupted;
+    let err = import_game(&corrupted_lines.join("\n")).unwrap_err();
+    assert_eq!(err.turn, Some(index - header_size + 1));
+    assert_eq!(err.message, "99-100 is not a legal move");
+
+    let mut reordered = lines.clone();
+    reordered.swap(header_size, header_size + 1);
+    assert_eq!(
+      import_game(&reordered.join("\n")).unwrap_err().turn,
+      Some(1)
+    );
+  }
+
+  #[test]
+  fn invalid_header_is_rejected() {
+    let text = export_game(&logged(&played_game(20))).unwrap();
+    let without_red = text
+      .lines()
+      .filter(|line| !line.starts_with("[Red"))
+      .collect::<Vec<_>>()
+      .join("\n");
+    let err = import_game(&without_red).unwrap_err();
+    assert_eq!(err.turn, None);
+    assert_eq!(err.message, "Red needs exactly one player");
+
+    let err = import_game(&text.replace("[Seed 11]", "[Seed -1]")).unwrap_err();
+    assert_eq!(err.turn, None);
+  }
+}
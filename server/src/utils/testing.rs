@@ -0,0 +1,215 @@
+use actix::Actor;
+use actix_http::Request;
+use actix_web::{dev::Service, dev::ServiceResponse, test, web, App, Error, HttpServer};
+use futures::{SinkExt, StreamExt};
+use std::{sync::Arc, time::Duration};
+use tokio::{net::TcpStream, time::timeout};
+use tokio_tungstenite::{
+  connect_async,
+  tungstenite::{client::IntoClientRequest, handshake::client::Request as WsRequest, Message},
+  MaybeTlsStream, WebSocketStream,
+};
+
+use ludo_core::models::{
+  color::Color,
+  game::Game,
+  lobby::JoinResponse,
+  messages::{ClientMessage, SequencedMessage, ServerMessage},
+};
+
+use crate::components::game::database::{memory::InMemoryGameRepository, Database};
+use crate::components::game::routes::attach_routes;
+use crate::components::game_server::actor::GameServer;
+use crate::components::session::actor::Heartbeat;
+use crate::models::app_data::AppData;
+use crate::utils::access::TokenSigner;
+use crate::utils::dice::{Dice, SeededDice};
+
+// the fixture the tests of the routes and the game server share
+
+pub type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+pub fn app_data() -> web::Data<AppData> {
+  app_data_with_dice(Arc::new(SeededDice))
+}
+
+pub fn app_data_with_dice(dice: Dice) -> web::Data<AppData> {
+  let db: Database = Arc::new(InMemoryGameRepository::new());
+  let game_server = GameServer::new(db.clone(), dice);
+  app_data_with(db, game_server, Heartbeat::default())
+}
+
+pub fn app_data_with(
+  db: Database,
+  game_server: GameServer,
+  heartbeat: Heartbeat,
+) -> web::Data<AppData> {
+  let room_locks = game_server.room_locks();
+  web::Data::new(AppData {
+    game_server_addr: game_server.start(),
+    db,
+    heartbeat,
+    tokens: TokenSigner::new(b"secret"),
+    room_locks,
+  })
+}
+
+// the routes as a service the requests of a test are called on
+pub async fn app(
+  app_data: web::Data<AppData>,
+) -> impl Service<Request, Response = ServiceResponse, Error = Error> {
+  test::init_service(App::new().app_data(app_data).configure(attach_routes)).await
+}
+
+// runs the routes on a random port, returns the address
+pub fn spawn_server(app_data: web::Data<AppData>) -> String {
+  let server = HttpServer::new(move || {
+    App::new()
+      .app_data(app_data.clone())
+      .configure(attach_routes)
+  })
+  .workers(1)
+  .bind("127.0.0.1:0")
+  .unwrap();
+  let address = server.addrs()[0];
+  actix_web::rt::spawn(server.run());
+  address.to_string()
+}
+
+// creates a game with the body, returns its id
+pub async fn create_game(
+  app: &impl Service<Request, Response = ServiceResponse, Error = Error>,
+  body: serde_json::Value,
+) -> String {
+  let request = test::TestRequest::post()
+    .uri("/games")
+    .set_json(body)
+    .to_request();
+  String::from_utf8(test::call_and_read_body(app, request).await.to_vec()).unwrap()
+}
+
+pub async fn join_game(
+  app: &impl Service<Request, Response = ServiceResponse, Error = Error>,
+  room: &str,
+  name: &str,
+) -> JoinResponse {
+  let request = test::TestRequest::put()
+    .uri(&format!("/games/{}", room))
+    .set_json(serde_json::json!({ "name": name }))
+    .to_request();
+  test::call_and_read_body_json(app, request).await
+}
+
+pub async fn next_message(socket: &mut Socket) -> ServerMessage {
+  next_sequenced(socket).await.message
+}
+
+pub async fn next_sequenced(socket: &mut Socket) -> SequencedMessage {
+  loop {
+    let frame = timeout(Duration::from_secs(5), socket.next())
+      .await
+      .expect("no message from the server")
+      .unwrap()
+      .unwrap();
+    if let Message::Text(text) = frame {
+      return serde_json::from_str(&text).unwrap();
+    }
+  }
+}
+
+pub async fn send_message(socket: &mut Socket, message: ClientMessage) {
+  let text = serde_json::to_string(&message).unwrap();
+  socket.send(Message::Text(text)).await.unwrap();
+}
+
+// skips the messages until one the test is interested in arrives
+pub async fn wait_for(
+  socket: &mut Socket,
+  matches: impl Fn(&ServerMessage) -> bool,
+) -> ServerMessage {
+  loop {
+    let message = next_message(socket).await;
+    if matches(&message) {
+      return message;
+    }
+  }
+}
+
+pub async fn next_error(socket: &mut Socket) -> String {
+  match wait_for(socket, |message| matches!(message, ServerMessage::Error(_))).await {
+    ServerMessage::Error(error) => error,
+    _ => unreachable!(),
+  }
+}
+
+// creates a game with the body and connects every one of the players to the server at the address
+pub async fn connect_players(
+  app_data: web::Data<AppData>,
+  address: &str,
+  body: serde_json::Value,
+  names: &[&str],
+) -> (String, Vec<Socket>) {
+  let app = app(app_data).await;
+  let room = create_game(&app, body).await;
+
+  let mut sockets = vec![];
+  for name in names {
+    let joined = join_game(&app, &room, name).await;
+    let (mut socket, _) = connect(address, &room, &joined.token).await;
+    wait_for(&mut socket, |message| {
+      matches!(message, ServerMessage::LobbyState(_))
+    })
+    .await;
+    sockets.push(socket);
+  }
+  (room, sockets)
+}
+
+pub fn ready_count(count: usize) -> impl Fn(&ServerMessage) -> bool {
+  move |message| match message {
+    ServerMessage::LobbyState(roster) => {
+      roster.iter().filter(|player| player.ready).count() == count
+    }
+    _ => false,
+  }
+}
+
+// every player gets ready one after another, once it returns every socket saw the last roster
+pub async fn ready_up(sockets: &mut [Socket]) {
+  for (index, socket) in sockets.iter_mut().enumerate() {
+    send_message(socket, ClientMessage::Ready(true)).await;
+    wait_for(socket, ready_count(index + 1)).await;
+  }
+  let count = sockets.len();
+  for socket in sockets[..count - 1].iter_mut() {
+    wait_for(socket, ready_count(count)).await;
+  }
+}
+
+// the request opening the websocket of the room with the token in the header
+pub fn websocket_request(address: &str, room: &str, token: &str) -> WsRequest {
+  let url = format!("ws://{}/games/websocket/{}", address, room);
+  let mut request = url.into_client_request().unwrap();
+  let bearer = format!("Bearer {}", token).parse().unwrap();
+  request.headers_mut().insert("Authorization", bearer);
+  request
+}
+
+pub fn token(app_data: &AppData, room: &str, player_id: &str) -> String {
+  let now = chrono::Utc::now().timestamp();
+  app_data.tokens.issue(room, player_id, now)
+}
+
+// opens the websocket of the player, returns it with the color and the game it was answered with
+pub async fn connect(address: &str, room: &str, token: &str) -> (Socket, (Game, Color)) {
+  let request = websocket_request(address, room, token);
+  let (mut socket, _) = connect_async(request).await.unwrap();
+  match wait_for(&mut socket, |message| {
+    matches!(message, ServerMessage::ConnectResponse(..))
+  })
+  .await
+  {
+    ServerMessage::ConnectResponse(game, color) => (socket, (game, color)),
+    _ => unreachable!(),
+  }
+}